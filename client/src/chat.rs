@@ -0,0 +1,134 @@
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::sse::{self, HEARTBEAT_TIMEOUT};
+use crate::transport::Transport;
+use crate::StreamChunk;
+
+/// Wait before sending a chat request again when the connection dropped before an answer
+const SEND_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Stream the reply to a chat request into `on_chunk` until it's done or `transport` is
+/// cancelled; no chunk is delivered after that. A request the network dropped is sent once
+/// more, unchanged, so its `idempotency_key` lets the backend skip it if the first one got
+/// through. A body that ends without `done` is not an error here.
+pub async fn send_message<T: Transport>(
+    transport: &T,
+    request: &impl Serialize,
+    request_id: &str,
+    mut on_chunk: impl FnMut(StreamChunk),
+) -> Result<(), String> {
+    let body = serde_json::to_string(request).map_err(|e| e.to_string())?;
+    let headers = [("X-Request-Id", request_id)];
+    let mut response = match transport.post("/chat", &body, &headers).await {
+        Err(e) if !e.starts_with("HTTP ") && !transport.cancelled() => {
+            log::warn!(target: "transport", "Chat request failed ({e}), retrying");
+            transport.sleep(SEND_RETRY_DELAY).await;
+            transport.post("/chat", &body, &headers).await?
+        }
+        response => response?,
+    };
+
+    sse::read_events(transport, &mut response, HEARTBEAT_TIMEOUT, |chunk: StreamChunk| {
+        let is_done = matches!(chunk, StreamChunk::Done);
+        on_chunk(chunk);
+        !is_done
+    })
+    .await
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use std::cell::{Cell, RefCell};
+    use std::collections::VecDeque;
+    use std::future::Future;
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+    use std::time::Duration;
+
+    use serde_json::{json, Value};
+
+    use super::send_message;
+    use crate::transport::{Read, Transport};
+
+    /// Answers each POST with the next of `responses`: the pieces of a body, or an error.
+    struct Fake {
+        responses: RefCell<VecDeque<Result<Vec<&'static str>, &'static str>>>,
+        posts: Cell<usize>,
+    }
+
+    impl Fake {
+        fn new(responses: Vec<Result<Vec<&'static str>, &'static str>>) -> Self {
+            Fake { responses: RefCell::new(responses.into()), posts: Cell::new(0) }
+        }
+    }
+
+    impl Transport for Fake {
+        type Body = VecDeque<&'static str>;
+
+        async fn post(&self, _: &str, _: &str, _: &[(&str, &str)]) -> Result<Self::Body, String> {
+            self.posts.set(self.posts.get() + 1);
+            let response = self.responses.borrow_mut().pop_front().expect("an unexpected POST");
+            response.map(VecDeque::from).map_err(String::from)
+        }
+
+        async fn read(&self, body: &mut Self::Body, _: Option<Duration>) -> Result<Read, String> {
+            Ok(body.pop_front().map_or(Read::End, |piece| Read::Bytes(piece.as_bytes().to_vec())))
+        }
+
+        async fn sleep(&self, _: Duration) {}
+
+        fn now(&self) -> f64 {
+            0.0
+        }
+    }
+
+    /// Run a future that never waits, as everything `Fake` does is ready at once.
+    fn ready<T>(future: impl Future<Output = T>) -> T {
+        match pin!(future).poll(&mut Context::from_waker(Waker::noop())) {
+            Poll::Ready(output) => output,
+            Poll::Pending => panic!("the future waited"),
+        }
+    }
+
+    fn chat(transport: &Fake) -> (Result<(), String>, Vec<Value>) {
+        let mut chunks = Vec::new();
+        let result = ready(send_message(transport, &json!({ "message": "hi" }), "test", |chunk| {
+            chunks.push(serde_json::to_value(&chunk).unwrap());
+        }));
+        (result, chunks)
+    }
+
+    #[test]
+    fn a_dropped_request_is_sent_once_more() {
+        let transport = Fake::new(vec![
+            Err("network error"),
+            Ok(vec!["data: {\"type\":\"text\",\"content\":\"hi\"}\n\ndata: {\"type\":\"done\"}\n\n"]),
+        ]);
+        let (result, chunks) = chat(&transport);
+        assert_eq!(result, Ok(()));
+        assert_eq!(chunks, [json!({ "type": "text", "content": "hi" }), json!({ "type": "done" })]);
+        assert_eq!(transport.posts.get(), 2);
+    }
+
+    #[test]
+    fn a_refused_request_is_not_retried() {
+        let transport = Fake::new(vec![Err("HTTP 429")]);
+        let (result, chunks) = chat(&transport);
+        assert_eq!(result, Err("HTTP 429".to_string()));
+        assert!(chunks.is_empty());
+        assert_eq!(transport.posts.get(), 1);
+    }
+
+    #[test]
+    fn reading_stops_at_done() {
+        let transport = Fake::new(vec![Ok(vec![
+            "data: {\"type\":\"done\"}\n\n",
+            "data: {\"type\":\"text\",\"content\":\"late\"}\n\n",
+        ])]);
+        let (result, chunks) = chat(&transport);
+        assert_eq!(result, Ok(()));
+        assert_eq!(chunks, [json!({ "type": "done" })]);
+    }
+}
@@ -0,0 +1,14 @@
+pub mod chat;
+pub mod sse;
+mod transport;
+mod types;
+#[cfg(feature = "web")]
+pub mod web;
+
+pub use transport::{Read, Transport};
+pub use types::{
+    ChartParams, ChartRef, ChatRequest, Indicator, Message, PortfolioSummary, PositionSummary, Role,
+    StreamChunk,
+};
+
+pub const API_URL: &str = "https://api.wxve.io";
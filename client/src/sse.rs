@@ -0,0 +1,219 @@
+use std::fmt::Debug;
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+
+use crate::transport::{Read, Transport};
+
+/// A stream whose server sends heartbeats (`: ping`) is given up as dead after this long
+/// without a line, and reconnected like any dropped stream
+pub const HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(45);
+
+/// Splits a server-sent event body into lines as it arrives. Reads can end anywhere,
+/// including inside a multi-byte character, so bytes are buffered until a newline
+/// completes the line; `\n` never occurs inside a UTF-8 sequence.
+#[derive(Default)]
+pub struct LineDecoder {
+    buffer: Vec<u8>,
+}
+
+impl LineDecoder {
+    /// Add the next read and return every line it completes, trimmed. A partial last
+    /// line waits for the next read; one never completed is dropped.
+    pub fn push(&mut self, bytes: &[u8]) -> Vec<String> {
+        self.buffer.extend_from_slice(bytes);
+        let mut lines = Vec::new();
+        let mut start = 0;
+        for (i, &byte) in self.buffer.iter().enumerate() {
+            if byte == b'\n' {
+                lines.push(String::from_utf8_lossy(&self.buffer[start..i]).trim().to_string());
+                start = i + 1;
+            }
+        }
+        self.buffer.drain(..start);
+        lines
+    }
+}
+
+/// The payload of a `data:` line; comments, `event:` and blank lines have none.
+pub fn data(line: &str) -> Option<&str> {
+    line.strip_prefix("data: ")
+}
+
+/// Time since a stream last sent anything, for servers that keep it alive with comment
+/// lines (`: ping`). Only a stream that has sent a heartbeat is expected to keep doing so;
+/// one that never has may legitimately stay quiet while the model thinks.
+pub struct Heartbeat {
+    timeout_ms: f64,
+    last_line_at: f64,
+    beating: bool,
+}
+
+impl Heartbeat {
+    /// A stream opened at `now` (milliseconds), stalled once quiet for `timeout_ms`
+    /// after its first heartbeat.
+    pub fn new(timeout_ms: f64, now: f64) -> Self {
+        Heartbeat { timeout_ms, last_line_at: now, beating: false }
+    }
+
+    /// Any line counts as a sign of life; a comment also shows the server sends heartbeats.
+    pub fn line(&mut self, line: &str, now: f64) {
+        self.last_line_at = now;
+        self.beating |= line.starts_with(':');
+    }
+
+    /// How long the next read may wait before the stream counts as stalled, or `None`
+    /// while no heartbeat has been seen.
+    pub fn remaining(&self, now: f64) -> Option<f64> {
+        self.beating.then(|| (self.last_line_at + self.timeout_ms - now).max(0.0))
+    }
+}
+
+/// Parse each `data:` payload of `body` as `E` and feed it to `on_event` until it returns
+/// false, the body ends or `transport` is cancelled. Payloads that don't parse are logged
+/// and skipped. Once the server has sent a heartbeat, a body quiet for `stall_after` fails,
+/// so the caller can reconnect rather than wait for the connection to time out.
+pub async fn read_events<T: Transport, E: DeserializeOwned + Debug>(
+    transport: &T,
+    body: &mut T::Body,
+    stall_after: Duration,
+    mut on_event: impl FnMut(E) -> bool,
+) -> Result<(), String> {
+    let mut decoder = LineDecoder::default();
+    let mut heartbeat = Heartbeat::new(stall_after.as_millis() as f64, transport.now());
+
+    loop {
+        let timeout = heartbeat
+            .remaining(transport.now())
+            .map(|ms| Duration::from_secs_f64(ms / 1000.0));
+        let bytes = match transport.read(body, timeout).await? {
+            Read::Bytes(bytes) => bytes,
+            Read::End => return Ok(()),
+            Read::TimedOut => return Err(format!("No heartbeat for {}s", stall_after.as_secs())),
+        };
+
+        for line in decoder.push(&bytes) {
+            log::trace!(target: "transport", "SSE line: {line}");
+            heartbeat.line(&line, transport.now());
+            transport.line(&line);
+            let Some(payload) = data(&line) else {
+                continue;
+            };
+            match serde_json::from_str::<E>(payload) {
+                Ok(event) => {
+                    if transport.cancelled() {
+                        return Ok(());
+                    }
+                    transport.parsed(&event);
+                    if !on_event(event) {
+                        return Ok(());
+                    }
+                }
+                Err(e) => {
+                    let kind = std::any::type_name::<E>().rsplit("::").next().unwrap_or_default();
+                    log::warn!(target: "parser", "Skipping {kind} event ({e}): {payload}");
+                    transport.skipped(&e.to_string());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use proptest::prelude::*;
+    use serde_json::{json, Value};
+
+    use super::{data, Heartbeat, LineDecoder};
+    use crate::StreamChunk;
+
+    /// Feed `body` to a decoder in pieces cut at `cuts` and return the `data:` payloads.
+    fn payloads(body: &[u8], cuts: &[usize]) -> Vec<String> {
+        let mut cuts: Vec<usize> = cuts.iter().map(|cut| cut % (body.len() + 1)).collect();
+        cuts.sort_unstable();
+        cuts.push(body.len());
+        let mut decoder = LineDecoder::default();
+        let mut start = 0;
+        let mut lines = Vec::new();
+        for cut in cuts {
+            lines.extend(decoder.push(&body[start..cut]));
+            start = cut;
+        }
+        lines.iter().filter_map(|line| data(line).map(String::from)).collect()
+    }
+
+    fn chunk() -> impl Strategy<Value = Value> {
+        prop_oneof![
+            any::<String>().prop_map(|content| json!({ "type": "text", "content": content })),
+            any::<String>().prop_map(|name| json!({ "type": "tool_start", "name": name })),
+            any::<String>().prop_map(|message| json!({ "type": "error", "message": message })),
+            // Quarters survive a JSON round trip exactly
+            (-4..=4i32).prop_map(|n| json!({ "type": "sentiment", "score": f64::from(n) / 4.0 })),
+            Just(json!({ "type": "done" })),
+        ]
+    }
+
+    #[test]
+    fn partial_line_waits_for_newline() {
+        let mut decoder = LineDecoder::default();
+        assert!(decoder.push(b"data: {\"type\"").is_empty());
+        assert_eq!(decoder.push(b":\"done\"}\n\n"), ["data: {\"type\":\"done\"}", ""]);
+    }
+
+    #[test]
+    fn character_split_across_reads() {
+        let body = "data: café\n".as_bytes();
+        let split = body.len() - 2;
+        let mut decoder = LineDecoder::default();
+        assert!(decoder.push(&body[..split]).is_empty());
+        assert_eq!(decoder.push(&body[split..]), ["data: café"]);
+    }
+
+    #[test]
+    fn crlf_line_endings() {
+        let mut decoder = LineDecoder::default();
+        assert_eq!(decoder.push(b": ping\r\ndata: 1\r\n\r\n"), [": ping", "data: 1", ""]);
+    }
+
+    #[test]
+    fn quiet_stream_without_heartbeats_never_stalls() {
+        let mut heartbeat = Heartbeat::new(30_000.0, 0.0);
+        heartbeat.line("data: 1", 1_000.0);
+        assert_eq!(heartbeat.remaining(600_000.0), None);
+    }
+
+    #[test]
+    fn heartbeat_arms_the_timeout_and_any_line_resets_it() {
+        let mut heartbeat = Heartbeat::new(30_000.0, 0.0);
+        heartbeat.line(": ping", 1_000.0);
+        assert_eq!(heartbeat.remaining(11_000.0), Some(20_000.0));
+        heartbeat.line("data: 1", 20_000.0);
+        assert_eq!(heartbeat.remaining(20_000.0), Some(30_000.0));
+        assert_eq!(heartbeat.remaining(90_000.0), Some(0.0));
+    }
+
+    proptest! {
+        #[test]
+        fn split_reads_yield_the_same_chunks(
+            chunks in prop::collection::vec(chunk(), 0..16),
+            cuts in prop::collection::vec(any::<usize>(), 0..32),
+        ) {
+            let body: String = chunks
+                .iter()
+                .map(|chunk| format!(": comment\nevent: message\ndata: {chunk}\n\n"))
+                .collect();
+            let whole = payloads(body.as_bytes(), &[]);
+            let split = payloads(body.as_bytes(), &cuts);
+            prop_assert_eq!(&split, &whole);
+
+            let parsed: Vec<Value> = split
+                .iter()
+                .map(|payload| {
+                    let chunk: StreamChunk = serde_json::from_str(payload).unwrap();
+                    serde_json::to_value(chunk).unwrap()
+                })
+                .collect();
+            prop_assert_eq!(parsed, chunks);
+        }
+    }
+}
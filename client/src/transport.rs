@@ -0,0 +1,54 @@
+use std::fmt::Debug;
+use std::future::Future;
+use std::time::Duration;
+
+/// What reading a response body gave
+pub enum Read {
+    Bytes(Vec<u8>),
+    /// The body ended
+    End,
+    /// Nothing arrived within the time the read was given; the body has been cancelled
+    TimedOut,
+}
+
+/// How the client reaches the backend: `web::WebTransport` in a browser, or one over
+/// whatever HTTP client a native app or TUI has. A status the backend answered with fails
+/// as `"HTTP <status>"`; any other error means the request may never have arrived.
+pub trait Transport {
+    /// A response body being read
+    type Body;
+
+    /// POST the JSON `body` to `path` with `headers` added.
+    fn post(
+        &self,
+        path: &str,
+        body: &str,
+        headers: &[(&str, &str)],
+    ) -> impl Future<Output = Result<Self::Body, String>>;
+
+    /// The next bytes of `body`, giving up after `timeout` if one is given.
+    fn read(
+        &self,
+        body: &mut Self::Body,
+        timeout: Option<Duration>,
+    ) -> impl Future<Output = Result<Read, String>>;
+
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()>;
+
+    /// Milliseconds since the epoch
+    fn now(&self) -> f64;
+
+    /// Whether the caller has stopped the request; nothing is delivered or retried after.
+    fn cancelled(&self) -> bool {
+        false
+    }
+
+    /// Each line of a streamed body, as it's read. For diagnostics, like the two below.
+    fn line(&self, _line: &str) {}
+
+    /// Each `data:` payload that parsed.
+    fn parsed(&self, _event: &dyn Debug) {}
+
+    /// Each `data:` payload that didn't parse, and why.
+    fn skipped(&self, _error: &str) {}
+}
@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    User,
+    Assistant,
+}
+
+/// Chart overlay, requested per turn in `ChatRequest.indicators` or per chart on `/chart`
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Indicator {
+    Rsi,
+    Macd,
+    Ma,
+}
+
+impl Indicator {
+    pub const ALL: [Indicator; 3] = [Indicator::Rsi, Indicator::Macd, Indicator::Ma];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Indicator::Rsi => "RSI",
+            Indicator::Macd => "MACD",
+            Indicator::Ma => "MA",
+        }
+    }
+}
+
+/// What the chart tool was called with besides the symbol, echoed on `chart` chunks and
+/// accepted by `/chart` to draw it again differently. Unset fields are the backend's defaults.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChartParams {
+    /// Bar size, e.g. `1d` or `4h`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeframe: Option<String>,
+    /// How many bars back the wave count starts
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lookback: Option<u32>,
+    /// Elliott wave degree counted, e.g. `minor` or `primary`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub degree: Option<String>,
+}
+
+/// A chart shown with an earlier turn, so a follow-up can refer to "that chart"
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ChartRef {
+    pub symbol: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeframe: Option<String>,
+    /// When it was drawn, in milliseconds since the epoch
+    pub drawn_at: f64,
+}
+
+/// One earlier turn as sent in `ChatRequest.history`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Message {
+    pub role: Role,
+    pub content: String,
+    /// Excerpt of an earlier assistant message this user message replies to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quoted: Option<String>,
+    /// Charts the assistant showed with this turn
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub charts: Vec<ChartRef>,
+}
+
+/// Body of `POST /chat`
+#[derive(Clone, Debug, Serialize)]
+pub struct ChatRequest {
+    pub message: String,
+    /// The turns before `message`; `None` continues the history the backend holds for
+    /// `conversation_id`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub history: Option<Vec<Message>>,
+    /// Lets the backend keep this conversation's history, so later turns can leave it out
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conversation_id: Option<String>,
+    pub indicators: Vec<Indicator>,
+    /// Portfolio snapshot the user attached to this conversation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub portfolio: Option<PortfolioSummary>,
+    /// Excerpt of an assistant message that `message` replies to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quoted: Option<String>,
+    /// Sampling temperature override, set by "Regenerate and compare"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    /// Names of the tools the model may call; `None` allows every tool
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<String>>,
+    /// Hold each tool call as a `tool_request` chunk until the user approves or denies it
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub approve_tools: bool,
+    /// The start of an answer to `message` that was cut off, for the backend to carry on from
+    /// rather than start over
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partial: Option<String>,
+    /// New for each send and kept when the same send is retried, so the backend can tell a
+    /// retry from a second message
+    pub idempotency_key: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PositionSummary {
+    pub symbol: String,
+    pub quantity: f64,
+    pub cost_basis: f64,
+    pub price: Option<f64>,
+    pub market_value: Option<f64>,
+    pub unrealized_pnl: Option<f64>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PortfolioSummary {
+    pub positions: Vec<PositionSummary>,
+    pub total_cost: f64,
+    pub total_value: f64,
+    pub total_unrealized_pnl: f64,
+}
+
+/// One `data:` event of the `/chat` response stream
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StreamChunk {
+    Text { content: String },
+    ToolStart { name: String },
+    ToolEnd { name: String },
+    /// A tool call held for the user's decision (see `ChatRequest.approve_tools`), sent
+    /// back on `POST /tools/approval`; `input` describes what it would run with
+    ToolRequest {
+        id: String,
+        name: String,
+        #[serde(default)]
+        input: Option<String>,
+    },
+    Chart {
+        symbol: String,
+        html: String,
+        #[serde(default)]
+        params: ChartParams,
+    },
+    /// Changes to a chart already shown for `symbol` (new candles, moved wave labels),
+    /// handed to the chart's page to apply instead of sending its HTML again
+    ChartUpdate { symbol: String, patch: serde_json::Value },
+    Alert { symbol: String, price: f64 },
+    Sentiment { score: f64 },
+    /// Which model answered; `request_id` replaces the client-generated one when present
+    Meta {
+        model: String,
+        #[serde(default)]
+        request_id: Option<String>,
+    },
+    Done,
+    Error { message: String },
+}
@@ -0,0 +1,254 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    AbortSignal, Blob, Headers, ReadableStream, ReadableStreamDefaultReader, ReadableWritablePair,
+    Request, RequestInit, RequestMode, Response, WritableStream,
+};
+
+use crate::transport::{Read, Transport};
+
+/// Fetch `url` with `headers` added, failing on network errors and non-2xx statuses.
+pub async fn fetch(
+    url: &str,
+    opts: &RequestInit,
+    headers: &[(&str, &str)],
+) -> Result<Response, String> {
+    let window = web_sys::window().ok_or("no window")?;
+
+    opts.set_mode(RequestMode::Cors);
+    let request = Request::new_with_str_and_init(url, opts).map_err(|e| format!("{e:?}"))?;
+    for (name, value) in headers {
+        request.headers().set(name, value).map_err(|e| format!("{e:?}"))?;
+    }
+
+    let resp_value = JsFuture::from(window.fetch_with_request(&request))
+        .await
+        .map_err(|e| format!("{e:?}"))?;
+    let response: Response = resp_value.dyn_into().map_err(|e| format!("{e:?}"))?;
+
+    log::debug!(target: "transport", "{} {url} -> {}", request.method(), response.status());
+    if !response.ok() {
+        return Err(format!("HTTP {}", response.status()));
+    }
+
+    Ok(response)
+}
+
+/// JSON bodies shorter than this are sent as they are by `post_json_encoded`; compressing
+/// them saves less than the header costs.
+pub const COMPRESS_MIN_BYTES: usize = 8 * 1024;
+
+/// A `Content-Encoding` the browser can compress a request body with
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Encoding {
+    Gzip,
+    Deflate,
+}
+
+impl Encoding {
+    /// The header value, which is also the `/config` name
+    pub fn name(self) -> &'static str {
+        match self {
+            Encoding::Gzip => "gzip",
+            Encoding::Deflate => "deflate",
+        }
+    }
+}
+
+/// Options for POSTing `body` as JSON, with `extra_headers` alongside the content type.
+pub fn post_json(body: &impl Serialize, extra_headers: &[(&str, &str)]) -> Result<RequestInit, String> {
+    let body_json = serde_json::to_string(body).map_err(|e| e.to_string())?;
+    post_json_str(&body_json, extra_headers)
+}
+
+/// Like `post_json`, for a body that's already JSON.
+pub fn post_json_str(body_json: &str, extra_headers: &[(&str, &str)]) -> Result<RequestInit, String> {
+    post(&wasm_bindgen::JsValue::from_str(body_json), extra_headers)
+}
+
+/// Like `post_json_str`, but a body of `COMPRESS_MIN_BYTES` or more is compressed with
+/// `encoding` and labelled with `Content-Encoding`. Only for endpoints that said they accept it.
+pub async fn post_json_encoded(
+    body_json: &str,
+    extra_headers: &[(&str, &str)],
+    encoding: Encoding,
+) -> Result<RequestInit, String> {
+    if body_json.len() < COMPRESS_MIN_BYTES {
+        return post_json_str(body_json, extra_headers);
+    }
+
+    let compressed = compress(body_json, encoding).await?;
+    log::debug!(
+        target: "transport",
+        "{} request body: {} -> {} bytes",
+        encoding.name(),
+        body_json.len(),
+        compressed.length()
+    );
+    let mut headers = extra_headers.to_vec();
+    headers.push(("Content-Encoding", encoding.name()));
+    post(&compressed, &headers)
+}
+
+fn post(body: &wasm_bindgen::JsValue, extra_headers: &[(&str, &str)]) -> Result<RequestInit, String> {
+    let headers = Headers::new().map_err(|e| format!("{e:?}"))?;
+    headers
+        .set("Content-Type", "application/json")
+        .map_err(|e| format!("{e:?}"))?;
+    for (name, value) in extra_headers {
+        headers.set(name, value).map_err(|e| format!("{e:?}"))?;
+    }
+
+    let opts = RequestInit::new();
+    opts.set_method("POST");
+    opts.set_headers(&headers);
+    opts.set_body(body);
+    Ok(opts)
+}
+
+/// Run `text` through the browser's `CompressionStream`, buffering the result: a streamed
+/// request body would need `duplex: "half"` and HTTP/2 all the way to the backend. Fails
+/// where the browser has no `CompressionStream`.
+async fn compress(text: &str, encoding: Encoding) -> Result<js_sys::Uint8Array, String> {
+    // web-sys only binds CompressionStream behind `web_sys_unstable_apis`, so it's looked up
+    let constructor = js_sys::Reflect::get(&js_sys::global(), &"CompressionStream".into())
+        .map_err(|e| format!("{e:?}"))?
+        .dyn_into::<js_sys::Function>()
+        .map_err(|_| "CompressionStream not supported")?;
+    let args = js_sys::Array::of1(&encoding.name().into());
+    let compressor = js_sys::Reflect::construct(&constructor, &args).map_err(|e| format!("{e:?}"))?;
+    let readable: ReadableStream = js_sys::Reflect::get(&compressor, &"readable".into())
+        .map_err(|e| format!("{e:?}"))?
+        .unchecked_into();
+    let writable: WritableStream = js_sys::Reflect::get(&compressor, &"writable".into())
+        .map_err(|e| format!("{e:?}"))?
+        .unchecked_into();
+    let input = Blob::new_with_str_sequence(&js_sys::Array::of1(&text.into()))
+        .map_err(|e| format!("{e:?}"))?;
+    let output = input.stream().pipe_through(&ReadableWritablePair::new(&readable, &writable));
+    let response =
+        Response::new_with_opt_readable_stream(Some(&output)).map_err(|e| format!("{e:?}"))?;
+    let buffer = JsFuture::from(response.array_buffer().map_err(|e| format!("{e:?}"))?)
+        .await
+        .map_err(|e| format!("{e:?}"))?;
+    Ok(js_sys::Uint8Array::new(&buffer))
+}
+
+pub async fn read_json<T: for<'de> Deserialize<'de>>(response: Response) -> Result<T, String> {
+    let text = JsFuture::from(response.text().map_err(|e| format!("{e:?}"))?)
+        .await
+        .map_err(|e| format!("{e:?}"))?;
+    serde_json::from_str(&text.as_string().unwrap_or_default()).map_err(|e| e.to_string())
+}
+
+/// A streamed response body being read
+pub struct Body(ReadableStreamDefaultReader);
+
+impl Body {
+    pub fn new(response: &Response) -> Result<Self, String> {
+        let body = response.body().ok_or("no body")?;
+        let reader = body
+            .get_reader()
+            .dyn_into::<ReadableStreamDefaultReader>()
+            .map_err(|e| format!("{e:?}"))?;
+        Ok(Body(reader))
+    }
+}
+
+/// The next bytes of `body`. One still waiting after `timeout` cancels the body.
+pub async fn read(body: &mut Body, timeout: Option<Duration>) -> Result<Read, String> {
+    let result = match timeout {
+        None => JsFuture::from(body.0.read()).await.map_err(|e| format!("{e:?}"))?,
+        Some(wait) => match within(body.0.read(), wait.as_millis() as f64).await? {
+            Some(result) => result,
+            None => {
+                let _ = body.0.cancel();
+                return Ok(Read::TimedOut);
+            }
+        },
+    };
+
+    let done = js_sys::Reflect::get(&result, &"done".into())
+        .map_err(|e| format!("{e:?}"))?
+        .as_bool()
+        .unwrap_or(true);
+    if done {
+        return Ok(Read::End);
+    }
+
+    let value = js_sys::Reflect::get(&result, &"value".into()).map_err(|e| format!("{e:?}"))?;
+    let array = js_sys::Uint8Array::new(&value);
+    let mut bytes = vec![0u8; array.length() as usize];
+    array.copy_to(&mut bytes);
+    Ok(Read::Bytes(bytes))
+}
+
+/// Resolves after `duration`.
+pub async fn sleep(duration: Duration) {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        if let Some(window) = web_sys::window() {
+            let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+                &resolve,
+                duration.as_millis() as i32,
+            );
+        }
+    });
+    let _ = JsFuture::from(promise).await;
+}
+
+/// Reaches the backend at `base_url` (such as `API_URL`) with `window.fetch`, adding
+/// `headers` to every request; aborting `signal` stops it.
+pub struct WebTransport {
+    pub base_url: String,
+    pub headers: Vec<(String, String)>,
+    pub signal: Option<AbortSignal>,
+}
+
+impl Transport for WebTransport {
+    type Body = Body;
+
+    async fn post(&self, path: &str, body: &str, headers: &[(&str, &str)]) -> Result<Body, String> {
+        let opts = post_json_str(body, headers)?;
+        opts.set_signal(self.signal.as_ref());
+        let headers: Vec<(&str, &str)> =
+            self.headers.iter().map(|(name, value)| (name.as_str(), value.as_str())).collect();
+        let response = fetch(&format!("{}{path}", self.base_url), &opts, &headers).await?;
+        Body::new(&response)
+    }
+
+    async fn read(&self, body: &mut Body, timeout: Option<Duration>) -> Result<Read, String> {
+        read(body, timeout).await
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        sleep(duration).await;
+    }
+
+    fn now(&self) -> f64 {
+        js_sys::Date::now()
+    }
+
+    fn cancelled(&self) -> bool {
+        self.signal.as_ref().is_some_and(AbortSignal::aborted)
+    }
+}
+
+/// What `promise` resolves to, or `None` if it takes longer than `ms`.
+async fn within(promise: js_sys::Promise, ms: f64) -> Result<Option<wasm_bindgen::JsValue>, String> {
+    let window = web_sys::window().ok_or("no window")?;
+    let mut timer = 0;
+    // Resolves with `undefined`, which a read never does
+    let timeout = js_sys::Promise::new(&mut |resolve, _| {
+        timer = window
+            .set_timeout_with_callback_and_timeout_and_arguments_0(&resolve, ms as i32)
+            .unwrap_or_default();
+    });
+    let result = JsFuture::from(js_sys::Promise::race(&js_sys::Array::of2(&promise, &timeout)))
+        .await
+        .map_err(|e| format!("{e:?}"))?;
+    window.clear_timeout_with_handle(timer);
+    Ok(Some(result).filter(|result| !result.is_undefined()))
+}
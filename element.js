@@ -0,0 +1,36 @@
+// Loads the chat bundle into another site and registers the <wxve-chat> element:
+//
+//   <script src="https://chat.wxve.io/element.js" defer></script>
+//   <wxve-chat symbol="AAPL" theme="dark" style="height: 640px"></wxve-chat>
+//
+// Attributes: `symbol` or `prompt` prefill the composer, `theme` is "light" or "dark",
+// `endpoint` points the chat at another API. The bundle's file names are hashed per
+// release, so they're read from the chat's index.html rather than hardcoded here.
+(function () {
+  "use strict";
+
+  const script = document.currentScript;
+  const base = new URL(".", script ? script.src : "https://chat.wxve.io/");
+
+  async function load() {
+    const response = await fetch(new URL("index.html", base));
+    const page = new DOMParser().parseFromString(await response.text(), "text/html");
+    const href = (selector) => {
+      const link = page.querySelector(selector);
+      return link && new URL(link.getAttribute("href"), base).toString();
+    };
+    const stylesheet = href('link[rel="stylesheet"]');
+    const module = href('link[rel="modulepreload"]');
+    const wasm = href('link[rel="preload"][type="application/wasm"]');
+    if (!module || !wasm) {
+      throw new Error("wxve-chat: bundle not found in index.html");
+    }
+
+    // Read by the bundle: its presence means render only <wxve-chat>, not the whole page
+    window.XveChatElement = { stylesheet };
+    const bindings = await import(module);
+    await bindings.default({ module_or_path: wasm });
+  }
+
+  load().catch((error) => console.error(error));
+})();
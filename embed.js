@@ -0,0 +1,123 @@
+// Embeds Xve on another site. Include this script, then:
+//
+//   const xve = XveChat.mount(document.getElementById("xve"), { theme: "dark" });
+//   xve.on("response", (event) => console.log(event.content));
+//   xve.send("What's the wave structure for AAPL?");
+//
+// Without a container the widget floats as a launcher button in the corner of the page.
+// Commands sent before the widget has loaded are queued until it announces `ready`.
+(function () {
+  "use strict";
+
+  const script = document.currentScript;
+  const base = new URL(".", script ? script.src : "https://chat.wxve.io/");
+
+  function mount(container, options) {
+    options = options || {};
+    const floating = !container;
+    const listeners = {};
+    const queue = [];
+    let ready = false;
+
+    const src = new URL(base);
+    src.searchParams.set("embed", "1");
+    src.searchParams.set("origin", window.location.origin);
+
+    const iframe = document.createElement("iframe");
+    iframe.src = src.toString();
+    iframe.title = "Xve chat";
+    iframe.allow = "clipboard-write; web-share";
+    iframe.style.cssText = "border: 0; width: 100%; height: 100%; background: transparent;";
+
+    let frame = iframe;
+    let launcher = null;
+    if (floating) {
+      frame = document.createElement("div");
+      frame.style.cssText =
+        "position: fixed; right: 1rem; bottom: 5rem; width: 380px; height: 600px;" +
+        "max-width: calc(100vw - 2rem); max-height: calc(100vh - 6rem); z-index: 2147483000;" +
+        "border-radius: 0.75rem; overflow: hidden; box-shadow: 0 8px 32px rgba(0, 0, 0, 0.2);" +
+        "display: none;";
+      frame.appendChild(iframe);
+      launcher = document.createElement("button");
+      launcher.type = "button";
+      launcher.title = "Ask Xve";
+      launcher.textContent = "X";
+      launcher.style.cssText =
+        "position: fixed; right: 1rem; bottom: 1rem; width: 3.25rem; height: 3.25rem;" +
+        "border: 0; border-radius: 50%; background: #111; color: #fff; font: 600 1.25rem sans-serif;" +
+        "cursor: pointer; z-index: 2147483000; box-shadow: 0 4px 16px rgba(0, 0, 0, 0.25);";
+      launcher.addEventListener("click", () => (frame.style.display === "none" ? api.open() : api.close()));
+      document.body.appendChild(frame);
+      document.body.appendChild(launcher);
+    } else {
+      container.appendChild(iframe);
+    }
+
+    function post(command) {
+      if (ready) {
+        iframe.contentWindow.postMessage(command, base.origin);
+      } else {
+        queue.push(command);
+      }
+    }
+
+    function emit(name, event) {
+      (listeners[name] || []).forEach((listener) => listener(event));
+    }
+
+    window.addEventListener("message", (event) => {
+      if (event.source !== iframe.contentWindow || event.origin !== base.origin) {
+        return;
+      }
+      const data = event.data || {};
+      if (data.xve === "ready") {
+        ready = true;
+        queue.splice(0).forEach(post);
+      } else if (data.xve === "close") {
+        api.close();
+      }
+      if (data.xve) {
+        emit(data.xve, data);
+      }
+    });
+
+    const api = {
+      open() {
+        frame.style.display = "";
+        post({ xve: "open" });
+      },
+      close() {
+        if (floating) {
+          frame.style.display = "none";
+        }
+      },
+      prefill(text) {
+        post({ xve: "prefill", text: String(text) });
+      },
+      send(text) {
+        post({ xve: "send", text: String(text) });
+      },
+      theme(theme) {
+        post({ xve: "theme", dark: theme === "dark" });
+      },
+      on(name, listener) {
+        (listeners[name] = listeners[name] || []).push(listener);
+        return api;
+      },
+      destroy() {
+        frame.remove();
+        if (launcher) {
+          launcher.remove();
+        }
+      },
+    };
+
+    if (options.theme) {
+      api.theme(options.theme);
+    }
+    return api;
+  }
+
+  window.XveChat = { mount };
+})();
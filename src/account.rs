@@ -0,0 +1,144 @@
+use leptos::prelude::{component, signal, view, Get, IntoView, Set};
+use leptos::tachys::prelude::*;
+use leptos::task::spawn_local;
+use serde_json::{Map, Value};
+use wasm_bindgen::{JsCast, JsValue};
+
+use crate::{delete_synced_conversations, dialog, history, local_storage, toast};
+
+/// Every key this app writes to localStorage starts with this
+pub const STORAGE_PREFIX: &str = "wxve-chat:";
+
+/// Everything under `STORAGE_PREFIX` as one JSON document, with the messages archived to
+/// IndexedDB under `archived_messages` by conversation id. Values that are JSON are
+/// embedded as-is; encrypted conversations stay encrypted.
+async fn export_archive() -> Result<String, String> {
+    let storage = local_storage().ok_or("localStorage is unavailable")?;
+    let mut data = Map::new();
+    for i in 0..storage.length().unwrap_or(0) {
+        let Some(key) = storage.key(i).ok().flatten() else {
+            continue;
+        };
+        if !key.starts_with(STORAGE_PREFIX) {
+            continue;
+        }
+        if let Some(raw) = storage.get_item(&key).ok().flatten() {
+            let value = serde_json::from_str(&raw).unwrap_or(Value::String(raw));
+            data.insert(key, value);
+        }
+    }
+    let archived = history::load_all().await.map_err(|e| format!("{e:?}"))?;
+    let archived: Map<String, Value> = archived
+        .into_iter()
+        .map(|(conversation, messages)| (conversation, serde_json::json!(messages)))
+        .collect();
+    let archive = serde_json::json!({
+        "app": "wxve-chat",
+        "exported_at": String::from(js_sys::Date::new_0().to_iso_string()),
+        "data": data,
+        "archived_messages": archived,
+    });
+    serde_json::to_string_pretty(&archive).map_err(|e| e.to_string())
+}
+
+/// Save `contents` through a temporary object URL.
+pub fn download(filename: &str, contents: &str, mime: &str) -> Result<(), String> {
+    let document = web_sys::window()
+        .and_then(|w| w.document())
+        .ok_or("no document")?;
+    let options = web_sys::BlobPropertyBag::new();
+    options.set_type(mime);
+    let blob = web_sys::Blob::new_with_str_sequence_and_options(
+        &js_sys::Array::of1(&JsValue::from_str(contents)),
+        &options,
+    )
+    .map_err(|e| format!("{e:?}"))?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(|e| format!("{e:?}"))?;
+    let link: web_sys::HtmlAnchorElement = document
+        .create_element("a")
+        .map_err(|e| format!("{e:?}"))?
+        .unchecked_into();
+    link.set_href(&url);
+    link.set_download(filename);
+    link.click();
+    let _ = web_sys::Url::revoke_object_url(&url);
+    Ok(())
+}
+
+#[derive(Clone, PartialEq)]
+enum Deletion {
+    Idle,
+    Deleting,
+    Deleted(usize),
+    Failed(String),
+}
+
+/// "Your data" section of the settings dialog: download everything stored locally,
+/// or ask the backend to delete the conversations it has synced.
+#[component]
+pub fn DataSettings() -> impl IntoView {
+    let (deletion, set_deletion) = signal(Deletion::Idle);
+
+    let export = move |_| {
+        spawn_local(async move {
+            let date = String::from(js_sys::Date::new_0().to_iso_string());
+            let filename = format!("wxve-chat-export-{}.json", &date[..10]);
+            let archive = export_archive().await;
+            match archive.and_then(|archive| download(&filename, &archive, "application/json")) {
+                Ok(()) => toast::success("Your data was downloaded"),
+                Err(e) => toast::error(format!("Export failed: {e}")),
+            }
+        });
+    };
+
+    let delete = move |_| {
+        spawn_local(async move {
+            if !dialog::confirm(
+                "Delete all of your conversations stored on the server? This can't be undone. Conversations saved in this browser are kept.",
+            )
+            .await
+            {
+                return;
+            }
+            set_deletion.set(Deletion::Deleting);
+            match delete_synced_conversations().await {
+                Ok(deleted) => {
+                    set_deletion.set(Deletion::Deleted(deleted));
+                    toast::success("Server-side conversations deleted");
+                }
+                Err(e) => set_deletion.set(Deletion::Failed(e)),
+            }
+        });
+    };
+
+    view! {
+        <section class="settings-section">
+            <h3>"Your data"</h3>
+            <p class="settings-hint">
+                "Download everything this app keeps in your browser, or delete the conversations stored on the server."
+            </p>
+            <div class="dialog-actions">
+                <button class="dialog-button" on:click=export>"Download my data"</button>
+                <button
+                    class="dialog-button danger"
+                    disabled=move || deletion.get() == Deletion::Deleting
+                    on:click=delete
+                >
+                    "Delete server data"
+                </button>
+            </div>
+            {move || match deletion.get() {
+                Deletion::Idle => None,
+                Deletion::Deleting => Some(view! {
+                    <p class="settings-hint"><span class="spinner"></span>" Deleting…"</p>
+                }.into_any()),
+                Deletion::Deleted(count) => Some(view! {
+                    <p class="settings-hint">{format!("Deleted {count} conversation(s) from the server.")}</p>
+                }.into_any()),
+                Deletion::Failed(e) => Some(view! {
+                    <p class="vault-error">{format!("Deletion failed: {e}")}</p>
+                }.into_any()),
+            }}
+        </section>
+    }
+}
@@ -0,0 +1,223 @@
+use leptos::prelude::{
+    component, event_target_value, on_cleanup, signal, view, Effect, For, Get, GetValue, IntoView,
+    Memo, RwSignal, Set, SetValue, StoredValue, Update, With, WithUntracked,
+};
+use leptos::tachys::prelude::*;
+use leptos::task::spawn_local;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{AbortController, Notification, NotificationOptions, NotificationPermission};
+
+use crate::connection::Connection;
+use crate::dialog;
+use crate::watchlist::normalize_symbol;
+use crate::{
+    delete_alert, keep_subscribed, load_json, register_alert, save_json, subscribe_alert_triggers,
+    toast, toggle_panel, Panel, Settings,
+};
+
+const ALERTS_KEY: &str = "wxve-chat:alerts";
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PriceAlert {
+    pub id: String,
+    pub symbol: String,
+    pub price: f64,
+    /// Price reported by the backend when the alert fired
+    #[serde(default, alias = "triggered_at")]
+    pub triggered_price: Option<f64>,
+}
+
+pub fn load_alerts() -> Vec<PriceAlert> {
+    load_json(ALERTS_KEY).unwrap_or_default()
+}
+
+pub fn save_alerts(alerts: &[PriceAlert]) {
+    save_json(ALERTS_KEY, &alerts);
+}
+
+/// Register an alert with the backend and track it locally once it has an id. Returns
+/// whether it was set; the user is told when it wasn't.
+pub async fn add_alert(alerts: RwSignal<Vec<PriceAlert>>, symbol: String, price: f64) -> bool {
+    match register_alert(symbol.clone(), price).await {
+        Ok(id) => {
+            alerts.update(|a| a.push(PriceAlert { id, symbol, price, triggered_price: None }));
+            true
+        }
+        Err(e) => {
+            log::error!(target: "transport", "Alert registration failed: {e}");
+            toast::error(format!("Couldn't set the {symbol} alert"));
+            false
+        }
+    }
+}
+
+fn request_notification_permission() {
+    if Notification::permission() == NotificationPermission::Default
+        && let Ok(promise) = Notification::request_permission()
+    {
+        spawn_local(async move {
+            let _ = JsFuture::from(promise).await;
+        });
+    }
+}
+
+fn notify(title: &str, body: &str) {
+    if Notification::permission() != NotificationPermission::Granted {
+        return;
+    }
+    let opts = NotificationOptions::new();
+    opts.set_body(body);
+    let _ = Notification::new_with_options(title, &opts);
+}
+
+#[component]
+pub fn Alerts(
+    alerts: RwSignal<Vec<PriceAlert>>,
+    settings: RwSignal<Settings>,
+    open_panel: RwSignal<Option<Panel>>,
+    connection: RwSignal<Connection>,
+) -> impl IntoView {
+    let (symbol_draft, set_symbol_draft) = signal(String::new());
+    let (price_draft, set_price_draft) = signal(String::new());
+    let pending_ids = Memo::new(move |_| {
+        alerts.with(|a| {
+            a.iter()
+                .filter(|alert| alert.triggered_price.is_none())
+                .map(|alert| alert.id.clone())
+                .collect::<Vec<_>>()
+        })
+    });
+    let has_triggered = move || alerts.with(|a| a.iter().any(|alert| alert.triggered_price.is_some()));
+    let controller = StoredValue::new(None::<AbortController>);
+
+    let stop = move || {
+        if let Some(controller) = controller.get_value() {
+            controller.abort();
+        }
+        controller.set_value(None);
+    };
+
+    // Listen for triggers on every alert that hasn't fired yet
+    Effect::new(move |_| {
+        let ids = pending_ids.get();
+        stop();
+        if ids.is_empty() {
+            return;
+        }
+        let Ok(abort) = AbortController::new() else {
+            return;
+        };
+        let signal = abort.signal();
+        controller.set_value(Some(abort));
+
+        spawn_local(async move {
+            keep_subscribed("Alert", connection, &signal, |opened| {
+                subscribe_alert_triggers(&ids, &signal, opened, |trigger| {
+                    alerts.update(|a| {
+                        if let Some(alert) = a.iter_mut().find(|alert| alert.id == trigger.id) {
+                            alert.triggered_price = Some(trigger.price);
+                            notify(
+                                &format!("{} crossed {:.2}", alert.symbol, alert.price),
+                                &format!("Now trading at {:.2}", trigger.price),
+                            );
+                            settings.with_untracked(Settings::play_sound);
+                        }
+                    });
+                })
+            })
+            .await;
+        });
+    });
+    on_cleanup(stop);
+
+    let add = move || {
+        let Some(symbol) = normalize_symbol(&symbol_draft.get()) else {
+            return;
+        };
+        let Ok(price) = price_draft.get().trim().parse::<f64>() else {
+            return;
+        };
+        request_notification_permission();
+        // The form keeps what was typed until the alert is set, to try again if it isn't
+        spawn_local(async move {
+            if add_alert(alerts, symbol, price).await {
+                set_symbol_draft.set(String::new());
+                set_price_draft.set(String::new());
+            }
+        });
+    };
+
+    let remove = move |id: String| {
+        spawn_local(async move {
+            if !dialog::confirm("Delete this price alert?").await {
+                return;
+            }
+            alerts.update(|a| a.retain(|alert| alert.id != id));
+            let _ = delete_alert(&id).await;
+        });
+    };
+
+    view! {
+        <button
+            class="icon-btn alerts-toggle"
+            class:has-triggered=has_triggered
+            title="Price alerts"
+            on:click=move |_| toggle_panel(open_panel, Panel::Alerts)
+        >
+            "🔔"
+        </button>
+        <aside class="panel alerts" class:open=move || open_panel.get() == Some(Panel::Alerts)>
+            <div class="panel-header">"Price alerts"</div>
+            <form
+                class="panel-form alerts-form"
+                on:submit=move |ev| {
+                    ev.prevent_default();
+                    add();
+                }
+            >
+                <input
+                    type="text"
+                    placeholder="Symbol"
+                    prop:value=move || symbol_draft.get()
+                    on:input=move |ev| set_symbol_draft.set(event_target_value(&ev))
+                />
+                <input
+                    type="text"
+                    inputmode="decimal"
+                    placeholder="Crosses"
+                    prop:value=move || price_draft.get()
+                    on:input=move |ev| set_price_draft.set(event_target_value(&ev))
+                />
+                <button type="submit">"Add"</button>
+            </form>
+            <ul class="panel-items">
+                <For
+                    each=move || alerts.get()
+                    key=|alert| (alert.id.clone(), alert.triggered_price.is_some())
+                    children=move |alert| {
+                        let status = match alert.triggered_price {
+                            Some(price) => format!("triggered at {price:.2}"),
+                            None => "watching".to_string(),
+                        };
+                        let id = alert.id.clone();
+                        view! {
+                            <li class="panel-item alert-item" class:triggered=alert.triggered_price.is_some()>
+                                <span class="alert-symbol">{alert.symbol}</span>
+                                <span class="price">{format!("{:.2}", alert.price)}</span>
+                                <span class="alert-status">{status}</span>
+                                <button
+                                    class="panel-remove"
+                                    title="Remove"
+                                    on:click=move |_| remove(id.clone())
+                                >
+                                    "×"
+                                </button>
+                            </li>
+                        }
+                    }
+                />
+            </ul>
+        </aside>
+    }
+}
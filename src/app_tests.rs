@@ -0,0 +1,352 @@
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::ops::Deref;
+use std::rc::Rc;
+
+use leptos::prelude::view;
+use serde_json::json;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::wasm_bindgen_test;
+use web_sys::{HtmlElement, HtmlTextAreaElement};
+
+use crate::test_support::{mock_fetch, wait_until, Body, End};
+use crate::{local_storage, App};
+
+/// A mounted `App` and the element it renders into. Dropping it unmounts the app
+/// and removes the element, so nothing carries over into later tests.
+struct Mounted {
+    container: HtmlElement,
+    app: Option<Box<dyn Any>>,
+}
+
+impl Deref for Mounted {
+    type Target = HtmlElement;
+
+    fn deref(&self) -> &HtmlElement {
+        &self.container
+    }
+}
+
+impl Drop for Mounted {
+    fn drop(&mut self) {
+        drop(self.app.take());
+        self.container.remove();
+    }
+}
+
+/// Mount a fresh `App` whose `/chat` requests are answered by `replies` in order.
+/// Every other request fails, as if the API were unreachable.
+fn mount(replies: Vec<Body>) -> Mounted {
+    let replies = Rc::new(RefCell::new(VecDeque::from(replies)));
+    mock_fetch(move |url| {
+        if url.ends_with("/chat") {
+            replies.borrow_mut().pop_front()
+        } else {
+            None
+        }
+    });
+    if let Some(storage) = local_storage() {
+        let _ = storage.clear();
+    }
+    reload()
+}
+
+/// Mount another `App` over what's in storage, as a reload of the page would.
+fn reload() -> Mounted {
+    let document = web_sys::window().unwrap().document().unwrap();
+    let container: HtmlElement = document.create_element("div").unwrap().unchecked_into();
+    document.body().unwrap().append_child(&container).unwrap();
+    let app = leptos::mount::mount_to(container.clone(), || view! { <App/> });
+    Mounted { container, app: Some(Box::new(app)) }
+}
+
+/// Type `text` into the input and press Send.
+fn send(container: &HtmlElement, text: &str) {
+    let input: HtmlTextAreaElement =
+        container.query_selector(".input-box textarea").unwrap().unwrap().unchecked_into();
+    input.set_value(text);
+    input.dispatch_event(&web_sys::Event::new("input").unwrap()).unwrap();
+    let button: HtmlElement =
+        container.query_selector(".input-box button").unwrap().unwrap().unchecked_into();
+    button.click();
+}
+
+fn all(container: &HtmlElement, selector: &str) -> Vec<HtmlElement> {
+    let nodes = container.query_selector_all(selector).unwrap();
+    (0..nodes.length())
+        .filter_map(|i| nodes.get(i)?.dyn_into().ok())
+        .collect()
+}
+
+fn text(element: &HtmlElement) -> String {
+    element.text_content().unwrap_or_default()
+}
+
+const USER: &str = ".messages > .message.user";
+const ASSISTANT: &str = ".messages > .message:not(.user):not(.streaming)";
+const STREAMING: &str = ".messages > .message.streaming";
+
+#[wasm_bindgen_test]
+async fn sent_message_appears_as_a_user_bubble() {
+    let container = mount(vec![Body::events(&[], End::Hang)]);
+    send(&container, "How does AAPL look?");
+
+    wait_until("the user bubble", || all(&container, USER).len() == 1).await;
+    assert!(text(&all(&container, USER)[0]).contains("How does AAPL look?"));
+    let input: HtmlTextAreaElement =
+        container.query_selector(".input-box textarea").unwrap().unwrap().unchecked_into();
+    assert_eq!(input.value(), "");
+}
+
+#[wasm_bindgen_test]
+async fn user_markdown_renders_without_raw_html() {
+    let container = mount(vec![Body::events(&[], End::Hang)]);
+    send(&container, "Watching:\n\n- AAPL\n- MSFT\n\n<img src=x onerror=alert(1)>");
+
+    wait_until("the user bubble", || all(&container, USER).len() == 1).await;
+    assert_eq!(all(&container, &format!("{USER} li")).len(), 2);
+    assert!(all(&container, &format!("{USER} img")).is_empty());
+}
+
+#[wasm_bindgen_test]
+async fn streaming_text_accumulates() {
+    let container = mount(vec![Body::events(
+        &[
+            json!({ "type": "text", "content": "Wave 3 " }),
+            json!({ "type": "text", "content": "is " }),
+            json!({ "type": "text", "content": "extending" }),
+        ],
+        End::Hang,
+    )]);
+    send(&container, "How does AAPL look?");
+
+    wait_until("the streamed text", || {
+        all(&container, STREAMING).first().is_some_and(|m| text(m).contains("Wave 3 is extending"))
+    })
+    .await;
+    assert!(all(&container, ASSISTANT).is_empty());
+}
+
+#[wasm_bindgen_test]
+async fn thinking_shows_until_the_first_token() {
+    let container = mount(vec![Body::events(&[], End::Hang)]);
+    send(&container, "How does AAPL look?");
+
+    wait_until("the thinking indicator", || {
+        all(&container, STREAMING).first().is_some_and(|m| text(m).contains("Xve is thinking"))
+    })
+    .await;
+    assert!(container.query_selector(".streamed.typing").unwrap().is_none());
+}
+
+#[wasm_bindgen_test]
+async fn caret_follows_the_streamed_text() {
+    let container = mount(vec![Body::events(&[json!({ "type": "text", "content": "Wave 3 " })], End::Hang)]);
+    send(&container, "How does AAPL look?");
+
+    wait_until("the caret", || container.query_selector(".streamed.typing").unwrap().is_some()).await;
+    assert!(!text(&all(&container, STREAMING)[0]).contains("Xve is thinking"));
+}
+
+#[wasm_bindgen_test]
+async fn done_turns_the_stream_into_a_message() {
+    let container = mount(vec![Body::events(
+        &[json!({ "type": "text", "content": "Wave 3 is extending" }), json!({ "type": "done" })],
+        End::Close,
+    )]);
+    send(&container, "How does AAPL look?");
+
+    wait_until("the finished message", || all(&container, ASSISTANT).len() == 1).await;
+    assert!(text(&all(&container, ASSISTANT)[0]).contains("Wave 3 is extending"));
+    assert!(all(&container, STREAMING).is_empty());
+}
+
+#[wasm_bindgen_test]
+async fn charts_attach_to_the_message_that_produced_them() {
+    let container = mount(vec![
+        Body::events(
+            &[
+                json!({ "type": "tool_start", "name": "wave_analysis" }),
+                json!({ "type": "chart", "symbol": "AAPL", "html": "<p>AAPL chart</p>" }),
+                json!({ "type": "tool_end", "name": "wave_analysis" }),
+                json!({ "type": "text", "content": "Here is the chart" }),
+                json!({ "type": "done" }),
+            ],
+            End::Close,
+        ),
+        Body::events(
+            &[json!({ "type": "text", "content": "No chart this time" }), json!({ "type": "done" })],
+            End::Close,
+        ),
+    ]);
+    send(&container, "Chart AAPL");
+    wait_until("the first reply", || all(&container, ASSISTANT).len() == 1).await;
+    send(&container, "And now?");
+    wait_until("the second reply", || all(&container, ASSISTANT).len() == 2).await;
+
+    let replies = all(&container, ASSISTANT);
+    assert_eq!(replies[0].query_selector_all(".chart-container").unwrap().length(), 1);
+    assert_eq!(replies[1].query_selector_all(".chart-container").unwrap().length(), 0);
+    for user in all(&container, USER) {
+        assert_eq!(user.query_selector_all(".chart-container").unwrap().length(), 0);
+    }
+}
+
+#[wasm_bindgen_test]
+async fn error_chunk_renders_as_a_server_error() {
+    let container = mount(vec![Body::events(
+        &[json!({ "type": "error", "message": "rate limited" })],
+        End::Close,
+    )]);
+    send(&container, "How does AAPL look?");
+
+    wait_until("the error message", || all(&container, ASSISTANT).len() == 1).await;
+    let banner = all(&all(&container, ASSISTANT)[0], ".turn-error");
+    assert_eq!(banner.len(), 1);
+    assert!(text(&banner[0]).contains("rate limited"));
+    assert!(text(&banner[0]).contains("Report"));
+    let button: web_sys::HtmlButtonElement =
+        container.query_selector(".input-box button").unwrap().unwrap().unchecked_into();
+    assert!(!button.disabled());
+}
+
+#[wasm_bindgen_test]
+async fn failed_request_renders_as_a_transient_error() {
+    let container = mount(Vec::new());
+    send(&container, "How does AAPL look?");
+
+    wait_until("the error message", || all(&container, ASSISTANT).len() == 1).await;
+    let banner = all(&all(&container, ASSISTANT)[0], ".turn-error");
+    assert_eq!(banner.len(), 1);
+    assert!(text(&banner[0]).contains("Couldn't reach Xve"));
+    assert!(text(&banner[0]).contains("Retry"));
+}
+
+#[wasm_bindgen_test]
+async fn stop_keeps_the_partial_response() {
+    let container = mount(vec![Body::events(&[json!({ "type": "text", "content": "Wave 3 " })], End::Hang)]);
+    send(&container, "How does AAPL look?");
+    wait_until("the streamed text", || {
+        all(&container, STREAMING).first().is_some_and(|m| text(m).contains("Wave 3"))
+    })
+    .await;
+
+    let stop: HtmlElement = container.query_selector(".input-box .stop").unwrap().unwrap().unchecked_into();
+    stop.click();
+
+    wait_until("the stopped message", || all(&container, ASSISTANT).len() == 1).await;
+    assert!(text(&all(&container, ASSISTANT)[0]).contains("Wave 3"));
+    assert!(all(&container, STREAMING).is_empty());
+    assert!(container.query_selector(".input-box .stop").unwrap().is_none());
+}
+
+#[wasm_bindgen_test]
+async fn a_dropped_stream_keeps_the_partial_response_to_continue() {
+    let container = mount(vec![Body::events(&[json!({ "type": "text", "content": "Wave 3 " })], End::Fail)]);
+    send(&container, "How does AAPL look?");
+
+    wait_until("the interrupted message", || all(&container, ASSISTANT).len() == 1).await;
+    let message = &all(&container, ASSISTANT)[0];
+    assert!(text(message).contains("Wave 3"));
+    assert_eq!(all(message, ".interrupted-note").len(), 1);
+    assert!(all(message, ".turn-error").is_empty());
+}
+
+#[wasm_bindgen_test]
+async fn an_error_chunk_after_text_keeps_the_partial_response_to_continue() {
+    let container = mount(vec![Body::events(
+        &[
+            json!({ "type": "text", "content": "Wave 3 " }),
+            json!({ "type": "error", "message": "model overloaded" }),
+        ],
+        End::Hang,
+    )]);
+    send(&container, "How does AAPL look?");
+
+    wait_until("the interrupted message", || all(&container, ASSISTANT).len() == 1).await;
+    let message = &all(&container, ASSISTANT)[0];
+    assert!(text(message).contains("Wave 3"));
+    assert_eq!(all(message, ".interrupted-note").len(), 1);
+    assert!(all(&container, STREAMING).is_empty());
+}
+
+#[wasm_bindgen_test]
+async fn a_reload_mid_stream_brings_the_partial_response_back() {
+    let container = mount(vec![Body::events(&[json!({ "type": "text", "content": "Wave 3 " })], End::Hang)]);
+    send(&container, "How does AAPL look?");
+    wait_until("the streamed text", || {
+        all(&container, STREAMING).first().is_some_and(|m| text(m).contains("Wave 3"))
+    })
+    .await;
+
+    let reloaded = reload();
+    wait_until("the restored message", || all(&reloaded, ASSISTANT).len() == 1).await;
+    assert!(text(&all(&reloaded, USER)[0]).contains("How does AAPL look?"));
+    let message = &all(&reloaded, ASSISTANT)[0];
+    assert!(text(message).contains("Wave 3"));
+    assert_eq!(all(message, ".interrupted-note").len(), 1);
+}
+
+#[wasm_bindgen_test]
+async fn pause_holds_the_stream_until_resumed() {
+    let container = mount(vec![Body::events(&[json!({ "type": "text", "content": "Wave 3 " })], End::Hang)]);
+    send(&container, "How does AAPL look?");
+    wait_until("the streamed text", || {
+        all(&container, STREAMING).first().is_some_and(|m| text(m).contains("Wave 3"))
+    })
+    .await;
+
+    let pause: HtmlElement = container.query_selector(".input-box .pause").unwrap().unwrap().unchecked_into();
+    pause.click();
+    wait_until("the paused notice", || container.query_selector(".paused-indicator").unwrap().is_some()).await;
+    assert_eq!(text(&pause), "Resume");
+    assert!(text(&all(&container, STREAMING)[0]).contains("Wave 3"));
+
+    pause.click();
+    wait_until("the stream to resume", || container.query_selector(".paused-indicator").unwrap().is_none()).await;
+}
+
+#[wasm_bindgen_test]
+async fn a_response_ending_while_paused_stays_held_until_resumed() {
+    let container = mount(vec![Body::events(&[json!({ "type": "text", "content": "Wave 3 " })], End::Hang)]);
+    send(&container, "How does AAPL look?");
+    wait_until("the streamed text", || {
+        all(&container, STREAMING).first().is_some_and(|m| text(m).contains("Wave 3"))
+    })
+    .await;
+    let pause: HtmlElement = container.query_selector(".input-box .pause").unwrap().unwrap().unchecked_into();
+    pause.click();
+    let stop: HtmlElement = container.query_selector(".input-box .stop").unwrap().unwrap().unchecked_into();
+    stop.click();
+
+    wait_until("the finished message", || all(&container, ASSISTANT).len() == 1).await;
+    assert_eq!(all(&container, &format!("{ASSISTANT} .paused-indicator")).len(), 1);
+    let resume: HtmlElement = container.query_selector(".input-box .pause").unwrap().unwrap().unchecked_into();
+    assert_eq!(text(&resume), "Resume");
+
+    resume.click();
+    wait_until("the hold to end", || container.query_selector(".paused-indicator").unwrap().is_none()).await;
+    assert!(container.query_selector(".input-box .pause").unwrap().is_none());
+}
+
+#[wasm_bindgen_test]
+async fn a_message_sent_while_streaming_waits_its_turn() {
+    let container = mount(vec![Body::events(&[json!({ "type": "text", "content": "Wave 3 " })], End::Hang)]);
+    send(&container, "How does AAPL look?");
+    wait_until("the streamed text", || !all(&container, STREAMING).is_empty()).await;
+
+    send(&container, "And MSFT?");
+    send(&container, "And NVDA?");
+    wait_until("the queued message", || !all(&container, ".queued-hint").is_empty()).await;
+    let hints = all(&container, ".queued-hint");
+    assert_eq!(hints.len(), 1);
+    assert!(text(&hints[0]).contains("And MSFT?"));
+    assert_eq!(all(&container, USER).len(), 1);
+
+    let stop: HtmlElement = container.query_selector(".input-box .stop").unwrap().unwrap().unchecked_into();
+    stop.click();
+    wait_until("the stopped message", || all(&container, ASSISTANT).len() == 1).await;
+    assert!(all(&container, ".queued-hint").is_empty());
+    assert_eq!(all(&container, USER).len(), 1);
+}
@@ -0,0 +1,149 @@
+use leptos::prelude::{component, view, Callable, Callback, Get, IntoView, Memo, ReadSignal};
+use leptos::tachys::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::Message;
+
+/// Alternative continuations of a conversation from the message at `at` on.
+/// The live path is `branches[active]`, left empty while it's the one in `messages`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Fork {
+    pub at: usize,
+    pub branches: Vec<Branch>,
+    pub active: usize,
+}
+
+/// One continuation set aside at a fork, with the forks further along it.
+/// Fork positions are indexes into the whole path, not relative to the branch.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct Branch {
+    pub messages: Vec<Message>,
+    pub forks: Vec<Fork>,
+}
+
+/// Move everything from `at` on into a branch of the fork there, leaving `path`
+/// ready for a new continuation (an edited message or a regenerated response).
+pub fn fork(path: &mut Vec<Message>, forks: &mut Vec<Fork>, at: usize) {
+    let old = set_aside(path, forks, at);
+    match forks.iter_mut().find(|f| f.at == at) {
+        Some(fork) => {
+            fork.branches[fork.active] = old;
+            fork.branches.push(Branch::default());
+            fork.active = fork.branches.len() - 1;
+        }
+        None => forks.push(Fork { at, branches: vec![old, Branch::default()], active: 1 }),
+    }
+}
+
+/// Swap the continuation at fork `at` for its branch `index`, renumbering the restored
+/// messages from `next_id`. Returns the next unused message id.
+pub fn switch_branch(
+    path: &mut Vec<Message>,
+    forks: &mut Vec<Fork>,
+    at: usize,
+    index: usize,
+    next_id: usize,
+) -> usize {
+    let Some(position) = forks.iter().position(|f| f.at == at) else {
+        return next_id;
+    };
+    if index == forks[position].active || index >= forks[position].branches.len() {
+        return next_id;
+    }
+    let old = set_aside(path, forks, at);
+    let fork = &mut forks[position];
+    let Branch { messages, forks: nested } = std::mem::take(&mut fork.branches[index]);
+    fork.branches[fork.active] = old;
+    fork.active = index;
+    let restored = messages.len();
+    path.extend(
+        messages
+            .into_iter()
+            .enumerate()
+            .map(|(i, message)| Message { id: next_id + i, ..message }),
+    );
+    forks.extend(nested);
+    next_id + restored
+}
+
+/// Delete `path[at]`, moving the forks beyond it (and those nested in their branches)
+/// back one place.
+pub fn remove(path: &mut Vec<Message>, forks: &mut [Fork], at: usize) {
+    if at >= path.len() {
+        return;
+    }
+    path.remove(at);
+    shift_back(forks, at);
+}
+
+fn shift_back(forks: &mut [Fork], at: usize) {
+    for fork in forks.iter_mut().filter(|f| f.at > at) {
+        fork.at -= 1;
+        for branch in &mut fork.branches {
+            shift_back(&mut branch.forks, at);
+        }
+    }
+}
+
+/// Take the first `count` messages off `path`, moving every fork back to match.
+/// `count` must not pass the first fork.
+pub fn remove_front(path: &mut Vec<Message>, forks: &mut [Fork], count: usize) {
+    path.drain(..count.min(path.len()));
+    move_forks(forks, &|at| at - count);
+}
+
+/// Put `earlier` messages back in front of `path`, moving every fork forward to match.
+pub fn prepend(path: &mut Vec<Message>, forks: &mut [Fork], earlier: Vec<Message>) {
+    let count = earlier.len();
+    path.splice(0..0, earlier);
+    move_forks(forks, &|at| at + count);
+}
+
+fn move_forks(forks: &mut [Fork], to: &impl Fn(usize) -> usize) {
+    for fork in forks {
+        fork.at = to(fork.at);
+        for branch in &mut fork.branches {
+            move_forks(&mut branch.forks, to);
+        }
+    }
+}
+
+/// Take `path[at..]` and the forks beyond `at` as a branch.
+fn set_aside(path: &mut Vec<Message>, forks: &mut Vec<Fork>, at: usize) -> Branch {
+    let (nested, kept) = std::mem::take(forks).into_iter().partition(|f| f.at > at);
+    *forks = kept;
+    Branch { messages: path.split_off(at.min(path.len())), forks: nested }
+}
+
+/// "‹ 2/3 ›" switcher shown on the message where branches diverge.
+#[component]
+pub fn BranchSwitcher(
+    /// Active branch and branch count of the fork at this message
+    fork: Memo<Option<(usize, usize)>>,
+    disabled: ReadSignal<bool>,
+    on_switch: Callback<usize>,
+) -> impl IntoView {
+    move || {
+        fork.get().map(|(active, count)| {
+            view! {
+                <div class="branch-switcher">
+                    <button
+                        title="Previous branch"
+                        disabled=move || disabled.get() || active == 0
+                        on:click=move |_| on_switch.run(active - 1)
+                    >
+                        "‹"
+                    </button>
+                    <span>{format!("{}/{}", active + 1, count)}</span>
+                    <button
+                        title="Next branch"
+                        disabled=move || disabled.get() || active + 1 == count
+                        on:click=move |_| on_switch.run(active + 1)
+                    >
+                        "›"
+                    </button>
+                </div>
+            }
+        })
+    }
+}
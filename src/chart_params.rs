@@ -0,0 +1,131 @@
+use leptos::prelude::{
+    component, event_target_value, view, Callable, Callback, Get, GetUntracked, IntoView,
+    ReadSignal, RwSignal, Set, With,
+};
+use leptos::tachys::prelude::*;
+use wxve_chat_client::ChartParams;
+
+const TIMEFRAMES: [&str; 5] = ["1h", "4h", "1d", "1w", "1mo"];
+const DEGREES: [&str; 4] = ["minute", "minor", "intermediate", "primary"];
+
+/// Where a chart drawn again from the parameters form goes
+#[derive(Clone, Copy, PartialEq)]
+pub enum Placement {
+    /// In place of the chart the form belongs to
+    Replace,
+    /// As another chart after it, in the same message
+    Append,
+}
+
+/// One line for the form's summary, e.g. `AAPL · 1d · 250 bars · minor wave degree`.
+fn summary(symbol: &str, params: &ChartParams) -> String {
+    let mut parts = vec![symbol.to_string()];
+    parts.extend(params.timeframe.clone());
+    parts.extend(params.lookback.map(|bars| format!("{bars} bars")));
+    parts.extend(params.degree.as_ref().map(|degree| format!("{degree} wave degree")));
+    if parts.len() == 1 {
+        parts.push("default settings".to_string());
+    }
+    parts.join(" · ")
+}
+
+fn non_empty(value: String) -> Option<String> {
+    Some(value.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// What a chart was drawn with, under it, opening into a form to draw it again with other
+/// settings.
+#[component]
+pub fn ChartParamsForm(
+    symbol: ReadSignal<String>,
+    params: ReadSignal<ChartParams>,
+    /// A chart is being drawn, so the form waits
+    rendering: ReadSignal<bool>,
+    /// Offer "Add below" as well as replacing the chart
+    can_append: bool,
+    on_run: Callback<(String, ChartParams, Placement)>,
+) -> impl IntoView {
+    let initial = params.get_untracked();
+    let draft_symbol = RwSignal::new(symbol.get_untracked());
+    let timeframe = RwSignal::new(initial.timeframe.unwrap_or_default());
+    let lookback = RwSignal::new(initial.lookback.map(|n| n.to_string()).unwrap_or_default());
+    let degree = RwSignal::new(initial.degree.unwrap_or_default());
+    let run = move |placement: Placement| {
+        let Some(symbol) = non_empty(draft_symbol.get().to_uppercase()) else {
+            return;
+        };
+        let params = ChartParams {
+            timeframe: non_empty(timeframe.get()),
+            lookback: lookback.get().trim().parse().ok().filter(|&n| n > 0),
+            degree: non_empty(degree.get()),
+        };
+        on_run.run((symbol, params, placement));
+    };
+    let options = |values: &'static [&'static str], selected: RwSignal<String>| {
+        values
+            .iter()
+            .map(|&value| view! { <option value=value selected=move || selected.get() == value>{value}</option> })
+            .collect::<Vec<_>>()
+    };
+
+    view! {
+        <details class="chart-params">
+            <summary>{move || params.with(|p| summary(&symbol.get(), p))}</summary>
+            <form
+                class="chart-params-form"
+                on:submit=move |ev| {
+                    ev.prevent_default();
+                    run(Placement::Replace);
+                }
+            >
+                <label>
+                    "Symbol"
+                    <input
+                        type="text"
+                        prop:value=draft_symbol
+                        on:input=move |ev| draft_symbol.set(event_target_value(&ev))
+                    />
+                </label>
+                <label>
+                    "Timeframe"
+                    <select on:change=move |ev| timeframe.set(event_target_value(&ev))>
+                        <option value="" selected=move || timeframe.get().is_empty()>"Default"</option>
+                        {options(&TIMEFRAMES, timeframe)}
+                    </select>
+                </label>
+                <label>
+                    "Bars"
+                    <input
+                        type="number"
+                        min="1"
+                        placeholder="Default"
+                        prop:value=lookback
+                        on:input=move |ev| lookback.set(event_target_value(&ev))
+                    />
+                </label>
+                <label>
+                    "Wave degree"
+                    <select on:change=move |ev| degree.set(event_target_value(&ev))>
+                        <option value="" selected=move || degree.get().is_empty()>"Default"</option>
+                        {options(&DEGREES, degree)}
+                    </select>
+                </label>
+                <div class="dialog-actions">
+                    {can_append.then(|| view! {
+                        <button
+                            type="button"
+                            class="dialog-button"
+                            disabled=rendering
+                            on:click=move |_| run(Placement::Append)
+                        >
+                            "Add below"
+                        </button>
+                    })}
+                    <button type="submit" class="dialog-button primary" disabled=rendering>
+                        "Redraw"
+                    </button>
+                </div>
+            </form>
+        </details>
+    }
+}
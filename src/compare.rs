@@ -0,0 +1,202 @@
+use leptos::prelude::{component, view, For, IntoView, ReadSignal, RwSignal, Set, Update, With};
+use leptos::tachys::prelude::*;
+use leptos::task::spawn_local;
+use web_sys::AbortSignal;
+
+use crate::state::use_app_state;
+use crate::tool_approval::send_decision;
+use crate::{
+    markdown_to_html, new_id, post_chart_update, send_message, ChartView, ChatRequest, Chart,
+    Message, ResponseMeta, Role, Sentiment, StreamChunk,
+};
+
+/// Sampling temperatures streamed side by side by "Regenerate and compare"
+const VARIANTS: [(&str, f64); 2] = [("Focused", 0.2), ("Creative", 1.0)];
+
+/// One response being streamed for comparison.
+#[derive(Clone)]
+pub struct Pane {
+    label: &'static str,
+    content: String,
+    charts: Vec<Chart>,
+    sentiment: Option<Sentiment>,
+    meta: ResponseMeta,
+    tool: Option<String>,
+    done: bool,
+}
+
+impl Pane {
+    pub fn into_message(self, id: usize) -> Message {
+        Message {
+            id,
+            role: Role::Assistant,
+            content: self.content,
+            quoted: None,
+            charts: self.charts,
+            sentiment: self.sentiment,
+            meta: Some(self.meta),
+            pinned: false,
+            reactions: Vec::new(),
+            delivery: None,
+            error: None,
+            interrupted: false,
+        }
+    }
+}
+
+/// Stream `request` once per variant into `panes` until `signal` aborts.
+/// Alerts are ignored so the same alert isn't registered twice.
+pub fn stream_variants(
+    request: ChatRequest,
+    panes: RwSignal<Vec<Pane>>,
+    charts_enabled: bool,
+    signal: AbortSignal,
+) {
+    let indicators = request.indicators.clone();
+    let requests: Vec<_> = VARIANTS
+        .iter()
+        .map(|&(label, temperature)| {
+            let request_id = new_id();
+            let pane = Pane {
+                label,
+                content: String::new(),
+                charts: Vec::new(),
+                sentiment: None,
+                meta: ResponseMeta::new(request_id.clone()),
+                tool: None,
+                done: false,
+            };
+            // Two panes can't take turns asking, so variants run their tools unasked
+            let request = ChatRequest {
+                temperature: Some(temperature),
+                approve_tools: false,
+                idempotency_key: new_id(),
+                ..request.clone()
+            };
+            (pane, request_id, request)
+        })
+        .collect();
+    panes.set(requests.iter().map(|(pane, ..)| pane.clone()).collect());
+
+    for (index, (_, request_id, request)) in requests.into_iter().enumerate() {
+        let indicators = indicators.clone();
+        let update = move |f: &dyn Fn(&mut Pane)| {
+            panes.update(|panes| {
+                if let Some(pane) = panes.get_mut(index) {
+                    f(pane);
+                }
+            });
+        };
+        let signal = signal.clone();
+        spawn_local(async move {
+            let finish = move |error: Option<String>| {
+                update(&|pane| {
+                    if let Some(error) = &error {
+                        pane.content = format!("Error: {error}");
+                    }
+                    pane.tool = None;
+                    pane.meta.finished_at = Some(js_sys::Date::now());
+                    pane.done = true;
+                })
+            };
+            let result = send_message(request, &request_id, Some(&signal), move |chunk| match chunk {
+                StreamChunk::Text { content } => update(&|pane| {
+                    pane.meta.first_token_at.get_or_insert_with(js_sys::Date::now);
+                    pane.content.push_str(&content);
+                }),
+                StreamChunk::Meta { model, request_id } => update(&|pane| {
+                    pane.meta.model = Some(model.clone());
+                    if let Some(request_id) = &request_id {
+                        pane.meta.request_id = request_id.clone();
+                    }
+                }),
+                StreamChunk::Chart { .. } if !charts_enabled => {}
+                StreamChunk::Chart { symbol, html, params } => update(&|pane| {
+                    pane.charts.push(Chart::new(
+                        symbol.clone(),
+                        html.clone(),
+                        indicators.clone(),
+                        params.clone(),
+                    ));
+                }),
+                StreamChunk::ChartUpdate { .. } if !charts_enabled => {}
+                StreamChunk::ChartUpdate { symbol, patch } => post_chart_update(&symbol, &patch),
+                StreamChunk::Sentiment { score } => update(&|pane| {
+                    pane.sentiment = Some(Sentiment { score: score.clamp(-1.0, 1.0) });
+                }),
+                StreamChunk::Alert { .. } => {}
+                StreamChunk::ToolRequest { id, .. } => send_decision(id, true),
+                StreamChunk::ToolStart { name } => update(&|pane| pane.tool = Some(name.clone())),
+                StreamChunk::ToolEnd { .. } => update(&|pane| {
+                    pane.tool = None;
+                    pane.content.push_str("\n\n");
+                }),
+                StreamChunk::Done => finish(None),
+                StreamChunk::Error { message } => finish(Some(message)),
+            })
+            .await;
+            if let Err(e) = result
+                && !signal.aborted()
+            {
+                finish(Some(e));
+            }
+        });
+    }
+}
+
+/// Responses streamed side by side; once all are finished each can be kept.
+#[component]
+pub fn Comparison(dark_mode: ReadSignal<bool>) -> impl IntoView {
+    let state = use_app_state();
+    let (panes, settings) = (state.compare_panes, state.settings);
+    let all_done = move || panes.with(|p| p.iter().all(|pane| pane.done));
+
+    view! {
+        <div class="comparison">
+            <For
+                each=move || 0..panes.with(Vec::len)
+                key=|index| *index
+                children=move |index| {
+                    view! {
+                        <div class="comparison-pane">
+                            <div class="comparison-header">
+                                <span>{move || panes.with(|p| p.get(index).map(|p| p.label))}</span>
+                                <button
+                                    class="dialog-button primary"
+                                    disabled=move || !all_done()
+                                    on:click=move |_| state.keep_variant(index)
+                                >
+                                    "Keep this"
+                                </button>
+                            </div>
+                            {move || panes.with(|p| p.get(index).and_then(|p| p.tool.clone())).map(|name| view! {
+                                <div class="tool-indicator">
+                                    <span class="spinner"></span>
+                                    {format!("Using {name}...")}
+                                </div>
+                            })}
+                            <span inner_html=move || {
+                                panes.with(|p| p.get(index).map(|p| markdown_to_html(&p.content)))
+                                    .unwrap_or_default()
+                            }></span>
+                            // Keyed by position so streamed text doesn't reload the chart iframes
+                            <For
+                                each=move || 0..panes.with(|p| p.get(index).map_or(0, |p| p.charts.len()))
+                                key=|chart| *chart
+                                children=move |chart| {
+                                    view! {
+                                        <ChartView
+                                            chart=panes.with(|p| p[index].charts[chart].clone())
+                                            settings=settings
+                                            dark_mode=dark_mode
+                                        />
+                                    }
+                                }
+                            />
+                        </div>
+                    }
+                }
+            />
+        </div>
+    }
+}
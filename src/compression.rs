@@ -0,0 +1,45 @@
+use std::cell::Cell;
+
+use web_sys::RequestInit;
+use wxve_chat_client::web::{self, Encoding};
+
+thread_local! {
+    /// What `/chat` bodies are compressed with, once `/config` says the backend takes it
+    static ENCODING: Cell<Option<Encoding>> = const { Cell::new(None) };
+}
+
+/// Pick the encoding for `/chat` bodies from the `request_encodings` the backend listed in
+/// `/config`, preferring gzip. Nothing listed (or an old backend) means plain JSON.
+pub fn negotiate(accepted: &[String]) {
+    let encoding = [Encoding::Gzip, Encoding::Deflate]
+        .into_iter()
+        .find(|encoding| accepted.iter().any(|name| name == encoding.name()));
+    log::debug!(target: "transport", "Request compression: {encoding:?}");
+    ENCODING.set(encoding);
+}
+
+/// The backend turned down a compressed body (415), so send plain JSON from now on.
+/// Returns whether anything was being compressed, i.e. whether a retry is worth it.
+pub fn refused() -> bool {
+    let encoding = ENCODING.take();
+    if let Some(encoding) = encoding {
+        log::warn!(target: "transport", "Backend refused {} request bodies", encoding.name());
+    }
+    encoding.is_some()
+}
+
+/// Options for POSTing the JSON `body`, compressed when it's big enough and the backend
+/// accepts it. Where the browser can't compress, this and later bodies go as plain JSON.
+pub async fn post_json(body: &str, extra_headers: &[(&str, &str)]) -> Result<RequestInit, String> {
+    let Some(encoding) = ENCODING.get() else {
+        return web::post_json_str(body, extra_headers);
+    };
+    match web::post_json_encoded(body, extra_headers, encoding).await {
+        Ok(opts) => Ok(opts),
+        Err(e) => {
+            log::warn!(target: "transport", "Sending uncompressed: {e}");
+            ENCODING.set(None);
+            web::post_json_str(body, extra_headers)
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use leptos::prelude::{component, signal, view, Get, IntoView, RwSignal, Set, With};
+use leptos::tachys::prelude::*;
+
+use crate::{window_listener, RECONNECT_DELAY};
+
+/// Health of the long-lived streams (quotes, alerts) as reported by `keep_subscribed()`.
+#[derive(Clone, Default, PartialEq)]
+pub struct Connection {
+    /// Streams waiting to reconnect, with the error that dropped them
+    retrying: Vec<(String, String)>,
+}
+
+impl Connection {
+    /// `label` is connected, or was closed on purpose. True if it had been retrying.
+    pub fn opened(&mut self, label: &str) -> bool {
+        let before = self.retrying.len();
+        self.retrying.retain(|(l, _)| l != label);
+        self.retrying.len() != before
+    }
+
+    pub fn failed(&mut self, label: &str, error: String) {
+        self.retrying.retain(|(l, _)| l != label);
+        self.retrying.push((label.to_string(), error));
+    }
+}
+
+fn navigator_online() -> bool {
+    web_sys::window().is_none_or(|w| w.navigator().on_line())
+}
+
+/// Status dot: connected, reconnecting (a stream is being retried) or offline.
+#[component]
+pub fn ConnectionStatus(connection: RwSignal<Connection>) -> impl IntoView {
+    let (online, set_online) = signal(navigator_online());
+    window_listener(leptos::ev::online, move |_| set_online.set(true));
+    window_listener(leptos::ev::offline, move |_| set_online.set(false));
+
+    let state = move || {
+        if !online.get() {
+            ("offline", "Offline - waiting for the network to come back".to_string())
+        } else if connection.with(|c| c.retrying.is_empty()) {
+            ("connected", "Connected".to_string())
+        } else {
+            let streams = connection.with(|c| {
+                c.retrying
+                    .iter()
+                    .map(|(label, error)| format!("{label} stream: {error}"))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            });
+            let detail = format!(
+                "Reconnecting every {}s\n{streams}",
+                RECONNECT_DELAY.as_secs(),
+            );
+            ("reconnecting", detail)
+        }
+    };
+
+    view! {
+        <div
+            class=move || format!("connection-status {}", state().0)
+            title=move || state().1
+        >
+            <span class="connection-dot"></span>
+        </div>
+    }
+}
@@ -0,0 +1,427 @@
+use std::time::Duration;
+
+use leptos::html::Input;
+use leptos::prelude::{
+    component, event_target_value, view, AnyView, Callable, Callback, Effect, For, Get,
+    GetUntracked, GetValue, IntoView, Memo, NodeRef, ReadSignal, RwSignal, Set, StoredValue,
+    Update, UpdateValue, With, WithUntracked,
+};
+use leptos::task::spawn_local;
+use leptos::tachys::prelude::*;
+
+use crate::conversations::{
+    add_tag, all_tags, merge_synced, remove_tag, rename, set_archived, Conversation,
+};
+use crate::dialog::{self, Dialog};
+use crate::export::export_conversation;
+use crate::pull_refresh::PullToRefresh;
+use crate::remote_config::RemoteConfig;
+use crate::state::{use_app_state, AppState};
+use crate::workspace::WorkspaceSelect;
+use crate::{fetch_synced_conversations, sleep, toast, toggle_panel, Panel};
+
+/// Pulls this close together make one `GET /conversations`
+const SYNC_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// What a row in the list needs, so the list only re-renders when one of them changes.
+#[derive(Clone, PartialEq)]
+struct Row {
+    id: String,
+    title: String,
+    updated_at: f64,
+    tags: Vec<String>,
+}
+
+impl Row {
+    fn new(conversation: &Conversation) -> Self {
+        Row {
+            id: conversation.id.clone(),
+            title: conversation.title.clone(),
+            updated_at: conversation.updated_at,
+            tags: conversation.tags.clone(),
+        }
+    }
+
+    fn key(&self) -> (String, String, u64, Vec<String>) {
+        (self.id.clone(), self.title.clone(), self.updated_at.to_bits(), self.tags.clone())
+    }
+}
+
+fn local_date(ms: f64) -> String {
+    js_sys::Date::new(&ms.into())
+        .to_locale_date_string("default", &wasm_bindgen::JsValue::UNDEFINED)
+        .into()
+}
+
+/// Add and remove the tags of one conversation.
+struct TagsDialog {
+    id: String,
+    conversations: RwSignal<Vec<Conversation>>,
+}
+
+impl Dialog for TagsDialog {
+    fn title(&self) -> String {
+        "Tags".to_string()
+    }
+
+    fn body(&self, close: Callback<()>) -> AnyView {
+        let conversations = self.conversations;
+        let id = StoredValue::new(self.id.clone());
+        let tags = Memo::new(move |_| {
+            let id = id.get_value();
+            conversations
+                .with(|c| c.iter().find(|c| c.id == id).map(|c| c.tags.clone()))
+                .unwrap_or_default()
+        });
+        let suggestions = move || {
+            let tags = tags.get();
+            let all = conversations.with(|c| all_tags(c));
+            all.into_iter().filter(|t| !tags.contains(t)).collect::<Vec<_>>()
+        };
+        let draft = RwSignal::new(String::new());
+        let add = move |ev: leptos::ev::SubmitEvent| {
+            ev.prevent_default();
+            conversations.update(|c| add_tag(c, &id.get_value(), &draft.get()));
+            draft.set(String::new());
+        };
+
+        view! {
+            <p class="settings-hint">"Tags group conversations; filter by them in the conversations panel."</p>
+            <div class="conversation-tags">
+                <For
+                    each=move || tags.get()
+                    key=String::clone
+                    children=move |tag| {
+                        let label = tag.clone();
+                        view! {
+                            <span class="tag">
+                                {label}
+                                <button
+                                    class="panel-remove"
+                                    title="Remove tag"
+                                    on:click=move |_| conversations.update(|c| remove_tag(c, &id.get_value(), &tag))
+                                >
+                                    "×"
+                                </button>
+                            </span>
+                        }
+                    }
+                />
+            </div>
+            <form class="panel-form" on:submit=add>
+                <input
+                    type="text"
+                    placeholder="Add a tag, e.g. swing trades"
+                    list="tag-suggestions"
+                    prop:value=draft
+                    on:input=move |ev| draft.set(event_target_value(&ev))
+                />
+                <datalist id="tag-suggestions">
+                    {move || suggestions().into_iter().map(|tag| view! { <option value=tag/> }).collect::<Vec<_>>()}
+                </datalist>
+            </form>
+            <div class="dialog-actions">
+                <button class="dialog-button" on:click=move |_| close.run(())>"Done"</button>
+            </div>
+        }
+        .into_any()
+    }
+}
+
+/// Re-sync the saved conversations from the backend on pull-to-refresh, once pulls have
+/// stopped for `SYNC_DEBOUNCE`. Returns whether a sync is under way, for the indicator.
+fn use_conversation_sync(state: AppState) -> (RwSignal<bool>, impl Fn() + Copy) {
+    let syncing = RwSignal::new(false);
+    let generation = StoredValue::new(0u64);
+    let sync = move || {
+        generation.update_value(|g| *g += 1);
+        let current = generation.get_value();
+        syncing.set(true);
+        spawn_local(async move {
+            sleep(SYNC_DEBOUNCE).await;
+            if generation.get_value() != current {
+                return;
+            }
+            let result = fetch_synced_conversations().await;
+            // A later pull's sync replaces this one
+            if generation.get_value() != current {
+                return;
+            }
+            syncing.set(false);
+            let synced = match result {
+                Ok(synced) => synced,
+                Err(e) => {
+                    log::warn!(target: "transport", "Conversation sync failed: {e}");
+                    toast::error("Couldn't sync conversations");
+                    return;
+                }
+            };
+            let open = state.conversation_id.get_untracked();
+            let busy = state.loading.get_untracked().then_some(open.as_str());
+            let mut changed = Vec::new();
+            state.conversations.update(|c| changed = merge_synced(c, synced, busy));
+            log::info!(target: "storage", "Synced conversations, {} changed", changed.len());
+            if changed.contains(&open) {
+                state.switch_conversation(&open);
+            }
+        });
+    };
+    (syncing, sync)
+}
+
+/// Saved conversations, most recent first, to switch between, rename (double-click or ✎),
+/// tag, duplicate and archive. Archived ones are kept (and still listed on a symbol's page) but
+/// listed apart until restored.
+/// Tags double as folders: picking one above the list shows only the conversations with it.
+#[component]
+pub fn ConversationList(
+    open_panel: RwSignal<Option<Panel>>,
+    /// Theme an export is saved in
+    dark_mode: ReadSignal<bool>,
+    config: RwSignal<RemoteConfig>,
+) -> impl IntoView {
+    let state = use_app_state();
+    let conversations = state.conversations;
+    let show_archived = RwSignal::new(false);
+    let tags = Memo::new(move |_| conversations.with(|c| all_tags(c)));
+    let filter = RwSignal::new(None::<String>);
+    // A filter on a tag no longer in use shows everything again
+    let active_filter =
+        Memo::new(move |_| filter.get().filter(|tag| tags.with(|t| t.contains(tag))));
+    let rows = move |archived: bool| {
+        let filter = active_filter.get();
+        conversations.with(|c| {
+            c.iter()
+                .filter(|c| c.archived_at.is_some() == archived)
+                .filter(|c| filter.as_ref().is_none_or(|tag| c.tags.contains(tag)))
+                .map(Row::new)
+                .collect::<Vec<_>>()
+        })
+    };
+    let active = Memo::new(move |_| rows(false));
+    let archived = Memo::new(move |_| rows(true));
+
+    let archive = move |id: String| {
+        // The open conversation is put away along with its messages
+        if state.conversation_id.get() == id && !state.new_conversation() {
+            return;
+        }
+        conversations.update(|c| set_archived(c, &id, true));
+    };
+    let restore = move |id: String| conversations.update(|c| set_archived(c, &id, false));
+    // Only a backend that keeps conversations has any to sync from
+    let pull = PullToRefresh::new();
+    let (syncing, sync) = use_conversation_sync(state);
+    let can_sync = move || config.with_untracked(|c| c.conversation_history);
+    let edit_tags = move |id: String| {
+        dialog::open(TagsDialog { id, conversations });
+    };
+    // The conversation whose title is being edited in place
+    let renaming = RwSignal::new(None::<String>);
+    let row = move |row: Row, archived: bool| {
+        let Row { id, title, updated_at, tags } = row;
+        let id = StoredValue::new(id);
+        let is_current = move || state.conversation_id.with(|c| *c == id.get_value());
+        let is_renaming = move || renaming.with(|r| r.as_ref() == Some(&id.get_value()));
+        let open = move |_| {
+            if !is_current() {
+                state.switch_conversation(&id.get_value());
+            }
+        };
+        let finish = move |text: Option<String>| {
+            if !is_renaming() {
+                return;
+            }
+            renaming.set(None);
+            if let Some(text) = text {
+                conversations.update(|c| rename(c, &id.get_value(), &text));
+            }
+        };
+        let title = StoredValue::new(title);
+        let title_view = move || {
+            if is_renaming() {
+                let input = NodeRef::<Input>::new();
+                Effect::new(move |_| {
+                    if let Some(input) = input.get() {
+                        let _ = input.focus();
+                        input.select();
+                    }
+                });
+                view! {
+                    <input
+                        class="conversation-rename"
+                        type="text"
+                        aria-label="Conversation title"
+                        node_ref=input
+                        prop:value=title.get_value()
+                        on:keydown=move |ev| match ev.key().as_str() {
+                            "Enter" => finish(Some(event_target_value(&ev))),
+                            "Escape" => finish(None),
+                            _ => {}
+                        }
+                        on:blur=move |ev| finish(Some(event_target_value(&ev)))
+                    />
+                }
+                .into_any()
+            } else {
+                let label = title.get_value();
+                let label = if label.is_empty() { "(untitled)".to_string() } else { label };
+                view! {
+                    <button
+                        class="conversation-open"
+                        title="Open; double-click to rename"
+                        disabled=state.loading
+                        on:click=open
+                        on:dblclick=move |_| renaming.set(Some(id.get_value()))
+                    >
+                        <span class="conversation-title">{label}</span>
+                        <span class="conversation-date">{local_date(updated_at)}</span>
+                    </button>
+                }
+                .into_any()
+            }
+        };
+        view! {
+            <li class="panel-item conversation-row" class:current=is_current>
+                {title_view}
+                {(!tags.is_empty()).then(|| view! {
+                    <span class="conversation-tags">
+                        {tags.into_iter().map(|tag| view! { <span class="tag">{tag}</span> }).collect::<Vec<_>>()}
+                    </span>
+                })}
+                <button class="panel-remove" title="Rename" on:click=move |_| renaming.set(Some(id.get_value()))>
+                    "✎"
+                </button>
+                <button class="panel-remove" title="Tags" on:click=move |_| edit_tags(id.get_value())>
+                    "🏷"
+                </button>
+                <button
+                    class="panel-remove"
+                    title="Duplicate"
+                    disabled=state.loading
+                    on:click=move |_| state.duplicate_conversation(&id.get_value())
+                >
+                    "⧉"
+                </button>
+                {if archived {
+                    view! {
+                        <button class="panel-remove" title="Restore" on:click=move |_| restore(id.get_value())>
+                            "↩"
+                        </button>
+                    }.into_any()
+                } else {
+                    view! {
+                        <button class="panel-remove" title="Archive" on:click=move |_| archive(id.get_value())>
+                            "🗄"
+                        </button>
+                    }.into_any()
+                }}
+            </li>
+        }
+    };
+
+    view! {
+        <button
+            class="icon-btn conversations-toggle"
+            title="Conversations"
+            on:click=move |_| toggle_panel(open_panel, Panel::Conversations)
+        >
+            "🗂"
+        </button>
+        <aside
+            class="panel conversations"
+            class:open=move || open_panel.get() == Some(Panel::Conversations)
+            on:touchstart=move |ev| {
+                if can_sync() && !syncing.get_untracked() {
+                    pull.start(&ev);
+                }
+            }
+            on:touchmove=move |ev| pull.moved(&ev)
+            on:touchend=move |_| {
+                if pull.end() {
+                    sync();
+                }
+            }
+            on:touchcancel=move |_| pull.cancel()
+        >
+            {move || (pull.pulling() || syncing.get()).then(|| view! {
+                <div
+                    class="pull-indicator"
+                    class:ready=move || pull.ready()
+                    style:height=move || if syncing.get() { String::new() } else { format!("{}px", pull.offset()) }
+                    role="status"
+                >
+                    {move || if syncing.get() {
+                        view! { <span class="spinner"></span>"Syncing…" }.into_any()
+                    } else if pull.ready() {
+                        view! { <span class="pull-arrow">"↓"</span>"Release to sync" }.into_any()
+                    } else {
+                        view! { <span class="pull-arrow">"↓"</span>"Pull to sync" }.into_any()
+                    }}
+                </div>
+            })}
+            <div class="panel-header">
+                "Conversations"
+                <span class="conversations-actions">
+                    <button
+                        class="panel-option"
+                        title="Download this conversation as a web page, charts included"
+                        disabled=move || state.messages.with(Vec::is_empty)
+                        on:click=move |_| {
+                            let dark = dark_mode.get_untracked();
+                            spawn_local(export_conversation(state, dark));
+                        }
+                    >
+                        "⤓ Export"
+                    </button>
+                    <button class="panel-option" on:click=move |_| { state.new_conversation(); }>
+                        "+ New"
+                    </button>
+                </span>
+            </div>
+            <WorkspaceSelect/>
+            {move || (!tags.with(Vec::is_empty)).then(|| view! {
+                <div class="tag-filters">
+                    <button class="tag" class:active=move || active_filter.with(Option::is_none) on:click=move |_| filter.set(None)>
+                        "All"
+                    </button>
+                    {tags.get().into_iter().map(|tag| {
+                        let selected = {
+                            let tag = tag.clone();
+                            move || active_filter.with(|f| f.as_ref() == Some(&tag))
+                        };
+                        let pick = {
+                            let tag = tag.clone();
+                            move |_| filter.set(Some(tag.clone()))
+                        };
+                        view! {
+                            <button class="tag" class:active=selected on:click=pick>
+                                {tag}
+                            </button>
+                        }
+                    }).collect::<Vec<_>>()}
+                </div>
+            })}
+            {move || active.with(Vec::is_empty).then(|| view! {
+                <p class="panel-empty">"Your saved conversations show up here."</p>
+            })}
+            <ul class="panel-items">
+                <For each=move || active.get() key=Row::key children=move |r| row(r, false)/>
+            </ul>
+            {move || (!archived.with(Vec::is_empty)).then(|| view! {
+                <button
+                    class="conversations-archived"
+                    aria-expanded=move || show_archived.get().to_string()
+                    on:click=move |_| show_archived.update(|s| *s = !*s)
+                >
+                    {move || format!("Archived ({})", archived.with(Vec::len))}
+                </button>
+            })}
+            {move || show_archived.get().then(|| view! {
+                <ul class="panel-items archived">
+                    <For each=move || archived.get() key=Row::key children=move |r| row(r, true)/>
+                </ul>
+            })}
+        </aside>
+    }
+}
@@ -0,0 +1,231 @@
+use serde::{Deserialize, Serialize};
+
+use crate::branches::Fork;
+use crate::{history, load_json, save_json, Message, Role};
+
+pub const CONVERSATIONS_KEY: &str = "wxve-chat:conversations";
+
+/// Oldest conversations are dropped past this many to stay within localStorage quota
+const MAX_CONVERSATIONS: usize = 100;
+
+const TITLE_LENGTH: usize = 60;
+const TAG_LENGTH: usize = 32;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Conversation {
+    pub id: String,
+    pub title: String,
+    /// Milliseconds since the epoch
+    pub updated_at: f64,
+    pub messages: Vec<Message>,
+    /// Continuations set aside by edits and regenerations
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub forks: Vec<Fork>,
+    /// Messages before `messages` that the history cap moved out to IndexedDB
+    #[serde(default)]
+    pub archived: usize,
+    /// When it was put away: archived conversations are listed apart until restored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archived_at: Option<f64>,
+    /// Labels to group and filter conversations by, e.g. "swing trades"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// `title` was set by the user rather than taken from the first message
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub renamed: bool,
+}
+
+impl Conversation {
+    /// Messages with fresh ids from `first_id` on, ready to render (ids aren't persisted).
+    /// Ids must not repeat ones already rendered or the keyed list would reuse stale rows.
+    pub fn restored_messages(&self, first_id: usize) -> Vec<Message> {
+        self.messages
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(i, message)| Message { id: first_id + i, ..message })
+            .collect()
+    }
+}
+
+/// Saved conversations, most recently updated first.
+pub fn load_conversations() -> Vec<Conversation> {
+    load_json(CONVERSATIONS_KEY).unwrap_or_default()
+}
+
+pub fn save_conversations(conversations: &[Conversation]) {
+    save_json(CONVERSATIONS_KEY, &conversations);
+}
+
+/// Title a conversation after its first user message.
+fn title_for(messages: &[Message]) -> String {
+    let first = messages
+        .iter()
+        .find(|m| m.role == Role::User)
+        .map(|m| m.content.trim())
+        .unwrap_or_default();
+    match first.char_indices().nth(TITLE_LENGTH) {
+        Some((end, _)) => format!("{}…", &first[..end]),
+        None => first.to_string(),
+    }
+}
+
+/// Insert or replace the conversation `id`, moving it to the front of the list.
+pub fn upsert_conversation(
+    conversations: &mut Vec<Conversation>,
+    id: &str,
+    messages: &[Message],
+    forks: &[Fork],
+    archived: usize,
+) {
+    let existing = conversations.iter().position(|c| c.id == id).map(|i| conversations.remove(i));
+    // Once the first message is archived the title can't be worked out again
+    let title = match &existing {
+        Some(c) if archived > 0 || c.renamed => c.title.clone(),
+        _ => title_for(messages),
+    };
+    conversations.insert(
+        0,
+        Conversation {
+            id: id.to_string(),
+            title,
+            updated_at: js_sys::Date::now(),
+            messages: messages.to_vec(),
+            forks: forks.to_vec(),
+            archived,
+            archived_at: existing.as_ref().and_then(|c| c.archived_at),
+            tags: existing.as_ref().map(|c| c.tags.clone()).unwrap_or_default(),
+            renamed: existing.is_some_and(|c| c.renamed),
+        },
+    );
+    drop_oldest(conversations);
+}
+
+/// Past the limit the oldest go, along with their archived messages.
+fn drop_oldest(conversations: &mut Vec<Conversation>) {
+    let limit = MAX_CONVERSATIONS.min(conversations.len());
+    for dropped in conversations.drain(limit..) {
+        if dropped.archived > 0 {
+            history::delete(&dropped.id);
+        }
+    }
+}
+
+/// A conversation as the backend keeps it (`GET /conversations`)
+#[derive(Deserialize)]
+pub struct SyncedConversation {
+    pub id: String,
+    #[serde(default)]
+    pub title: String,
+    /// Milliseconds since the epoch
+    pub updated_at: f64,
+    pub history: Vec<wxve_chat_client::Message>,
+}
+
+/// Take in what the backend has that's newer than the copy here, returning the ids of the
+/// conversations that changed. One this device hasn't seen is added; one changed elsewhere
+/// since takes the backend's messages, dropping its archived messages and branches, which
+/// no longer lead up to them. Tags, archiving and a title the user gave are kept, as are
+/// conversations the backend doesn't have. `busy` (a conversation mid-response) is skipped.
+pub fn merge_synced(
+    conversations: &mut Vec<Conversation>,
+    synced: Vec<SyncedConversation>,
+    busy: Option<&str>,
+) -> Vec<String> {
+    let mut changed = Vec::new();
+    for remote in synced {
+        if busy == Some(remote.id.as_str()) {
+            continue;
+        }
+        let messages: Vec<Message> = remote.history.into_iter().map(Message::from).collect();
+        let title = if remote.title.is_empty() { title_for(&messages) } else { remote.title };
+        match conversations.iter_mut().find(|c| c.id == remote.id) {
+            Some(local) if local.updated_at >= remote.updated_at => continue,
+            Some(local) => {
+                if local.archived > 0 {
+                    history::delete(&local.id);
+                }
+                if !local.renamed {
+                    local.title = title;
+                }
+                local.updated_at = remote.updated_at;
+                local.messages = messages;
+                local.forks.clear();
+                local.archived = 0;
+            }
+            None => conversations.push(Conversation {
+                id: remote.id.clone(),
+                title,
+                updated_at: remote.updated_at,
+                messages,
+                forks: Vec::new(),
+                archived: 0,
+                archived_at: None,
+                tags: Vec::new(),
+                renamed: false,
+            }),
+        }
+        changed.push(remote.id);
+    }
+    conversations.sort_by(|a, b| b.updated_at.total_cmp(&a.updated_at));
+    drop_oldest(conversations);
+    changed
+}
+
+/// Give the conversation `id` the user's own title; a blank one goes back to the first
+/// message's, if that's still loaded.
+pub fn rename(conversations: &mut [Conversation], id: &str, title: &str) {
+    let Some(conversation) = conversations.iter_mut().find(|c| c.id == id) else {
+        return;
+    };
+    let title = title.trim();
+    if !title.is_empty() {
+        conversation.title = title.to_string();
+        conversation.renamed = true;
+    } else if conversation.archived == 0 {
+        conversation.title = title_for(&conversation.messages);
+        conversation.renamed = false;
+    }
+}
+
+/// Every tag in use, alphabetically.
+pub fn all_tags(conversations: &[Conversation]) -> Vec<String> {
+    let mut tags: Vec<String> = conversations.iter().flat_map(|c| c.tags.clone()).collect();
+    tags.sort_by_key(|t| t.to_lowercase());
+    tags.dedup();
+    tags
+}
+
+/// Tag the conversation `id` with `tag`, spelled as it already is on other conversations
+/// so "Research" and "research" stay one tag.
+pub fn add_tag(conversations: &mut [Conversation], id: &str, tag: &str) {
+    let tag = tag.split_whitespace().collect::<Vec<_>>().join(" ");
+    let tag: String = tag.chars().take(TAG_LENGTH).collect();
+    if tag.is_empty() {
+        return;
+    }
+    let tag = conversations
+        .iter()
+        .flat_map(|c| &c.tags)
+        .find(|t| t.eq_ignore_ascii_case(&tag))
+        .cloned()
+        .unwrap_or(tag);
+    if let Some(conversation) = conversations.iter_mut().find(|c| c.id == id)
+        && !conversation.tags.contains(&tag)
+    {
+        conversation.tags.push(tag);
+    }
+}
+
+pub fn remove_tag(conversations: &mut [Conversation], id: &str, tag: &str) {
+    if let Some(conversation) = conversations.iter_mut().find(|c| c.id == id) {
+        conversation.tags.retain(|t| t != tag);
+    }
+}
+
+/// Archive or restore the conversation `id`.
+pub fn set_archived(conversations: &mut [Conversation], id: &str, archived: bool) {
+    if let Some(conversation) = conversations.iter_mut().find(|c| c.id == id) {
+        conversation.archived_at = archived.then(js_sys::Date::now);
+    }
+}
@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use leptos::prelude::{
+    component, event_target_value, view, Effect, IntoView, RwSignal, Set, Update, With,
+    WithUntracked,
+};
+use leptos::tachys::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::{fetch_fx_rates, Settings};
+
+/// Display currencies offered in the picker
+pub const CURRENCIES: &[&str] = &["USD", "EUR", "GBP", "JPY", "CAD", "CHF", "AUD", "HKD"];
+
+/// Units of each currency per US dollar.
+pub type FxRates = HashMap<String, f64>;
+
+/// FX rates, fetched the first time a display currency is selected.
+pub fn use_fx_rates(settings: RwSignal<Settings>) -> RwSignal<FxRates> {
+    let rates = RwSignal::new(FxRates::new());
+    Effect::new(move |_| {
+        let wanted = settings.with(|s| s.display_currency.is_some());
+        if wanted && rates.with_untracked(HashMap::is_empty) {
+            spawn_local(async move {
+                match fetch_fx_rates().await {
+                    Ok(fetched) => rates.set(fetched),
+                    Err(e) => log::error!(target: "transport", "FX fetch failed: {e}"),
+                }
+            });
+        }
+    });
+    rates
+}
+
+fn rate(rates: &FxRates, currency: &str) -> Option<f64> {
+    match currency {
+        "USD" => Some(1.0),
+        _ => rates.get(currency).copied(),
+    }
+}
+
+/// `amount` in `from` expressed in `to`, if both rates are known.
+pub fn convert(amount: f64, from: &str, to: &str, rates: &FxRates) -> Option<f64> {
+    if from == to {
+        return Some(amount);
+    }
+    Some(amount / rate(rates, from)? * rate(rates, to)?)
+}
+
+fn currency_symbol(code: &str) -> String {
+    match code {
+        "USD" => "$".to_string(),
+        "EUR" => "€".to_string(),
+        "GBP" => "£".to_string(),
+        "JPY" => "¥".to_string(),
+        _ => format!("{code} "),
+    }
+}
+
+/// A quoted price in the user's display currency, with the original on hover.
+#[component]
+pub fn Price(
+    amount: f64,
+    currency: String,
+    settings: RwSignal<Settings>,
+    rates: RwSignal<FxRates>,
+) -> impl IntoView {
+    let original = format!("{amount:.2} {currency}");
+    let text = move || {
+        let target = settings.with(|s| s.display_currency.clone());
+        let converted = target.and_then(|to| {
+            let value = rates.with(|r| convert(amount, &currency, &to, r))?;
+            Some(format!("{}{value:.2}", currency_symbol(&to)))
+        });
+        converted.unwrap_or_else(|| format!("{amount:.2}"))
+    };
+
+    view! { <span class="price" title=original>{text}</span> }
+}
+
+/// Picker for `Settings::display_currency`; the empty option keeps quotes as listed.
+#[component]
+pub fn CurrencySelect(settings: RwSignal<Settings>) -> impl IntoView {
+    let selected = move |code: &str| settings.with(|s| s.display_currency.as_deref() == Some(code));
+
+    view! {
+        <select
+            class="panel-option currency-select"
+            title="Display currency"
+            on:change=move |ev| {
+                let code = event_target_value(&ev);
+                settings.update(|s| s.display_currency = (!code.is_empty()).then_some(code));
+            }
+        >
+            <option value="" selected=move || settings.with(|s| s.display_currency.is_none())>
+                "Native"
+            </option>
+            {CURRENCIES.iter().map(|&code| view! {
+                <option value=code selected=move || selected(code)>{code}</option>
+            }).collect::<Vec<_>>()}
+        </select>
+    }
+}
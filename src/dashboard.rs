@@ -0,0 +1,244 @@
+use std::collections::HashMap;
+
+use leptos::prelude::{
+    component, event_target_value, signal, view, For, Get, GetUntracked, IntoView, Memo, ReadSignal,
+    RwSignal, Set, Update, With,
+};
+use leptos::tachys::prelude::*;
+use leptos::task::spawn_local;
+use serde::{Deserialize, Serialize};
+use wxve_chat_client::ChartParams;
+
+use crate::currency::{FxRates, Price};
+use crate::watchlist::{format_change, normalize_symbol, poll_quotes};
+use crate::{load_json, new_id, render_chart, save_json, Chart, ChartView, Quote, Settings};
+
+const DASHBOARD_KEY: &str = "wxve-chat:dashboard";
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Widget {
+    Chart { symbol: String },
+    /// Live quotes for the watchlist
+    Quotes,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tile {
+    pub id: String,
+    pub widget: Widget,
+}
+
+/// Dashboard tiles in display order.
+pub fn load_tiles() -> Vec<Tile> {
+    load_json(DASHBOARD_KEY).unwrap_or_default()
+}
+
+pub fn save_tiles(tiles: &[Tile]) {
+    save_json(DASHBOARD_KEY, &tiles);
+}
+
+/// Add a chart tile for `symbol` unless one is already pinned.
+pub fn pin_chart(tiles: RwSignal<Vec<Tile>>, symbol: String) {
+    tiles.update(|t| {
+        let widget = Widget::Chart { symbol };
+        if !t.iter().any(|tile| tile.widget == widget) {
+            t.push(Tile { id: new_id(), widget });
+        }
+    });
+}
+
+/// Move the tile at `from` so it lands at `to`.
+fn move_tile(tiles: &mut Vec<Tile>, from: usize, to: usize) {
+    if from < tiles.len() && to < tiles.len() && from != to {
+        let tile = tiles.remove(from);
+        tiles.insert(to, tile);
+    }
+}
+
+/// `/dashboard` - pinned charts and widgets in a grid, rearranged by dragging.
+#[component]
+pub fn Dashboard(
+    tiles: RwSignal<Vec<Tile>>,
+    settings: RwSignal<Settings>,
+    quotes: RwSignal<HashMap<String, Quote>>,
+    rates: RwSignal<FxRates>,
+    dark_mode: ReadSignal<bool>,
+) -> impl IntoView {
+    let (draft, set_draft) = signal(String::new());
+    let (dragging, set_dragging) = signal(None::<String>);
+
+    let add_chart = move || {
+        if let Some(symbol) = normalize_symbol(&draft.get()) {
+            pin_chart(tiles, symbol);
+            set_draft.set(String::new());
+        }
+    };
+    let add_quotes = move |_| {
+        tiles.update(|t| {
+            if !t.iter().any(|tile| tile.widget == Widget::Quotes) {
+                t.push(Tile { id: new_id(), widget: Widget::Quotes });
+            }
+        });
+    };
+    let drop_on = move |target: String| {
+        let Some(source) = dragging.get_untracked() else { return };
+        set_dragging.set(None);
+        tiles.update(|t| {
+            let from = t.iter().position(|tile| tile.id == source);
+            let to = t.iter().position(|tile| tile.id == target);
+            if let (Some(from), Some(to)) = (from, to) {
+                move_tile(t, from, to);
+            }
+        });
+    };
+
+    view! {
+        <div class="page dashboard">
+            <div class="page-title">"Dashboard"</div>
+            <form class="dashboard-toolbar" on:submit=move |ev| {
+                ev.prevent_default();
+                add_chart();
+            }>
+                <input
+                    type="text"
+                    placeholder="Pin a chart, e.g. NVDA"
+                    prop:value=move || draft.get()
+                    on:input=move |ev| set_draft.set(event_target_value(&ev))
+                />
+                <button type="submit">"Add chart"</button>
+                <button type="button" on:click=add_quotes>"Add quotes"</button>
+            </form>
+            {move || tiles.with(Vec::is_empty).then(|| view! {
+                <p class="panel-empty">"Pin a chart from any analysis, or add one above."</p>
+            })}
+            <div class="dashboard-grid">
+                <For
+                    each=move || tiles.get()
+                    key=|tile| tile.id.clone()
+                    children=move |tile| {
+                        let Tile { id, widget } = tile;
+                        let drag_id = id.clone();
+                        let drop_id = id.clone();
+                        let is_dragging = {
+                            let id = id.clone();
+                            move || dragging.get().as_deref() == Some(id.as_str())
+                        };
+                        let wide = matches!(widget, Widget::Chart { .. });
+                        view! {
+                            <div
+                                class="dashboard-tile"
+                                class:wide=wide
+                                class:dragging=is_dragging
+                                draggable="true"
+                                on:dragstart=move |ev| {
+                                    // Firefox won't start a drag without data
+                                    if let Some(transfer) = ev.data_transfer() {
+                                        let _ = transfer.set_data("text/plain", &drag_id);
+                                    }
+                                    set_dragging.set(Some(drag_id.clone()));
+                                }
+                                on:dragend=move |_| set_dragging.set(None)
+                                on:dragover=|ev| ev.prevent_default()
+                                on:drop=move |ev| {
+                                    ev.prevent_default();
+                                    drop_on(drop_id.clone());
+                                }
+                            >
+                                <button
+                                    class="panel-remove dashboard-remove"
+                                    title="Remove"
+                                    on:click=move |_| tiles.update(|t| t.retain(|tile| tile.id != id))
+                                >
+                                    "×"
+                                </button>
+                                {match widget {
+                                    Widget::Chart { symbol } => view! {
+                                        <ChartTile symbol=symbol settings=settings dark_mode=dark_mode/>
+                                    }.into_any(),
+                                    Widget::Quotes => view! {
+                                        <QuotesTile settings=settings quotes=quotes rates=rates/>
+                                    }.into_any(),
+                                }}
+                            </div>
+                        }
+                    }
+                />
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn ChartTile(
+    symbol: String,
+    settings: RwSignal<Settings>,
+    dark_mode: ReadSignal<bool>,
+) -> impl IntoView {
+    let (chart, set_chart) = signal(None::<Chart>);
+
+    {
+        let symbol = symbol.clone();
+        let indicators = settings.get_untracked().indicators;
+        let params = ChartParams::default();
+        spawn_local(async move {
+            match render_chart(symbol.clone(), indicators.clone(), params.clone()).await {
+                Ok(html) => set_chart.set(Some(Chart::new(symbol, html, indicators, params))),
+                Err(e) => log::error!(target: "ui", "Chart render failed: {e}"),
+            }
+        });
+    }
+
+    view! {
+        <div class="dashboard-tile-title">{symbol}</div>
+        {move || match chart.get() {
+            Some(chart) => view! {
+                <ChartView chart=chart settings=settings dark_mode=dark_mode/>
+            }
+            .into_any(),
+            None => view! { <span class="spinner"></span> }.into_any(),
+        }}
+    }
+}
+
+#[component]
+fn QuotesTile(
+    settings: RwSignal<Settings>,
+    quotes: RwSignal<HashMap<String, Quote>>,
+    rates: RwSignal<FxRates>,
+) -> impl IntoView {
+    let symbols = Memo::new(move |_| settings.with(|s| s.watchlist.clone()));
+
+    poll_quotes(symbols, quotes);
+
+    view! {
+        <div class="dashboard-tile-title">"Watchlist"</div>
+        {move || symbols.with(Vec::is_empty).then(|| view! {
+            <p class="panel-empty">"Your watchlist is empty."</p>
+        })}
+        <ul class="panel-items">
+            <For
+                each=move || symbols.get()
+                key=|symbol| symbol.clone()
+                children=move |symbol| {
+                    let quote = {
+                        let symbol = symbol.clone();
+                        move || quotes.with(|q| q.get(&symbol).cloned())
+                    };
+                    view! {
+                        <li class="dashboard-quote">
+                            <span>{symbol}</span>
+                            {move || quote().map(|q| {
+                                let (change, class) = format_change(q.change_percent);
+                                view! {
+                                    <Price amount=q.price currency=q.currency settings=settings rates=rates/>
+                                    <span class=class>{change}</span>
+                                }
+                            })}
+                        </li>
+                    }
+                }
+            />
+        </ul>
+    }
+}
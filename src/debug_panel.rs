@@ -0,0 +1,233 @@
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+
+use leptos::prelude::{
+    component, event_target_checked, signal, view, For, Get, IntoView,
+    RwSignal, Set, Update, With,
+};
+use leptos::tachys::prelude::*;
+
+use crate::account::download;
+use crate::replay::ReplayControls;
+use crate::{api_base, toast, window_listener};
+
+/// Older entries are dropped once the log holds this many
+const MAX_ENTRIES: usize = 1_000;
+
+/// Longer lines and chunks are cut; chart chunks carry whole HTML documents
+const MAX_TEXT: usize = 2_000;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Kind {
+    Open,
+    Line,
+    Parsed,
+    Failed,
+    Close,
+}
+
+impl Kind {
+    fn label(self) -> &'static str {
+        match self {
+            Kind::Open => "open",
+            Kind::Line => "line",
+            Kind::Parsed => "parsed",
+            Kind::Failed => "failed",
+            Kind::Close => "close",
+        }
+    }
+
+    fn class(self) -> &'static str {
+        match self {
+            Kind::Open => "debug-entry open",
+            Kind::Line => "debug-entry line",
+            Kind::Parsed => "debug-entry parsed",
+            Kind::Failed => "debug-entry failed",
+            Kind::Close => "debug-entry close",
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Entry {
+    id: usize,
+    stream: usize,
+    path: String,
+    /// Milliseconds since the stream was opened
+    elapsed: f64,
+    kind: Kind,
+    text: String,
+}
+
+impl Entry {
+    fn to_line(&self) -> String {
+        format!(
+            "#{} {} +{:.0}ms {}: {}",
+            self.stream,
+            self.path,
+            self.elapsed,
+            self.kind.label(),
+            self.text
+        )
+    }
+}
+
+thread_local! {
+    /// Registered by the mounted `DebugPanel`; nothing is recorded before that
+    static ENTRIES: Cell<Option<RwSignal<VecDeque<Entry>>>> = const { Cell::new(None) };
+    static NEXT_ID: Cell<usize> = const { Cell::new(0) };
+}
+
+fn next_id() -> usize {
+    NEXT_ID.with(|n| {
+        let id = n.get();
+        n.set(id + 1);
+        id
+    })
+}
+
+fn truncate(text: String) -> String {
+    match text.char_indices().nth(MAX_TEXT) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text,
+    }
+}
+
+/// Everything read from one SSE response, for the debug panel.
+pub struct StreamLog {
+    stream: usize,
+    path: String,
+    started: f64,
+}
+
+impl StreamLog {
+    pub fn open(url: &str, status: u16) -> Self {
+        let log = StreamLog {
+            stream: next_id(),
+            path: url.strip_prefix(&api_base()).unwrap_or(url).to_string(),
+            started: js_sys::Date::now(),
+        };
+        log.record(Kind::Open, || format!("HTTP {status}"));
+        log
+    }
+
+    pub fn line(&self, line: &str) {
+        self.record(Kind::Line, || line.to_string());
+    }
+
+    pub fn parsed(&self, event: &dyn Debug) {
+        self.record(Kind::Parsed, || format!("{event:?}"));
+    }
+
+    pub fn failed(&self, error: &str) {
+        self.record(Kind::Failed, || error.to_string());
+    }
+
+    pub fn close(&self, result: &Result<(), String>) {
+        self.record(Kind::Close, || match result {
+            Ok(()) => "ended".to_string(),
+            Err(e) => e.clone(),
+        });
+    }
+
+    /// `text` is only built while the panel is mounted.
+    fn record(&self, kind: Kind, text: impl FnOnce() -> String) {
+        let Some(entries) = ENTRIES.with(Cell::get) else {
+            return;
+        };
+        let entry = Entry {
+            id: next_id(),
+            stream: self.stream,
+            path: self.path.clone(),
+            elapsed: js_sys::Date::now() - self.started,
+            kind,
+            text: truncate(text()),
+        };
+        entries.update(|e| {
+            if e.len() == MAX_ENTRIES {
+                e.pop_front();
+            }
+            e.push_back(entry);
+        });
+    }
+}
+
+/// True for the shortcut that shows and hides the panel.
+fn is_toggle(ev: &web_sys::KeyboardEvent) -> bool {
+    ev.ctrl_key() && ev.shift_key() && ev.key().eq_ignore_ascii_case("d")
+}
+
+/// Hidden panel listing every SSE line received, how it parsed and when, for
+/// diagnosing backend protocol issues. Records from mount, so it can be opened
+/// after something went wrong; mount once near the root.
+#[component]
+pub fn DebugPanel() -> impl IntoView {
+    let entries = RwSignal::new(VecDeque::<Entry>::new());
+    ENTRIES.with(|e| e.set(Some(entries)));
+    let (open, set_open) = signal(false);
+    let (show_lines, set_show_lines) = signal(true);
+
+    window_listener(leptos::ev::keydown, move |ev| {
+        if is_toggle(&ev) {
+            ev.prevent_default();
+            set_open.update(|o| *o = !*o);
+        }
+    });
+
+    let save = move |_| {
+        let log = entries.with(|e| e.iter().map(Entry::to_line).collect::<Vec<_>>().join("\n"));
+        if let Err(e) = download("wxve-chat-stream.log", &log, "text/plain") {
+            toast::error(format!("Couldn't save the log: {e}"));
+        }
+    };
+
+    move || {
+        open.get().then(|| view! {
+            <aside class="debug-panel" aria-label="Stream debug">
+                <div class="debug-header">
+                    <h3>"Streams"</h3>
+                    <span class="debug-count">{move || format!("{} entries", entries.with(VecDeque::len))}</span>
+                    <label class="debug-option">
+                        <input
+                            type="checkbox"
+                            prop:checked=show_lines
+                            on:change=move |ev| set_show_lines.set(event_target_checked(&ev))
+                        />
+                        "Raw lines"
+                    </label>
+                    <ReplayControls/>
+                    <button class="dialog-button" on:click=save>"Save"</button>
+                    <button class="dialog-button" on:click=move |_| entries.set(VecDeque::new())>
+                        "Clear"
+                    </button>
+                    <button class="debug-close" title="Close" on:click=move |_| set_open.set(false)>
+                        "×"
+                    </button>
+                </div>
+                <div class="debug-entries">
+                    <For
+                        each=move || {
+                            let show_lines = show_lines.get();
+                            entries.with(|e| {
+                                e.iter()
+                                    .filter(|entry| show_lines || entry.kind != Kind::Line)
+                                    .cloned()
+                                    .collect::<Vec<_>>()
+                            })
+                        }
+                        key=|entry| entry.id
+                        children=|entry| view! {
+                            <div class=entry.kind.class()>
+                                <span class="debug-stream">{format!("#{} {}", entry.stream, entry.path)}</span>
+                                <span class="debug-time">{format!("+{:.0}ms", entry.elapsed)}</span>
+                                <span class="debug-kind">{entry.kind.label()}</span>
+                                <code class="debug-text">{entry.text}</code>
+                            </div>
+                        }
+                    />
+                </div>
+            </aside>
+        })
+    }
+}
@@ -0,0 +1,50 @@
+use leptos::prelude::{component, view, Get, IntoView, Memo, With};
+use leptos::tachys::prelude::*;
+
+use crate::state::use_app_state;
+
+/// How far a user message got on its way to the backend. Not saved: a reloaded
+/// conversation shows none.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Delivery {
+    Sending,
+    /// The backend started answering
+    Delivered,
+    /// The request failed before anything came back; sending it again reuses its key
+    Failed { idempotency_key: String },
+}
+
+/// Sending / delivered / failed under a user message, with Retry when it failed.
+#[component]
+pub fn DeliveryStatus(id: usize) -> impl IntoView {
+    let state = use_app_state();
+    let delivery = Memo::new(move |_| {
+        state.messages.with(|m| m.iter().find(|m| m.id == id).and_then(|m| m.delivery.clone()))
+    });
+
+    move || {
+        delivery.get().map(|delivery| match delivery {
+            Delivery::Sending => view! {
+                <span class="delivery sending" title="Sending">"Sending…"</span>
+            }
+            .into_any(),
+            Delivery::Delivered => view! {
+                <span class="delivery delivered" title="Delivered">"✓"</span>
+            }
+            .into_any(),
+            Delivery::Failed { .. } => view! {
+                <span class="delivery failed">
+                    "Not sent"
+                    <button
+                        class="delivery-retry"
+                        disabled=state.loading
+                        on:click=move |_| state.retry(id)
+                    >
+                        "Retry"
+                    </button>
+                </span>
+            }
+            .into_any(),
+        })
+    }
+}
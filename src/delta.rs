@@ -0,0 +1,38 @@
+use wxve_chat_client::{ChatRequest, Message, Role};
+
+/// What the backend holds for a conversation after a turn finished, so the next turn can
+/// send just its message (`conversation_history` in `/config`).
+#[derive(Clone)]
+pub struct Held {
+    conversation_id: String,
+    /// `AppState.archived` when it was sent; messages archived since are still held there
+    archived: usize,
+    messages: Vec<Message>,
+}
+
+impl Held {
+    /// What the backend holds once `request`, sent in full with `archived` messages archived,
+    /// has been answered with `reply`. `None` if the request didn't name a conversation.
+    pub fn after(request: &ChatRequest, archived: usize, reply: Message) -> Option<Held> {
+        let conversation_id = request.conversation_id.clone()?;
+        let mut messages = request.history.clone()?;
+        messages.push(Message {
+            role: Role::User,
+            content: request.message.clone(),
+            quoted: request.quoted.clone(),
+            charts: Vec::new(),
+        });
+        messages.push(reply);
+        Some(Held { conversation_id, archived, messages })
+    }
+
+    /// `request` without its history, if the backend holds exactly that history already:
+    /// nothing was edited, deleted or branched since, only archived.
+    pub fn delta(&self, request: &ChatRequest, archived: usize) -> Option<ChatRequest> {
+        let history = request.history.as_deref()?;
+        let newly_archived = archived.checked_sub(self.archived)?;
+        let holds = request.conversation_id.as_ref() == Some(&self.conversation_id)
+            && self.messages.get(newly_archived..) == Some(history);
+        holds.then(|| ChatRequest { history: None, ..request.clone() })
+    }
+}
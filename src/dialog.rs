@@ -0,0 +1,264 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use leptos::prelude::{
+    component, on_cleanup, request_animation_frame, view, AnyView, Callable,
+    Callback, For, Get, IntoView, LocalStorage, RwSignal, Update, WithUntracked,
+};
+use leptos::tachys::prelude::*;
+use send_wrapper::SendWrapper;
+use wasm_bindgen::JsCast;
+
+use crate::{element, new_id, query_all, window_listener};
+
+const FOCUSABLE: &str =
+    "button, [href], input, select, textarea, [tabindex]:not([tabindex='-1'])";
+
+/// Something that can be shown in a dialog; open it with `dialog::open()`.
+pub trait Dialog {
+    fn title(&self) -> String;
+
+    /// Dialog content. `close` pops this dialog off the stack.
+    fn body(&self, close: Callback<()>) -> AnyView;
+
+    /// Extra class on the dialog box, e.g. for sizing
+    fn class(&self) -> &'static str {
+        ""
+    }
+
+    /// Called however the dialog closes (button, Esc or backdrop)
+    fn on_close(&self) {}
+}
+
+#[derive(Clone)]
+struct Entry {
+    id: String,
+    dialog: Rc<dyn Dialog>,
+    /// Element that had focus when the dialog opened, refocused when it closes
+    return_focus: Option<web_sys::HtmlElement>,
+}
+
+thread_local! {
+    /// Registered by the mounted `DialogHost`
+    static STACK: Cell<Option<RwSignal<Vec<Entry>, LocalStorage>>> = const { Cell::new(None) };
+}
+
+/// The focused element in the app. Inside the `<wxve-chat>` shadow root the document only
+/// sees the host element as focused.
+fn active_element() -> Option<web_sys::HtmlElement> {
+    let active = match element::shadow_root() {
+        Some(root) => root.active_element(),
+        None => web_sys::window()?.document()?.active_element(),
+    };
+    active?.dyn_into().ok()
+}
+
+fn element_id(id: &str) -> String {
+    format!("dialog-{id}")
+}
+
+fn focusable_in(id: &str) -> Vec<web_sys::HtmlElement> {
+    let Some(dialog) = query_all(&format!("#{}", element_id(id))).pop() else {
+        return Vec::new();
+    };
+    let Ok(nodes) = dialog.query_selector_all(FOCUSABLE) else {
+        return Vec::new();
+    };
+    (0..nodes.length())
+        .filter_map(|i| nodes.get(i)?.dyn_into::<web_sys::HtmlElement>().ok())
+        .collect()
+}
+
+/// Open `dialog` on top of any others, returning its id for `close()`.
+pub fn open(dialog: impl Dialog + 'static) -> String {
+    let id = new_id();
+    if let Some(stack) = STACK.with(Cell::get) {
+        let entry = Entry { id: id.clone(), dialog: Rc::new(dialog), return_focus: active_element() };
+        stack.update(|s| s.push(entry));
+        let focus_id = id.clone();
+        // Prefer an `autofocus` element, e.g. a confirm button
+        request_animation_frame(move || {
+            let focusable = focusable_in(&focus_id);
+            let preferred = focusable.iter().find(|el| el.has_attribute("autofocus"));
+            if let Some(el) = preferred.or(focusable.first()) {
+                let _ = el.focus();
+            }
+        });
+    }
+    id
+}
+
+/// Close dialog `id` when `signal` aborts, as if it was cancelled.
+pub fn close_on_abort(id: String, signal: &web_sys::AbortSignal) {
+    let close = wasm_bindgen::closure::Closure::once_into_js(move || close(&id));
+    let _ = signal.add_event_listener_with_callback("abort", close.unchecked_ref());
+}
+
+/// True while any dialog is open, so page-wide hotkeys can stand down.
+pub fn is_open() -> bool {
+    STACK.with(Cell::get).is_some_and(|stack| stack.with_untracked(|s| !s.is_empty()))
+}
+
+pub fn close(id: &str) {
+    let Some(stack) = STACK.with(Cell::get) else {
+        return;
+    };
+    let mut closed = None;
+    stack.update(|s| {
+        if let Some(pos) = s.iter().position(|e| e.id == id) {
+            closed = Some(s.remove(pos));
+        }
+    });
+    if let Some(entry) = closed {
+        entry.dialog.on_close();
+        if let Some(element) = entry.return_focus {
+            let _ = element.focus();
+        }
+    }
+}
+
+struct Confirm {
+    message: String,
+    /// Resolves the promise `confirm()` awaits; only the first call counts
+    resolve: js_sys::Function,
+}
+
+impl Confirm {
+    fn answer(&self, confirmed: bool) {
+        let _ = self.resolve.call1(&wasm_bindgen::JsValue::NULL, &confirmed.into());
+    }
+}
+
+impl Dialog for Confirm {
+    fn title(&self) -> String {
+        "Are you sure?".to_string()
+    }
+
+    fn body(&self, close: Callback<()>) -> AnyView {
+        let resolve = self.resolve.clone();
+        let accept = move |_| {
+            let _ = resolve.call1(&wasm_bindgen::JsValue::NULL, &true.into());
+            close.run(());
+        };
+        view! {
+            <p class="dialog-message">{self.message.clone()}</p>
+            <div class="dialog-actions">
+                <button class="dialog-button" on:click=move |_| close.run(())>"Cancel"</button>
+                <button class="dialog-button danger" autofocus=true on:click=accept>"Confirm"</button>
+            </div>
+        }
+        .into_any()
+    }
+
+    fn class(&self) -> &'static str {
+        "confirm"
+    }
+
+    fn on_close(&self) {
+        self.answer(false);
+    }
+}
+
+/// Ask before a destructive action. Enter confirms, Esc or the backdrop cancels.
+pub async fn confirm(message: impl Into<String>) -> bool {
+    // Nothing could ever answer without a host, so treat it as cancelled
+    if STACK.with(Cell::get).is_none() {
+        return false;
+    }
+    let message = message.into();
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        open(Confirm { message: message.clone(), resolve });
+    });
+    wasm_bindgen_futures::JsFuture::from(promise)
+        .await
+        .ok()
+        .and_then(|answer| answer.as_bool())
+        .unwrap_or(false)
+}
+
+/// Keep Tab and Shift+Tab cycling inside the top dialog.
+fn trap_focus(id: &str, ev: &web_sys::KeyboardEvent) {
+    let focusable = focusable_in(id);
+    let (Some(first), Some(last)) = (focusable.first(), focusable.last()) else {
+        ev.prevent_default();
+        return;
+    };
+    let active = active_element();
+    let inside = active.as_ref().is_some_and(|a| focusable.contains(a));
+    let target = match (ev.shift_key(), active) {
+        (_, _) if !inside => Some(first),
+        (true, Some(a)) if a == *first => Some(last),
+        (false, Some(a)) if a == *last => Some(first),
+        _ => None,
+    };
+    if let Some(target) = target {
+        ev.prevent_default();
+        let _ = target.focus();
+    }
+}
+
+/// Renders the dialog stack; mount once near the root.
+#[component]
+pub fn DialogHost() -> impl IntoView {
+    let stack = RwSignal::new_local(Vec::<Entry>::new());
+    STACK.with(|s| s.set(Some(stack)));
+    on_cleanup(|| STACK.with(|s| s.set(None)));
+
+    window_listener(leptos::ev::keydown, move |ev| {
+        let Some(top) = stack.with_untracked(|s| s.last().map(|e| e.id.clone())) else {
+            return;
+        };
+        match ev.key().as_str() {
+            "Escape" => {
+                ev.prevent_default();
+                close(&top);
+            }
+            "Tab" => trap_focus(&top, &ev),
+            _ => {}
+        }
+    });
+
+    // `For` items must be `Send`; entries are only ever touched on this thread
+    let entries = move || stack.get().into_iter().map(SendWrapper::new).collect::<Vec<_>>();
+
+    view! {
+        <For
+            each=entries
+            key=|entry| entry.id.clone()
+            children=move |entry| {
+                let depth = stack.with_untracked(Vec::len);
+                let id = entry.id.clone();
+                let close_cb = Callback::new(move |()| close(&id));
+                let title_id = format!("{}-title", element_id(&entry.id));
+                view! {
+                    <div
+                        class="dialog-overlay"
+                        style=format!("z-index: {}", 40 + depth)
+                        on:click=move |ev| {
+                            // Only clicks on the backdrop itself, not inside the dialog
+                            if ev.target() == ev.current_target() {
+                                close_cb.run(());
+                            }
+                        }
+                    >
+                        <div
+                            id=element_id(&entry.id)
+                            class=format!("dialog {}", entry.dialog.class())
+                            role="dialog"
+                            aria-modal="true"
+                            aria-labelledby=title_id.clone()
+                        >
+                            <div class="dialog-header">
+                                <h2 id=title_id.clone()>{entry.dialog.title()}</h2>
+                                <button class="panel-remove" title="Close" on:click=move |_| close_cb.run(())>
+                                    "×"
+                                </button>
+                            </div>
+                            {entry.dialog.body(close_cb)}
+                        </div>
+                    </div>
+                }
+            }
+        />
+    }
+}
@@ -0,0 +1,88 @@
+use leptos::prelude::{
+    component, view, Callable, Callback, Effect, For, Get, IntoView, Memo, RwSignal, Set, With,
+};
+use leptos::tachys::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::{fetch_earnings, EarningsReport};
+
+/// Format a revenue estimate in billions/millions, e.g. `$94.3B`.
+fn format_revenue(revenue: f64) -> String {
+    if revenue >= 1e9 {
+        format!("${:.1}B", revenue / 1e9)
+    } else {
+        format!("${:.0}M", revenue / 1e6)
+    }
+}
+
+/// Upcoming earnings for the watchlist, soonest first.
+#[component]
+pub fn EarningsCalendar(
+    symbols: Memo<Vec<String>>,
+    on_setup: Callback<EarningsReport>,
+) -> impl IntoView {
+    let reports = RwSignal::new(Vec::<EarningsReport>::new());
+
+    Effect::new(move |_| {
+        let symbols = symbols.get();
+        if symbols.is_empty() {
+            reports.set(Vec::new());
+            return;
+        }
+        spawn_local(async move {
+            match fetch_earnings(&symbols).await {
+                Ok(mut fetched) => {
+                    // ISO dates sort chronologically as strings
+                    fetched.sort_by(|a, b| a.date.cmp(&b.date));
+                    reports.set(fetched);
+                }
+                Err(e) => log::error!(target: "transport", "Earnings fetch failed: {e}"),
+            }
+        });
+    });
+
+    move || {
+        (!reports.with(Vec::is_empty)).then(|| view! {
+            <div class="earnings">
+                <div class="panel-header">"Upcoming earnings"</div>
+                <ul class="panel-items">
+                    <For
+                        each=move || reports.get()
+                        key=|report| (report.symbol.clone(), report.date.clone())
+                        children=move |report| {
+                            let when = match &report.time {
+                                Some(time) => format!("{} {}", report.date, time.to_uppercase()),
+                                None => report.date.clone(),
+                            };
+                            let estimates = [
+                                report.eps_estimate.map(|eps| format!("EPS {eps:.2}")),
+                                report.revenue_estimate.map(format_revenue),
+                            ]
+                            .into_iter()
+                            .flatten()
+                            .collect::<Vec<_>>()
+                            .join(" · ");
+                            let symbol = report.symbol.clone();
+                            view! {
+                                <li class="earnings-item">
+                                    <div class="earnings-row">
+                                        <span class="earnings-symbol">{symbol}</span>
+                                        <span class="earnings-date">{when}</span>
+                                        <button
+                                            class="earnings-setup"
+                                            title="Ask Xve for a pre-earnings wave setup"
+                                            on:click=move |_| on_setup.run(report.clone())
+                                        >
+                                            "Setup"
+                                        </button>
+                                    </div>
+                                    <div class="earnings-estimates">{estimates}</div>
+                                </li>
+                            }
+                        }
+                    />
+                </ul>
+            </div>
+        })
+    }
+}
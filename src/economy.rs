@@ -0,0 +1,95 @@
+use leptos::prelude::{
+    component, event_target_value, view, Callable, Callback, For, Get, GetValue, IntoView, Memo,
+    RwSignal, Set, StoredValue, With,
+};
+use leptos::tachys::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::{fetch_economic_calendar, toggle_panel, EconomicEvent, Panel};
+
+/// Upcoming macro events with a per-event "how does this affect X?" prompt.
+#[component]
+pub fn EconomicCalendar(
+    /// Symbols the user holds (portfolio first, then watchlist)
+    held: Memo<Vec<String>>,
+    open_panel: RwSignal<Option<Panel>>,
+    on_ask: Callback<String>,
+) -> impl IntoView {
+    let events = RwSignal::new(Vec::<EconomicEvent>::new());
+
+    spawn_local(async move {
+        match fetch_economic_calendar().await {
+            Ok(fetched) => events.set(fetched),
+            Err(e) => log::error!(target: "transport", "Calendar fetch failed: {e}"),
+        }
+    });
+
+    view! {
+        <button
+            class="icon-btn economy-toggle"
+            title="Economic calendar"
+            on:click=move |_| toggle_panel(open_panel, Panel::Economy)
+        >
+            "📅"
+        </button>
+        <aside class="panel economy" class:open=move || open_panel.get() == Some(Panel::Economy)>
+            <div class="panel-header">"Economic calendar"</div>
+            {move || events.with(Vec::is_empty).then(|| view! {
+                <p class="panel-empty">"No upcoming events."</p>
+            })}
+            <ul class="panel-items">
+                <For
+                    each=move || events.get()
+                    key=|event| (event.name.clone(), event.date.clone())
+                    children=move |event| {
+                        let selected = RwSignal::new(String::new());
+                        let details = [
+                            event.forecast.as_ref().map(|f| format!("forecast {f}")),
+                            event.previous.as_ref().map(|p| format!("prev {p}")),
+                        ]
+                        .into_iter()
+                        .flatten()
+                        .collect::<Vec<_>>()
+                        .join(" · ");
+                        let when = match &event.time {
+                            Some(time) => format!("{} {time}", event.date),
+                            None => event.date.clone(),
+                        };
+                        let prompt = StoredValue::new((event.name.clone(), event.date.clone()));
+                        let ask = move || {
+                            let symbol = match selected.get() {
+                                s if s.is_empty() => held.with(|h| h.first().cloned()),
+                                s => Some(s),
+                            };
+                            if let Some(symbol) = symbol {
+                                let (name, date) = prompt.get_value();
+                                on_ask.run(format!("How might the {name} on {date} affect {symbol}?"));
+                            }
+                        };
+                        view! {
+                            <li class="economy-event" class:high=event.importance == "high">
+                                <div class="economy-row">
+                                    <span class="economy-name">{event.name.clone()}</span>
+                                    <span class="economy-date">{when}</span>
+                                </div>
+                                <div class="economy-details">{details}</div>
+                                {move || (!held.with(Vec::is_empty)).then(|| view! {
+                                    <div class="economy-ask">
+                                        <select on:change=move |ev| {
+                                            selected.set(event_target_value(&ev));
+                                        }>
+                                            {held.get().into_iter().map(|symbol| view! {
+                                                <option value=symbol.clone()>{symbol.clone()}</option>
+                                            }).collect::<Vec<_>>()}
+                                        </select>
+                                        <button on:click=move |_| ask()>"Ask impact"</button>
+                                    </div>
+                                })}
+                            </li>
+                        }
+                    }
+                />
+            </ul>
+        </aside>
+    }
+}
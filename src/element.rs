@@ -0,0 +1,145 @@
+use std::any::Any;
+use std::cell::RefCell;
+
+use leptos::mount::mount_to;
+use leptos::prelude::{provide_context, view};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::App;
+
+/// Tag the custom element is registered under
+const TAG: &str = "wxve-chat";
+
+#[wasm_bindgen(inline_js = r#"
+export function define(tag, connected, disconnected) {
+    if (customElements.get(tag)) {
+        return;
+    }
+    customElements.define(tag, class extends HTMLElement {
+        connectedCallback() {
+            const root = this.shadowRoot || this.attachShadow({ mode: "open" });
+            root.replaceChildren();
+            const stylesheet = (globalThis.XveChatElement || {}).stylesheet;
+            if (stylesheet) {
+                const link = document.createElement("link");
+                link.rel = "stylesheet";
+                link.href = stylesheet;
+                root.append(link);
+            }
+            const mount = document.createElement("div");
+            mount.style.height = "100%";
+            root.append(mount);
+            connected(this, mount);
+        }
+        disconnectedCallback() {
+            disconnected(this);
+        }
+    });
+}
+
+export function isElementBuild() {
+    return globalThis.XveChatElement !== undefined;
+}
+"#)]
+extern "C" {
+    fn define(
+        tag: &str,
+        connected: &Closure<dyn FnMut(web_sys::HtmlElement, web_sys::HtmlElement)>,
+        disconnected: &Closure<dyn FnMut(web_sys::HtmlElement)>,
+    );
+
+    /// True when the bundle was loaded by `element.js` into another site, where only the
+    /// custom element should render, rather than as the chat page itself.
+    #[wasm_bindgen(js_name = isElementBuild)]
+    pub fn is_element() -> bool;
+}
+
+/// Attributes of the mounted `<wxve-chat>`, provided to `App` through context
+#[derive(Clone)]
+pub struct ElementConfig {
+    /// The custom element; its shadow root holds the app
+    pub host: web_sys::HtmlElement,
+    /// `theme="dark"` or `theme="light"`; otherwise the app's default
+    pub dark: Option<bool>,
+    /// Put in the composer on load: `prompt`, or a question about `symbol`
+    pub prompt: Option<String>,
+}
+
+impl ElementConfig {
+    fn from_attributes(host: web_sys::HtmlElement) -> Self {
+        let attribute = |name: &str| host.get_attribute(name).filter(|v| !v.trim().is_empty());
+        let dark = attribute("theme").map(|theme| theme == "dark");
+        let prompt = attribute("prompt").or_else(|| {
+            attribute("symbol")
+                .map(|symbol| format!("What's the wave structure for {}?", symbol.trim().to_uppercase()))
+        });
+        ElementConfig { host, dark, prompt }
+    }
+}
+
+thread_local! {
+    /// The mounted element and its app; toasts, dialogs and the other registries are
+    /// global, so one page holds at most one `<wxve-chat>`
+    static MOUNTED: RefCell<Option<Mounted>> = const { RefCell::new(None) };
+}
+
+struct Mounted {
+    host: web_sys::HtmlElement,
+    /// API base URL from the `endpoint` attribute
+    endpoint: Option<String>,
+    /// The mounted app; dropping it unmounts
+    app: Option<Box<dyn Any>>,
+}
+
+/// API base URL set with `<wxve-chat endpoint="...">`, if any.
+pub fn endpoint() -> Option<String> {
+    MOUNTED.with(|m| m.borrow().as_ref().and_then(|m| m.endpoint.clone()))
+}
+
+/// The shadow root the app is mounted in, when running as `<wxve-chat>`.
+pub fn shadow_root() -> Option<web_sys::ShadowRoot> {
+    MOUNTED.with(|m| m.borrow().as_ref().and_then(|m| m.host.shadow_root()))
+}
+
+/// Register `<wxve-chat>`: each connected element mounts `App` into its shadow root,
+/// configured from its `symbol`, `theme`, `endpoint` and `prompt` attributes.
+pub fn register() {
+    let connected = Closure::<dyn FnMut(web_sys::HtmlElement, web_sys::HtmlElement)>::new(
+        |host: web_sys::HtmlElement, mount: web_sys::HtmlElement| {
+            if MOUNTED.with(|m| m.borrow().is_some()) {
+                log::warn!(target: "ui", "Only one <{TAG}> per page is supported; ignoring another");
+                return;
+            }
+            let endpoint = host
+                .get_attribute("endpoint")
+                .map(|url| url.trim_end_matches('/').to_string())
+                .filter(|url| !url.is_empty());
+            // Set before mounting so the app's first requests already use it
+            MOUNTED.with(|m| {
+                *m.borrow_mut() = Some(Mounted { host: host.clone(), endpoint, app: None });
+            });
+            let config = ElementConfig::from_attributes(host);
+            let handle = mount_to(mount, move || {
+                provide_context(config);
+                view! { <App/> }
+            });
+            MOUNTED.with(|m| {
+                if let Some(mounted) = m.borrow_mut().as_mut() {
+                    mounted.app = Some(Box::new(handle));
+                }
+            });
+        },
+    );
+    let disconnected = Closure::<dyn FnMut(web_sys::HtmlElement)>::new(|host: web_sys::HtmlElement| {
+        let mounted = MOUNTED.with(|m| {
+            let mut m = m.borrow_mut();
+            if m.as_ref().is_some_and(|mounted| mounted.host == host) { m.take() } else { None }
+        });
+        // Unmount outside the borrow: cleanups may ask for the endpoint or shadow root
+        drop(mounted);
+    });
+    define(TAG, &connected, &disconnected);
+    connected.forget();
+    disconnected.forget();
+}
@@ -0,0 +1,162 @@
+use leptos::prelude::{Callable, Callback, Set, WriteSignal};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::JsCast;
+
+use crate::state::use_app_state;
+use crate::{query_all, window_listener, Message};
+
+/// Query parameter naming the host page's origin; without it the referrer's is assumed
+const ORIGIN_PARAM: &str = "origin=";
+
+/// True when the page was opened with `?embed=1`, as inside the `embed.js` widget:
+/// only the conversation and composer are shown, driven by the host over postMessage.
+pub fn enabled() -> bool {
+    query().split('&').any(|pair| matches!(pair, "embed=1" | "embed=true"))
+}
+
+fn query() -> String {
+    let search = web_sys::window()
+        .and_then(|w| w.location().search().ok())
+        .unwrap_or_default();
+    search.trim_start_matches('?').to_string()
+}
+
+/// The host origin given with `?origin=`, or else that of the page that framed the widget
+/// (the referrer). Without either, commands are taken from any parent, but no events are
+/// posted: they carry the conversation, and `*` would hand it to whatever page is around.
+fn host_origin() -> Option<String> {
+    let given = query()
+        .split('&')
+        .find_map(|pair| pair.strip_prefix(ORIGIN_PARAM))
+        .and_then(|value| js_sys::decode_uri_component(value).ok())
+        .map(String::from)
+        .filter(|origin| !origin.is_empty());
+    given.or_else(|| {
+        let referrer = web_sys::window()?.document()?.referrer();
+        let origin = web_sys::Url::new(&referrer).ok()?.origin();
+        // Opaque origins serialize as "null" and can't be posted to
+        Some(origin).filter(|origin| origin != "null")
+    })
+}
+
+/// Commands the host page posts into the widget, e.g. `{"xve": "send", "text": "..."}`
+#[derive(Debug, Deserialize)]
+#[serde(tag = "xve", rename_all = "snake_case")]
+enum Command {
+    /// The widget was shown; focus the composer
+    Open,
+    /// Put text in the composer without sending it
+    Prefill { text: String },
+    Send { text: String },
+    Theme { dark: bool },
+}
+
+/// Events the widget posts to the host page, e.g. `{"xve": "response", ...}`
+#[derive(Serialize)]
+#[serde(tag = "xve", rename_all = "snake_case")]
+enum Event<'a> {
+    /// Listening for commands
+    Ready,
+    /// The user asked to close the widget
+    Close,
+    /// A response finished streaming
+    Response {
+        content: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        request_id: Option<&'a str>,
+    },
+    /// A response failed
+    Error { message: &'a str },
+}
+
+fn post(event: &Event) {
+    if !enabled() {
+        return;
+    }
+    let Some(parent) = web_sys::window().and_then(|w| w.parent().ok().flatten()) else {
+        return;
+    };
+    let Ok(json) = serde_json::to_string(event) else {
+        return;
+    };
+    let Ok(value) = js_sys::JSON::parse(&json) else {
+        return;
+    };
+    let Some(target) = host_origin() else {
+        log::warn!(target: "ui", "Embed event not posted: the host origin is unknown (add ?origin=)");
+        return;
+    };
+    if let Err(e) = parent.post_message(&value, &target) {
+        log::warn!(target: "ui", "Embed event not delivered: {e:?}");
+    }
+}
+
+/// Tell the host a response finished.
+pub fn response_finished(message: &Message) {
+    post(&Event::Response {
+        content: &message.content,
+        request_id: message.meta.as_ref().map(|m| m.request_id.as_str()),
+    });
+}
+
+/// Tell the host a response failed.
+pub fn response_failed(error: &str) {
+    post(&Event::Error { message: error });
+}
+
+/// Ask the host to hide the widget.
+pub fn close() {
+    post(&Event::Close);
+}
+
+/// Answer the host page's commands for the life of the page, then announce `ready`.
+pub fn use_embed(set_input: WriteSignal<String>, set_dark: Callback<bool>) {
+    let state = use_app_state();
+    let origin = host_origin();
+
+    window_listener(leptos::ev::message, move |ev| {
+        let Some(window) = web_sys::window() else {
+            return;
+        };
+        let from_parent = ev.source().zip(window.parent().ok().flatten()).is_some_and(
+            |(source, parent)| js_sys::Object::is(&source, parent.unchecked_ref()),
+        );
+        if !from_parent || origin.as_ref().is_some_and(|o| *o != ev.origin()) {
+            return;
+        }
+        let Ok(json) = js_sys::JSON::stringify(&ev.data()) else {
+            return;
+        };
+        let json = String::from(json);
+        let command = match serde_json::from_str::<Command>(&json) {
+            Ok(command) => command,
+            // Other scripts on the host may post their own messages
+            Err(e) => {
+                log::debug!(target: "ui", "Ignoring host message ({e}): {json}");
+                return;
+            }
+        };
+        log::debug!(target: "ui", "Embed command: {command:?}");
+        match command {
+            Command::Open => focus_composer(),
+            Command::Prefill { text } => {
+                set_input.set(text);
+                focus_composer();
+            }
+            Command::Send { text } => {
+                state.send(text, None);
+            }
+            Command::Theme { dark } => set_dark.run(dark),
+        }
+    });
+
+    post(&Event::Ready);
+}
+
+fn focus_composer() {
+    if let Some(input) = query_all(".input-box textarea").first()
+        && let Some(input) = input.dyn_ref::<web_sys::HtmlElement>()
+    {
+        let _ = input.focus();
+    }
+}
@@ -0,0 +1,134 @@
+use leptos::prelude::{GetUntracked, WithUntracked};
+
+use crate::account::download;
+use crate::history;
+use crate::state::AppState;
+use crate::{markdown_to_html, render_chart, toast, user_markdown_to_html, Chart, Message, Role};
+
+/// The app's own stylesheet, so an export looks like the conversation did
+const STYLES: &str = include_str!("../styles/main.css");
+
+/// Undo the room the app leaves for its fixed toolbar and composer
+const EXPORT_STYLES: &str = "
+.exported .messages { padding: 1rem; }
+.export-title { max-width: 48rem; margin: 2rem auto 0; padding: 0 1rem; font-size: 1.25rem; }
+.export-footer { margin: 0 auto 2rem; color: var(--text-muted); font-size: 0.75rem; text-align: center; }
+";
+
+/// Charts get the page's theme over postMessage once loaded, as they do in the app
+const THEME_SCRIPT: &str = "<script>
+document.querySelectorAll('iframe').forEach(function (f) {
+  f.addEventListener('load', function () { f.contentWindow.postMessage({ type: 'theme', dark: true }, '*'); });
+});
+</script>";
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn chart_html(chart: &Chart) -> String {
+    format!(
+        "<div class=\"chart-container\"><iframe srcdoc=\"{}\" title=\"{} chart\" \
+         sandbox=\"allow-scripts allow-fullscreen\"></iframe></div>",
+        escape(&chart.html),
+        escape(&chart.symbol),
+    )
+}
+
+fn message_html(message: &Message) -> String {
+    let (class, content) = match message.role {
+        Role::User => ("message user", user_markdown_to_html(&message.content)),
+        Role::Assistant => ("message", markdown_to_html(&message.content)),
+    };
+    let quoted = message
+        .quoted
+        .as_ref()
+        .map(|quoted| format!("<blockquote class=\"reply-quote\">{}</blockquote>", escape(quoted)))
+        .unwrap_or_default();
+    let charts: String = message.charts.iter().map(chart_html).collect();
+    format!("<div class=\"{class}\">{quoted}<span>{content}</span>{charts}</div>")
+}
+
+/// The conversation as one HTML file that opens offline and can be mailed as an
+/// attachment: messages rendered as in the app, its stylesheet, and each chart's HTML
+/// inlined as an iframe `srcdoc` (charts that load scripts from a CDN still need it).
+fn conversation_html(title: &str, messages: &[Message], dark: bool) -> String {
+    let title = if title.is_empty() { "Xve Chat" } else { title };
+    let exported_at = String::from(
+        js_sys::Date::new_0().to_locale_string("default", &wasm_bindgen::JsValue::UNDEFINED),
+    );
+    let messages: String = messages.iter().map(message_html).collect();
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{title}</title>\n<style>\n{STYLES}\n{EXPORT_STYLES}</style>\n</head>\n\
+         <body{body_class}>\n<div class=\"container exported\">\n\
+         <h1 class=\"export-title\">{title}</h1>\n<div class=\"messages\">{messages}</div>\n\
+         <p class=\"export-footer\">Exported from Xve Chat on {exported_at}</p>\n</div>\n{script}\
+         </body>\n</html>\n",
+        title = escape(title),
+        body_class = if dark { " class=\"dark\"" } else { "" },
+        exported_at = escape(&exported_at),
+        script = if dark { THEME_SCRIPT } else { "" },
+    )
+}
+
+/// Download the open conversation, archived messages included, as an HTML file named
+/// after its title.
+pub async fn export_conversation(state: AppState, dark: bool) {
+    let id = state.conversation_id.get_untracked();
+    let title = state
+        .conversations
+        .with_untracked(|c| c.iter().find(|c| c.id == id).map(|c| c.title.clone()))
+        .unwrap_or_default();
+    let archived = state.archived.get_untracked();
+    let mut messages = if archived > 0 {
+        match history::load(&id, 0, archived).await {
+            Ok(earlier) => earlier,
+            Err(e) => {
+                log::warn!(target: "ui", "Couldn't load archived messages to export: {e:?}");
+                toast::error("Couldn't export: earlier messages didn't load");
+                return;
+            }
+        }
+    } else {
+        Vec::new()
+    };
+    state.messages.with_untracked(|m| messages.extend(m.iter().cloned()));
+    // Saved charts keep only what drew them; draw those again for the file
+    let unrendered =
+        messages.iter_mut().flat_map(|m| m.charts.iter_mut()).filter(|c| c.html.is_empty());
+    for chart in unrendered {
+        let (symbol, indicators) = (chart.symbol.clone(), chart.indicators.clone());
+        match render_chart(symbol, indicators, chart.params.clone()).await {
+            Ok(html) => chart.html = html,
+            Err(e) => log::warn!(target: "ui", "Couldn't draw the {} chart to export: {e}", chart.symbol),
+        }
+    }
+
+    let slug: String = title
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .take(8)
+        .collect::<Vec<_>>()
+        .join("-");
+    let filename = if slug.is_empty() { "xve-chat.html".to_string() } else { format!("{slug}.html") };
+    match download(&filename, &conversation_html(&title, &messages, dark), "text/html") {
+        Ok(()) => toast::success("Conversation exported"),
+        Err(e) => toast::error(format!("Couldn't export: {e}")),
+    }
+}
@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+
+use leptos::prelude::{Memo, RwSignal, With};
+
+use crate::remote_config::RemoteConfig;
+
+/// Known flags and their defaults when neither the remote config nor an override sets them
+const DEFAULTS: &[(&str, bool)] = &[("throughput_indicator", true), ("dev_mode", false)];
+
+/// Effective feature flags: defaults, then remote config, then (debug builds only)
+/// `?flags=name,other:off` query overrides.
+#[derive(Clone, Default, PartialEq)]
+pub struct Flags(HashMap<String, bool>);
+
+impl Flags {
+    pub fn enabled(&self, flag: &str) -> bool {
+        self.0.get(flag).copied().unwrap_or(false)
+    }
+}
+
+/// Parse `name` / `name:on` / `name:off` entries from a `flags` query value.
+fn parse_overrides(value: &str) -> Vec<(String, bool)> {
+    value
+        .split(',')
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| match entry.split_once(':') {
+            Some((name, state)) => (name.to_string(), !matches!(state, "off" | "0" | "false")),
+            None => (entry.to_string(), true),
+        })
+        .collect()
+}
+
+fn query_overrides() -> Vec<(String, bool)> {
+    if !cfg!(debug_assertions) {
+        return Vec::new();
+    }
+    let search = web_sys::window()
+        .and_then(|w| w.location().search().ok())
+        .unwrap_or_default();
+    search
+        .trim_start_matches('?')
+        .split('&')
+        .filter_map(|pair| pair.strip_prefix("flags="))
+        .filter_map(|value| js_sys::decode_uri_component(value).ok())
+        .flat_map(|value| parse_overrides(&String::from(value)))
+        .collect()
+}
+
+pub fn use_flags(config: RwSignal<RemoteConfig>) -> Memo<Flags> {
+    let overrides = query_overrides();
+    Memo::new(move |_| {
+        let mut flags: HashMap<String, bool> =
+            DEFAULTS.iter().map(|&(name, on)| (name.to_string(), on)).collect();
+        config.with(|c| flags.extend(c.flags.clone()));
+        flags.extend(overrides.iter().cloned());
+        Flags(flags)
+    })
+}
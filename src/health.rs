@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use leptos::prelude::{
+    component, on_cleanup, set_interval_with_handle, signal, view, Get, IntoView, Set,
+};
+use leptos::tachys::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::{fetch_status, ApiStatus, ServiceState};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Banner shown while `/status` reports degraded performance or an incident.
+/// Dismissing hides the current message; a different one shows again.
+#[component]
+pub fn HealthBanner() -> impl IntoView {
+    let (status, set_status) = signal(None::<ApiStatus>);
+    let (dismissed, set_dismissed) = signal(None::<String>);
+
+    let refresh = move || {
+        spawn_local(async move {
+            match fetch_status().await {
+                Ok(fetched) => set_status.set(Some(fetched)),
+                // An unreachable status endpoint shows up on the connection dot instead
+                Err(e) => log::error!(target: "transport", "Status fetch failed: {e}"),
+            }
+        });
+    };
+    refresh();
+    if let Ok(handle) = set_interval_with_handle(refresh, POLL_INTERVAL) {
+        on_cleanup(move || handle.clear());
+    }
+
+    move || {
+        let status = status.get()?;
+        let (class, fallback) = match status.status {
+            ServiceState::Operational => return None,
+            ServiceState::Degraded => ("banner warning", "Xve is responding slower than usual."),
+            ServiceState::Outage => ("banner error", "Xve is having an outage. Responses may fail."),
+        };
+        let message = status.message.unwrap_or_else(|| fallback.to_string());
+        if dismissed.get().as_ref() == Some(&message) {
+            return None;
+        }
+        let dismiss_message = message.clone();
+        Some(view! {
+            <div class=class role="status">
+                <span class="banner-text">{message}</span>
+                {status.incident_url.map(|url| view! {
+                    <a href=url target="_blank" rel="noopener">"Details"</a>
+                })}
+                <button
+                    class="banner-dismiss"
+                    title="Dismiss"
+                    on:click=move |_| set_dismissed.set(Some(dismiss_message.clone()))
+                >
+                    "×"
+                </button>
+            </div>
+        })
+    }
+}
@@ -0,0 +1,187 @@
+use std::time::Duration;
+
+use leptos::prelude::{
+    component, on_cleanup, set_interval_with_handle, signal, view, Callable, Callback, Get,
+    IntoView, RwSignal, Set, With,
+};
+use leptos::tachys::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::{fetch_heatmap, HeatmapSector};
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Percent change at which tile color saturates
+const MAX_CHANGE: f64 = 3.0;
+
+#[derive(Clone, Copy)]
+struct Rect {
+    x: f64,
+    y: f64,
+    w: f64,
+    h: f64,
+}
+
+/// Worst aspect ratio of a row of areas laid along a side of length `side`.
+fn worst_ratio(row: &[f64], side: f64) -> f64 {
+    let sum: f64 = row.iter().sum();
+    let max = row.iter().cloned().fold(f64::MIN, f64::max);
+    let min = row.iter().cloned().fold(f64::MAX, f64::min);
+    let side2 = side * side;
+    f64::max(side2 * max / (sum * sum), sum * sum / (side2 * min))
+}
+
+/// Place a finished row along the shorter side of `rect`, returning the leftover space.
+fn layout_row(row: &[f64], rect: Rect, out: &mut Vec<Rect>) -> Rect {
+    let sum: f64 = row.iter().sum();
+    if rect.w >= rect.h {
+        let width = sum / rect.h;
+        let mut y = rect.y;
+        for area in row {
+            let h = area / width;
+            out.push(Rect { x: rect.x, y, w: width, h });
+            y += h;
+        }
+        Rect { x: rect.x + width, w: rect.w - width, ..rect }
+    } else {
+        let height = sum / rect.w;
+        let mut x = rect.x;
+        for area in row {
+            let w = area / height;
+            out.push(Rect { x, y: rect.y, w, h: height });
+            x += w;
+        }
+        Rect { y: rect.y + height, h: rect.h - height, ..rect }
+    }
+}
+
+/// Squarified treemap: one rect per weight (sorted descending) filling `rect`.
+fn squarify(weights: &[f64], rect: Rect) -> Vec<Rect> {
+    let total: f64 = weights.iter().sum();
+    let mut out = Vec::with_capacity(weights.len());
+    if total <= 0.0 {
+        return out;
+    }
+    let scale = rect.w * rect.h / total;
+    let areas: Vec<f64> = weights.iter().map(|w| w * scale).collect();
+
+    let mut free = rect;
+    let mut row: Vec<f64> = Vec::new();
+    for &area in &areas {
+        let side = free.w.min(free.h);
+        let mut candidate = row.clone();
+        candidate.push(area);
+        if row.is_empty() || worst_ratio(&candidate, side) <= worst_ratio(&row, side) {
+            row = candidate;
+        } else {
+            free = layout_row(&row, free, &mut out);
+            row = vec![area];
+        }
+    }
+    if !row.is_empty() {
+        layout_row(&row, free, &mut out);
+    }
+    out
+}
+
+fn tile_color(change_percent: f64) -> String {
+    let intensity = (change_percent.abs() / MAX_CHANGE).min(1.0);
+    let alpha = 0.25 + 0.75 * intensity;
+    if change_percent >= 0.0 {
+        format!("rgba(22, 163, 74, {alpha:.2})")
+    } else {
+        format!("rgba(220, 38, 38, {alpha:.2})")
+    }
+}
+
+fn position_style(rect: Rect) -> String {
+    format!(
+        "left: {:.3}%; top: {:.3}%; width: {:.3}%; height: {:.3}%;",
+        rect.x, rect.y, rect.w, rect.h
+    )
+}
+
+/// Market heatmap: sectors sized by total market cap, tiles by company market cap.
+#[component]
+pub fn Heatmap(on_select: Callback<String>) -> impl IntoView {
+    let sectors = RwSignal::new(Vec::<HeatmapSector>::new());
+    let (error, set_error) = signal(None::<String>);
+
+    let refresh = move || {
+        spawn_local(async move {
+            match fetch_heatmap().await {
+                Ok(mut fetched) => {
+                    for sector in &mut fetched {
+                        sector.tiles.sort_by(|a, b| b.market_cap.total_cmp(&a.market_cap));
+                    }
+                    fetched.sort_by(|a, b| b.market_cap().total_cmp(&a.market_cap()));
+                    sectors.set(fetched);
+                    set_error.set(None);
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+        });
+    };
+    refresh();
+    if let Ok(handle) = set_interval_with_handle(refresh, REFRESH_INTERVAL) {
+        on_cleanup(move || handle.clear());
+    }
+
+    let layout = move || {
+        sectors.with(|sectors| {
+            let weights: Vec<f64> = sectors.iter().map(HeatmapSector::market_cap).collect();
+            let full = Rect { x: 0.0, y: 0.0, w: 100.0, h: 100.0 };
+            sectors
+                .iter()
+                .zip(squarify(&weights, full))
+                .map(|(sector, sector_rect)| {
+                    let weights: Vec<f64> = sector.tiles.iter().map(|t| t.market_cap).collect();
+                    let inner = Rect { x: 0.0, y: 0.0, w: 100.0, h: 100.0 };
+                    let tiles = sector
+                        .tiles
+                        .iter()
+                        .zip(squarify(&weights, inner))
+                        .map(|(tile, rect)| {
+                            let symbol = tile.symbol.clone();
+                            let style = format!(
+                                "{} background: {};",
+                                position_style(rect),
+                                tile_color(tile.change_percent)
+                            );
+                            let title = format!("{} · {:+.2}%", tile.name, tile.change_percent);
+                            view! {
+                                <button
+                                    class="heatmap-tile"
+                                    style=style
+                                    title=title
+                                    on:click=move |_| on_select.run(symbol.clone())
+                                >
+                                    <span class="heatmap-symbol">{tile.symbol.clone()}</span>
+                                    <span class="heatmap-change">
+                                        {format!("{:+.2}%", tile.change_percent)}
+                                    </span>
+                                </button>
+                            }
+                        })
+                        .collect::<Vec<_>>();
+                    view! {
+                        <div class="heatmap-sector" style=position_style(sector_rect)>
+                            <div class="heatmap-sector-name">{sector.name.clone()}</div>
+                            <div class="heatmap-tiles">{tiles}</div>
+                        </div>
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+    };
+
+    view! {
+        <div class="page heatmap-page">
+            <div class="page-title">"Market heatmap"</div>
+            {move || error.get().map(|e| view! {
+                <p class="panel-empty">{format!("Couldn't load the heatmap: {e}")}</p>
+            })}
+            <div class="heatmap">{layout}</div>
+        </div>
+    }
+}
@@ -0,0 +1,226 @@
+use js_sys::{Array, Promise};
+use leptos::html::Button;
+use leptos::prelude::{
+    component, event_target_value, on_cleanup, view, Effect, Get, GetUntracked, IntoView,
+    NodeRef, RwSignal, Set, Update, With,
+};
+use leptos::task::spawn_local;
+use leptos::tachys::prelude::*;
+use send_wrapper::SendWrapper;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    IdbDatabase, IdbKeyRange, IdbOpenDbRequest, IdbRequest, IdbTransaction, IdbTransactionMode,
+    IntersectionObserver, IntersectionObserverEntry,
+};
+
+use crate::state::use_app_state;
+use crate::{Message, Settings};
+
+const DB_NAME: &str = "wxve-chat";
+/// Messages moved out of memory, keyed by `[conversation id, position in the conversation]`
+const STORE: &str = "archived-messages";
+
+/// Messages kept in memory when `Settings::history_cap` isn't set
+const DEFAULT_CAP: usize = 200;
+const CAPS: [usize; 4] = [100, 200, 500, 1000];
+/// Archived messages brought back per scroll to the top
+pub const PAGE: usize = 50;
+
+/// Messages a conversation keeps in memory; past this the oldest are archived.
+pub fn cap(settings: &Settings) -> usize {
+    settings.history_cap.unwrap_or(DEFAULT_CAP)
+}
+
+fn key(conversation: &str, position: usize) -> JsValue {
+    Array::of2(&conversation.into(), &(position as f64).into()).into()
+}
+
+/// Resolve once `request` succeeds, to its result.
+async fn done(request: &IdbRequest) -> Result<JsValue, JsValue> {
+    let promise = Promise::new(&mut |resolve, reject| {
+        request.set_onsuccess(Some(&resolve));
+        request.set_onerror(Some(&reject));
+    });
+    JsFuture::from(promise).await?;
+    request.result()
+}
+
+async fn committed(transaction: &IdbTransaction) -> Result<(), JsValue> {
+    let promise = Promise::new(&mut |resolve, reject| {
+        transaction.set_oncomplete(Some(&resolve));
+        transaction.set_onerror(Some(&reject));
+    });
+    JsFuture::from(promise).await?;
+    Ok(())
+}
+
+async fn open() -> Result<IdbDatabase, JsValue> {
+    let factory = web_sys::window()
+        .ok_or(JsValue::NULL)?
+        .indexed_db()?
+        .ok_or_else(|| JsValue::from_str("IndexedDB unavailable"))?;
+    let request = factory.open_with_u32(DB_NAME, 1)?;
+    let upgrade = Closure::<dyn Fn(web_sys::Event)>::new(|ev: web_sys::Event| {
+        let db = ev
+            .target()
+            .and_then(|t| t.dyn_into::<IdbOpenDbRequest>().ok())
+            .and_then(|request| request.result().ok());
+        if let Some(db) = db {
+            let _ = db.unchecked_into::<IdbDatabase>().create_object_store(STORE);
+        }
+    });
+    request.set_onupgradeneeded(Some(upgrade.as_ref().unchecked_ref()));
+    let db = done(&request).await?;
+    Ok(db.unchecked_into())
+}
+
+/// Store `messages` as positions `from..` of `conversation`, replacing what was there.
+pub async fn archive(conversation: &str, from: usize, messages: &[Message]) -> Result<(), JsValue> {
+    let db = open().await?;
+    let transaction = db.transaction_with_str_and_mode(STORE, IdbTransactionMode::Readwrite)?;
+    let store = transaction.object_store(STORE)?;
+    for (i, message) in messages.iter().enumerate() {
+        let json = serde_json::to_string(message).map_err(|e| JsValue::from_str(&e.to_string()))?;
+        store.put_with_key(&json.into(), &key(conversation, from + i))?;
+    }
+    committed(&transaction).await
+}
+
+/// The archived messages at positions `start..end` of `conversation`.
+pub async fn load(conversation: &str, start: usize, end: usize) -> Result<Vec<Message>, JsValue> {
+    let db = open().await?;
+    let store = db.transaction_with_str(STORE)?.object_store(STORE)?;
+    let range = IdbKeyRange::bound_with_lower_open_and_upper_open(
+        &key(conversation, start),
+        &key(conversation, end),
+        false,
+        true,
+    )?;
+    let values = done(&store.get_all_with_key(&range)?).await?;
+    Array::from(&values)
+        .iter()
+        .map(|value| {
+            value
+                .as_string()
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .ok_or_else(|| JsValue::from_str("Unreadable archived message"))
+        })
+        .collect()
+}
+
+/// Every position of `conversation`.
+fn all_of(conversation: &str) -> Result<IdbKeyRange, JsValue> {
+    let past_last = Array::of2(&conversation.into(), &f64::INFINITY.into());
+    IdbKeyRange::bound(&key(conversation, 0), &past_last)
+}
+
+async fn remove(conversation: &str) -> Result<(), JsValue> {
+    let db = open().await?;
+    let transaction = db.transaction_with_str_and_mode(STORE, IdbTransactionMode::Readwrite)?;
+    transaction.object_store(STORE)?.delete(&all_of(conversation)?.into())?;
+    committed(&transaction).await
+}
+
+/// Forget the archived messages of `conversation`, in the background.
+pub fn delete(conversation: &str) {
+    let conversation = conversation.to_string();
+    spawn_local(async move {
+        if let Err(e) = remove(&conversation).await {
+            log::warn!(target: "storage", "Couldn't delete archived messages of {conversation}: {e:?}");
+        }
+    });
+}
+
+/// Every archived message, as the messages of each conversation in order.
+pub async fn load_all() -> Result<Vec<(String, Vec<Message>)>, JsValue> {
+    let db = open().await?;
+    let store = db.transaction_with_str(STORE)?.object_store(STORE)?;
+    // Both come in key order, so conversation by conversation, earliest first
+    let keys = Array::from(&done(&store.get_all_keys()?).await?);
+    let values = Array::from(&done(&store.get_all()?).await?);
+    let mut all: Vec<(String, Vec<Message>)> = Vec::new();
+    for (key, value) in keys.iter().zip(values.iter()) {
+        let conversation = Array::from(&key).get(0).as_string().unwrap_or_default();
+        let message = value
+            .as_string()
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .ok_or_else(|| JsValue::from_str("Unreadable archived message"))?;
+        match all.last_mut() {
+            Some((id, messages)) if *id == conversation => messages.push(message),
+            _ => all.push((conversation, vec![message])),
+        }
+    }
+    Ok(all)
+}
+
+/// "Show earlier messages" at the top of a conversation whose start was archived. It loads
+/// them when scrolled into view, so scrolling up reads like one continuous conversation.
+#[component]
+pub fn EarlierMessages() -> impl IntoView {
+    let state = use_app_state();
+    let fetching = RwSignal::new(false);
+    let load = move || {
+        if fetching.get_untracked() {
+            return;
+        }
+        fetching.set(true);
+        spawn_local(async move {
+            state.load_earlier().await;
+            fetching.set(false);
+        });
+    };
+
+    let button = NodeRef::<Button>::new();
+    Effect::new(move |_| {
+        let Some(button) = button.get() else {
+            return;
+        };
+        let callback = Closure::<dyn Fn(Array)>::new(move |entries: Array| {
+            if entries.iter().any(|e| e.unchecked_into::<IntersectionObserverEntry>().is_intersecting()) {
+                load();
+            }
+        });
+        let Ok(observer) = IntersectionObserver::new(callback.as_ref().unchecked_ref()) else {
+            return;
+        };
+        observer.observe(&button);
+        let observer = SendWrapper::new((observer, callback));
+        on_cleanup(move || observer.0.disconnect());
+    });
+
+    move || {
+        let archived = state.archived.get();
+        (archived > 0).then(|| view! {
+            <button class="load-earlier" node_ref=button disabled=fetching on:click=move |_| load()>
+                {move || if fetching.get() {
+                    "Loading earlier messages…".to_string()
+                } else {
+                    format!("Show {} earlier messages", archived.min(PAGE))
+                }}
+            </button>
+        })
+    }
+}
+
+/// Picker for `Settings::history_cap`.
+#[component]
+pub fn HistoryCapSelect(settings: RwSignal<Settings>) -> impl IntoView {
+    view! {
+        <select
+            class="panel-option"
+            on:change=move |ev| {
+                if let Ok(cap) = event_target_value(&ev).parse() {
+                    settings.update(|s| s.history_cap = Some(cap));
+                }
+            }
+        >
+            {CAPS.into_iter().map(|n| view! {
+                <option value=n.to_string() selected=move || settings.with(|s| cap(s) == n)>
+                    {format!("{n} messages")}
+                </option>
+            }).collect::<Vec<_>>()}
+        </select>
+    }
+}
@@ -0,0 +1,210 @@
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+
+use leptos::prelude::{
+    component, event_target_value, signal, view, AnyView, Callable, Callback, Effect, Get, IntoView,
+    Memo, RwSignal, Set, Update, With,
+};
+use leptos::tachys::prelude::*;
+use serde::Serialize;
+use serde_json::Value;
+use wasm_bindgen::JsValue;
+use web_sys::AbortSignal;
+
+use crate::dialog::{self, Dialog};
+use crate::flags::Flags;
+
+/// Only the most recent turns are kept
+const MAX_TURNS: usize = 20;
+
+/// One chat request and every chunk received for it.
+#[derive(Clone)]
+struct Turn {
+    request_id: String,
+    /// Request body as sent, pretty-printed
+    request: String,
+    /// Milliseconds since the epoch
+    started_at: f64,
+    /// Milliseconds since `started_at`, and the parsed chunk
+    chunks: Vec<(f64, String)>,
+}
+
+thread_local! {
+    /// Registered by `Inspector` while dev mode is on; nothing is recorded otherwise
+    static TURNS: Cell<Option<RwSignal<VecDeque<Turn>>>> = const { Cell::new(None) };
+}
+
+/// Record a request as it's sent.
+pub fn begin(request_id: &str, request: &impl Serialize) {
+    let Some(turns) = TURNS.with(Cell::get) else {
+        return;
+    };
+    let turn = Turn {
+        request_id: request_id.to_string(),
+        request: serde_json::to_string_pretty(request).unwrap_or_default(),
+        started_at: js_sys::Date::now(),
+        chunks: Vec::new(),
+    };
+    turns.update(|t| {
+        if t.len() == MAX_TURNS {
+            t.pop_front();
+        }
+        t.push_back(turn);
+    });
+}
+
+/// Record a chunk received for `request_id`.
+pub fn chunk(request_id: &str, chunk: &impl Debug) {
+    let Some(turns) = TURNS.with(Cell::get) else {
+        return;
+    };
+    turns.update(|t| {
+        if let Some(turn) = t.iter_mut().rev().find(|turn| turn.request_id == request_id) {
+            turn.chunks.push((js_sys::Date::now() - turn.started_at, format!("{chunk:?}")));
+        }
+    });
+}
+
+struct Review {
+    json: String,
+    /// Resolves the promise `review()` awaits with the edited JSON, or null if cancelled
+    resolve: js_sys::Function,
+}
+
+impl Dialog for Review {
+    fn title(&self) -> String {
+        "Review request".to_string()
+    }
+
+    fn body(&self, close: Callback<()>) -> AnyView {
+        let (text, set_text) = signal(self.json.clone());
+        let (error, set_error) = signal(None::<String>);
+        let resolve = self.resolve.clone();
+        let send = move |_| {
+            let text = text.get();
+            match serde_json::from_str::<Value>(&text) {
+                Ok(_) => {
+                    let _ = resolve.call1(&JsValue::NULL, &JsValue::from_str(&text));
+                    close.run(());
+                }
+                Err(e) => set_error.set(Some(e.to_string())),
+            }
+        };
+        view! {
+            <p class="settings-hint">"Edit the body below; it's sent exactly as written."</p>
+            <textarea
+                class="inspector-json"
+                spellcheck="false"
+                prop:value=text
+                on:input=move |ev| set_text.set(event_target_value(&ev))
+            ></textarea>
+            {move || error.get().map(|e| view! { <p class="vault-error">{e}</p> })}
+            <div class="dialog-actions">
+                <button class="dialog-button" on:click=move |_| close.run(())>"Cancel"</button>
+                <button class="dialog-button primary" autofocus=true on:click=send>"Send"</button>
+            </div>
+        }
+        .into_any()
+    }
+
+    fn class(&self) -> &'static str {
+        "inspector"
+    }
+
+    fn on_close(&self) {
+        let _ = self.resolve.call1(&JsValue::NULL, &JsValue::NULL);
+    }
+}
+
+/// The body to send for `request`. In dev mode the JSON is shown for editing first,
+/// and `None` means the user cancelled (or `signal` aborted); otherwise it's `request`
+/// unchanged.
+pub async fn review(request: &impl Serialize, signal: &AbortSignal) -> Option<Value> {
+    if TURNS.with(Cell::get).is_none() {
+        return serde_json::to_value(request).ok();
+    }
+    let json = serde_json::to_string_pretty(request).ok()?;
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        let id = dialog::open(Review { json: json.clone(), resolve });
+        dialog::close_on_abort(id, signal);
+    });
+    let edited = wasm_bindgen_futures::JsFuture::from(promise).await.ok()?.as_string()?;
+    serde_json::from_str(&edited).ok()
+}
+
+struct Turns {
+    turns: RwSignal<VecDeque<Turn>>,
+}
+
+impl Dialog for Turns {
+    fn title(&self) -> String {
+        "Requests".to_string()
+    }
+
+    fn body(&self, _close: Callback<()>) -> AnyView {
+        let turns = self.turns;
+        view! {
+            {move || turns.with(VecDeque::is_empty).then(|| view! {
+                <p class="panel-empty">"Nothing sent yet"</p>
+            })}
+            <div class="inspector-turns">
+                {move || turns.with(|t| {
+                    t.iter()
+                        .rev()
+                        .map(|turn| view! {
+                            <details class="inspector-turn">
+                                <summary>
+                                    <code>{turn.request_id.clone()}</code>
+                                    {format!(" · {} chunks", turn.chunks.len())}
+                                </summary>
+                                <h3>"Request"</h3>
+                                <pre class="inspector-json">{turn.request.clone()}</pre>
+                                <h3>"Chunks"</h3>
+                                <ol class="inspector-chunks">
+                                    {turn.chunks.iter().map(|(elapsed, chunk)| view! {
+                                        <li>
+                                            <span class="debug-time">{format!("+{elapsed:.0}ms")}</span>
+                                            <code>{chunk.clone()}</code>
+                                        </li>
+                                    }).collect::<Vec<_>>()}
+                                </ol>
+                            </details>
+                        })
+                        .collect::<Vec<_>>()
+                })}
+            </div>
+        }
+        .into_any()
+    }
+
+    fn class(&self) -> &'static str {
+        "inspector"
+    }
+}
+
+/// Dev-mode request inspector: every chat request is shown for editing before it's
+/// sent, and the button lists recent requests with the chunks received for each.
+/// Only active while the `dev_mode` flag is on.
+#[component]
+pub fn Inspector(flags: Memo<Flags>) -> impl IntoView {
+    let turns = RwSignal::new(VecDeque::<Turn>::new());
+    let dev_mode = move || flags.with(|f| f.enabled("dev_mode"));
+    Effect::new(move |_| {
+        TURNS.with(|t| t.set(dev_mode().then_some(turns)));
+    });
+
+    move || {
+        dev_mode().then(|| view! {
+            <button
+                class="icon-btn inspector-toggle"
+                title="Inspect requests"
+                on:click=move |_| {
+                    dialog::open(Turns { turns });
+                }
+            >
+                "🔍"
+            </button>
+        })
+    }
+}
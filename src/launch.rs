@@ -0,0 +1,84 @@
+/// Path the web app manifest's `share_target` opens with `title`, `text` and `url`
+const SHARE_PATH: &str = "/share";
+
+/// A question the page was opened with: `?q=analyze%20NVDA` puts it in the composer,
+/// adding `&send=1` sends it straight away. Sharing to the installed app opens
+/// `/share?title=...&text=...&url=...`, which becomes a prompt about the shared content.
+pub struct Launch {
+    pub question: String,
+    pub send: bool,
+}
+
+/// Read the launch parameters from the URL, then drop them so a reload or bookmark of
+/// the resulting page doesn't ask again.
+pub fn take() -> Option<Launch> {
+    let window = web_sys::window()?;
+    let location = window.location();
+    let search = location.search().ok()?;
+    let path = location.pathname().unwrap_or_default();
+    let shared = path.trim_end_matches('/') == SHARE_PATH;
+
+    let mut params = Vec::new();
+    let mut rest = Vec::new();
+    for pair in search.trim_start_matches('?').split('&').filter(|pair| !pair.is_empty()) {
+        match pair.split_once('=') {
+            Some((name @ ("q" | "send" | "title" | "text" | "url"), value)) => {
+                params.push((name, decode(value).unwrap_or_default()));
+            }
+            _ => rest.push(pair),
+        }
+    }
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(n, value)| *n == name && !value.is_empty())
+            .map(|(_, value)| value.clone())
+    };
+
+    let launch = if shared {
+        share_prompt(param("title"), param("text"), param("url"))
+            .map(|question| Launch { question, send: false })
+    } else {
+        param("q").map(|question| Launch {
+            question,
+            send: param("send").is_some_and(|v| matches!(v.as_str(), "1" | "true")),
+        })
+    };
+    if launch.is_none() && !shared {
+        return None;
+    }
+
+    if let Ok(history) = window.history() {
+        let path = if shared { "/".to_string() } else { path };
+        let hash = location.hash().unwrap_or_default();
+        let query = if rest.is_empty() { String::new() } else { format!("?{}", rest.join("&")) };
+        let url = format!("{path}{query}{hash}");
+        let _ = history.replace_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&url));
+    }
+    launch
+}
+
+/// The prompt for content shared from another app. Apps fill the share fields
+/// inconsistently (many put the link in `text`), so each distinct part is kept once.
+fn share_prompt(title: Option<String>, text: Option<String>, url: Option<String>) -> Option<String> {
+    let mut parts: Vec<String> = Vec::new();
+    for part in [title, text, url].into_iter().flatten() {
+        if !parts.iter().any(|p| p.contains(&part)) {
+            parts.push(part);
+        }
+    }
+    if parts.is_empty() {
+        return None;
+    }
+    // Each part on its own line, as the user would paste them into the composer
+    let shared = parts.join("\n");
+    Some(format!("Summarize this and analyze what it means for the stocks involved:\n\n{shared}"))
+}
+
+/// Decode a query value, treating `+` as a space as forms and search engines encode it.
+fn decode(value: &str) -> Option<String> {
+    js_sys::decode_uri_component(&value.replace('+', " "))
+        .ok()
+        .map(String::from)
+        .map(|q| q.trim().to_string())
+}
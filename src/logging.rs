@@ -0,0 +1,89 @@
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+/// Default level plus per-target overrides, parsed from `?log=`.
+struct Filter {
+    default: LevelFilter,
+    targets: Vec<(String, LevelFilter)>,
+}
+
+impl Filter {
+    /// `?log=debug` sets every target; `?log=warn,transport:trace,ui:off` sets
+    /// a default and overrides individual targets. Unknown levels are ignored.
+    fn parse(spec: &str, default: LevelFilter) -> Self {
+        let mut filter = Filter { default, targets: Vec::new() };
+        for part in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            match part.split_once(':') {
+                Some((target, level)) => {
+                    if let Ok(level) = level.parse() {
+                        filter.targets.push((target.to_string(), level));
+                    }
+                }
+                None => {
+                    if let Ok(level) = part.parse() {
+                        filter.default = level;
+                    }
+                }
+            }
+        }
+        filter
+    }
+
+    fn level_for(&self, target: &str) -> LevelFilter {
+        self.targets
+            .iter()
+            .find(|(t, _)| t == target)
+            .map_or(self.default, |(_, level)| *level)
+    }
+
+    fn max(&self) -> LevelFilter {
+        self.targets.iter().map(|(_, level)| *level).fold(self.default, Ord::max)
+    }
+}
+
+struct ConsoleLogger {
+    filter: Filter,
+}
+
+impl Log for ConsoleLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.filter.level_for(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = format!("[{}] {}", record.target(), record.args()).into();
+        match record.level() {
+            Level::Error => web_sys::console::error_1(&line),
+            Level::Warn => web_sys::console::warn_1(&line),
+            Level::Info => web_sys::console::info_1(&line),
+            Level::Debug | Level::Trace => web_sys::console::debug_1(&line),
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Route the `log` facade to the browser console. Warnings and errors are shown by
+/// default (everything from `info` in debug builds); `?log=` changes that for the page load.
+/// Targets in use: `transport` (requests and streams), `parser` (SSE payloads),
+/// `ui` (components), `storage` (localStorage and encryption) and `telemetry`.
+pub fn init() {
+    let default = if cfg!(debug_assertions) { LevelFilter::Info } else { LevelFilter::Warn };
+    let search = web_sys::window()
+        .and_then(|w| w.location().search().ok())
+        .unwrap_or_default();
+    let spec = search
+        .trim_start_matches('?')
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("log="))
+        .and_then(|value| js_sys::decode_uri_component(value).ok())
+        .map(String::from)
+        .unwrap_or_default();
+    let filter = Filter::parse(&spec, default);
+    let max = filter.max();
+    if log::set_boxed_logger(Box::new(ConsoleLogger { filter })).is_ok() {
+        log::set_max_level(max);
+    }
+}
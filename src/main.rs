@@ -1,40 +1,414 @@
-use leptos::{
-    component, create_effect, create_signal, view, For, IntoView,
-    SignalGet, SignalSet, SignalUpdate, spawn_local, mount_to_body,
+mod account;
+mod alerts;
+#[cfg(test)]
+mod app_tests;
+mod branches;
+mod chart_params;
+mod compare;
+mod compression;
+mod connection;
+mod conversation_list;
+mod conversations;
+mod currency;
+mod dashboard;
+mod debug_panel;
+mod delivery;
+mod delta;
+mod dialog;
+mod earnings;
+mod element;
+mod economy;
+mod embed;
+mod export;
+mod flags;
+mod health;
+mod history;
+mod heatmap;
+mod inspector;
+mod launch;
+mod logging;
+#[cfg(test)]
+mod markdown_bench;
+mod message_keys;
+mod message_menu;
+mod mock;
+mod news;
+mod outline;
+mod permalink;
+mod pinned_context;
+mod portfolio;
+mod profile;
+mod pull_refresh;
+mod quote_card;
+mod reactions;
+mod remote_config;
+mod replay;
+mod reply;
+mod reporting;
+mod request_preview;
+mod resume;
+mod router;
+mod screener;
+mod scroll;
+mod selection;
+mod session;
+mod share;
+mod sound;
+mod swipe;
+mod state;
+mod symbol;
+mod telemetry;
+#[cfg(test)]
+mod test_support;
+mod ticker;
+mod toast;
+mod tool_approval;
+mod tools;
+mod turn_error;
+#[cfg(test)]
+mod transport_tests;
+mod typing;
+mod unread;
+mod vault;
+mod viewport;
+mod watchlist;
+mod workspace;
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+
+use leptos::ev::EventDescriptor;
+use leptos::prelude::{
+    component, event_target, use_context, event_target_checked, event_target_value, mount_to_body, on_cleanup,
+    set_interval_with_handle, signal, view, window_event_listener, AnyView, Callable, Callback, Effect, For, Get, GetUntracked, GetValue, IntoView, Memo, ReadSignal,
+    RwSignal, Set, Show, StoredValue, Track, Update, UpdateValue, With, WithUntracked, WithValue,
 };
-use pulldown_cmark::{html as md_html, Parser};
+use leptos::tachys::prelude::*;
+use leptos::task::spawn_local;
+use pulldown_cmark::{html as md_html, CowStr, Event, Options, Parser, Tag, TagEnd};
+use send_wrapper::SendWrapper;
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::JsCast;
-use wasm_bindgen_futures::JsFuture;
-use web_sys::{Request, RequestInit, RequestMode, Response};
-use js_sys;
+use web_sys::{RequestInit, Response};
+use wxve_chat_client::sse::{self, HEARTBEAT_TIMEOUT};
+use wxve_chat_client::web::{self, sleep};
+use wxve_chat_client::{
+    chat, ChartParams, ChartRef, ChatRequest, Indicator, PortfolioSummary, PositionSummary, Read,
+    Role, StreamChunk, Transport, API_URL,
+};
+
+use account::DataSettings;
+use alerts::Alerts;
+use branches::BranchSwitcher;
+use chart_params::{ChartParamsForm, Placement};
+use compare::Comparison;
+use connection::{Connection, ConnectionStatus};
+use conversation_list::ConversationList;
+use conversations::{Conversation, SyncedConversation};
+use currency::{use_fx_rates, FxRates};
+use dashboard::Dashboard;
+use debug_panel::{DebugPanel, StreamLog};
+use delivery::{Delivery, DeliveryStatus};
+use dialog::{Dialog, DialogHost};
+use earnings::EarningsCalendar;
+use element::ElementConfig;
+use economy::EconomicCalendar;
+use embed::use_embed;
+use flags::use_flags;
+use health::HealthBanner;
+use heatmap::Heatmap;
+use history::{EarlierMessages, HistoryCapSelect};
+use inspector::Inspector;
+use message_menu::{LongPress, MessageMenu};
+use news::News;
+use outline::Outline;
+use pinned_context::PinnedContext;
+use portfolio::Portfolio;
+use profile::use_profile_sync;
+use quote_card::QuoteCard;
+use reactions::{Reaction, Reactions};
+use reply::QuoteSelection;
+use reporting::use_error_reporting;
+use remote_config::{use_remote_config, ConfigBanners, RemoteConfig};
+use replay::use_replay;
+use router::{navigate, toggle_route, use_route, Route};
+use screener::Screener;
+use selection::{SelectCheckbox, SelectionBar};
+use session::SessionSettings;
+use state::{use_app_state, AppState, StreamPhase};
+use symbol::SymbolPage;
+use swipe::Swipe;
+use telemetry::use_telemetry;
+use ticker::Ticker;
+use toast::Toaster;
+use tool_approval::ToolApproval;
+use tools::{Tool, ToolSettings, ToolsResponse};
+use turn_error::{InterruptedNote, TurnError, TurnErrorBanner};
+use typing::{use_typewriter, TypingSpeed, TypingSpeedSelect};
+use unread::{use_unread, NewResponsesDivider};
+use vault::{EncryptionSettings, LockedBanner, Vault};
+use watchlist::Watchlist;
 
 // ----------------------------------------------------------------------------
 // Helpers
 // ----------------------------------------------------------------------------
 
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Render markdown, wrapping ticker mentions in `.ticker-mention` spans for the quote card.
 fn markdown_to_html(md: &str) -> String {
-    let parser = Parser::new(md);
+    // Code and link text are left alone
+    let mut skip_depth = 0usize;
+    let parser = Parser::new(md).flat_map(|event| match event {
+        Event::Start(Tag::CodeBlock(_) | Tag::Link { .. }) => {
+            skip_depth += 1;
+            vec![event]
+        }
+        Event::End(TagEnd::CodeBlock | TagEnd::Link) => {
+            skip_depth = skip_depth.saturating_sub(1);
+            vec![event]
+        }
+        Event::Text(text) if skip_depth == 0 => mark_tickers(text),
+        event => vec![event],
+    });
+    let mut html_output = String::new();
+    md_html::push_html(&mut html_output, parser);
+    html_output
+}
+
+/// Markdown the user wrote, as HTML that can't run or load anything: raw HTML shows as
+/// text, images as their alt text, and only http(s) and mailto links stay links.
+fn user_markdown_to_html(md: &str) -> String {
+    // Whether each open link or image was dropped, so its end goes too
+    let mut dropped = Vec::new();
+    let options = Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH;
+    let parser = Parser::new_ext(md, options).filter_map(|event| match event {
+        Event::Html(html) | Event::InlineHtml(html) => Some(Event::Text(html)),
+        Event::Start(Tag::Image { .. }) => {
+            dropped.push(true);
+            None
+        }
+        Event::Start(Tag::Link { ref dest_url, .. }) => {
+            let safe = ["http://", "https://", "mailto:"].iter().any(|s| dest_url.starts_with(s));
+            dropped.push(!safe);
+            safe.then_some(event)
+        }
+        Event::End(TagEnd::Image | TagEnd::Link) => {
+            (!dropped.pop().unwrap_or_default()).then_some(event)
+        }
+        event => Some(event),
+    });
     let mut html_output = String::new();
     md_html::push_html(&mut html_output, parser);
     html_output
 }
 
+/// Split a text event around ticker mentions, wrapping each in a span.
+fn mark_tickers(text: CowStr<'_>) -> Vec<Event<'_>> {
+    let mut events = Vec::new();
+    let mut last = 0;
+    let mut start = None;
+    for (i, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+        if c.is_ascii_alphanumeric() || c == '$' || c == '.' {
+            start.get_or_insert(i);
+            continue;
+        }
+        let Some(word_start) = start.take() else { continue };
+        let word = text[word_start..i].trim_end_matches('.');
+        if let Some(symbol) = ticker_in(word) {
+            if last < word_start {
+                events.push(Event::Text(text[last..word_start].to_string().into()));
+            }
+            events.push(Event::InlineHtml(
+                format!(r#"<span class="ticker-mention" data-symbol="{symbol}">"#).into(),
+            ));
+            events.push(Event::Text(word.to_string().into()));
+            events.push(Event::InlineHtml("</span>".into()));
+            last = word_start + word.len();
+        }
+    }
+    if last == 0 {
+        return vec![Event::Text(text)];
+    }
+    if last < text.len() {
+        events.push(Event::Text(text[last..].to_string().into()));
+    }
+    events
+}
+
+fn document_hidden() -> bool {
+    web_sys::window()
+        .and_then(|w| w.document())
+        .is_some_and(|d| d.hidden())
+}
+
+/// Call `f(hidden)` whenever the tab is hidden or shown, until the current owner is
+/// cleaned up.
+fn on_visibility_change(f: impl Fn(bool) + 'static) {
+    let Some(document) = web_sys::window().and_then(|w| w.document()) else {
+        return;
+    };
+    let callback = wasm_bindgen::closure::Closure::<dyn Fn()>::new(move || f(document_hidden()));
+    let _ = document
+        .add_event_listener_with_callback("visibilitychange", callback.as_ref().unchecked_ref());
+    let listener = SendWrapper::new((document, callback));
+    on_cleanup(move || {
+        let (document, callback) = listener.take();
+        let _ = document.remove_event_listener_with_callback(
+            "visibilitychange",
+            callback.as_ref().unchecked_ref(),
+        );
+    });
+}
+
+/// `window_event_listener` that is removed when the current owner is cleaned up. Leptos
+/// leaves window listeners in place, so once `App` is unmounted (a `<wxve-chat>` taken off
+/// the page) they would go on reading its disposed signals, which panics.
+fn window_listener<E: EventDescriptor + 'static>(event: E, cb: impl Fn(E::EventType) + 'static)
+where
+    E::EventType: JsCast,
+{
+    let handle = window_event_listener(event, cb);
+    on_cleanup(move || handle.remove());
+}
+
+/// Elements matching `selector` in the app: inside the `<wxve-chat>` shadow root when
+/// mounted as a custom element, otherwise in the document.
+fn query_all(selector: &str) -> Vec<web_sys::Element> {
+    let nodes = match element::shadow_root() {
+        Some(root) => root.query_selector_all(selector),
+        None => match web_sys::window().and_then(|w| w.document()) {
+            Some(document) => document.query_selector_all(selector),
+            None => return Vec::new(),
+        },
+    };
+    let Ok(nodes) = nodes else {
+        return Vec::new();
+    };
+    (0..nodes.length()).filter_map(|i| nodes.get(i)?.dyn_into().ok()).collect()
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+/// Read `key` in the open workspace (see `workspace::scoped`).
+fn load_json<T: for<'de> Deserialize<'de>>(key: &str) -> Option<T> {
+    let raw = local_storage()?.get_item(&workspace::scoped(key)).ok()??;
+    serde_json::from_str(&raw).ok()
+}
+
+fn save_json<T: Serialize>(key: &str, value: &T) {
+    if let Some(storage) = local_storage()
+        && let Ok(raw) = serde_json::to_string(value)
+    {
+        let _ = storage.set_item(&workspace::scoped(key), &raw);
+    }
+}
+
+/// Random id for client-created records such as conversations.
+fn new_id() -> String {
+    let random = (js_sys::Math::random() * f64::from(u32::MAX)) as u32;
+    format!("{:x}{random:08x}", js_sys::Date::now() as u64)
+}
+
+/// Common uppercase words in analysis text that aren't tickers
+const NOT_TICKERS: &[&str] = &[
+    "AI", "ATH", "ATL", "CEO", "CFO", "CPI", "EPS", "ETF", "EU", "FOMC", "GDP", "IPO", "MA",
+    "MACD", "NFP", "OK", "PE", "RSI", "SEC", "SMA", "EMA", "UK", "US", "USA", "USD", "YOY",
+];
+
+thread_local! {
+    /// Symbols in the portfolio and watchlist, kept up to date by `App`; they count as
+    /// tickers even when they're a single letter (`F`, `T`, `C`)
+    static HELD_SYMBOLS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The symbol a word refers to, if it looks like a `$CASHTAG`, a 2-5 letter all-caps
+/// ticker, or a held symbol.
+fn ticker_in(word: &str) -> Option<&str> {
+    let (cashtag, candidate) = match word.strip_prefix('$') {
+        Some(rest) => (true, rest),
+        None => (false, word),
+    };
+    let is_symbol = (1..=5).contains(&candidate.len())
+        && candidate.chars().all(|c| c.is_ascii_uppercase())
+        && (cashtag
+            || (candidate.len() >= 2 && !NOT_TICKERS.contains(&candidate))
+            || HELD_SYMBOLS.with(|held| held.borrow().iter().any(|s| s == candidate)));
+    is_symbol.then_some(candidate)
+}
+
+/// Pull likely ticker symbols out of free text: `$CASHTAGS` and 2-5 letter all-caps words.
+fn extract_symbols(text: &str) -> Vec<String> {
+    let mut symbols = Vec::new();
+    for word in text.split(|c: char| !(c.is_ascii_alphanumeric() || c == '$' || c == '.')) {
+        if let Some(candidate) = ticker_in(word.trim_end_matches('.'))
+            && !symbols.iter().any(|s| s == candidate)
+        {
+            symbols.push(candidate.to_string());
+        }
+    }
+    symbols
+}
+
+/// Hand `patch` from a `chart_update` chunk to every chart of `symbol` on the page, as
+/// `{type: "chart_update", patch}`; the chart's own script applies it.
+fn post_chart_update(symbol: &str, patch: &serde_json::Value) {
+    let Ok(patch) = js_sys::JSON::parse(&patch.to_string()) else {
+        return;
+    };
+    let msg = js_sys::Object::new();
+    let _ = js_sys::Reflect::set(&msg, &"type".into(), &"chart_update".into());
+    let _ = js_sys::Reflect::set(&msg, &"patch".into(), &patch);
+    for iframe in query_all(&format!("iframe[data-symbol=\"{symbol}\"]")) {
+        if let Some(content_window) = iframe
+            .dyn_into::<web_sys::HtmlIFrameElement>()
+            .ok()
+            .and_then(|iframe| iframe.content_window())
+        {
+            let _ = content_window.post_message(&msg, "*");
+        }
+    }
+}
+
+/// Tell a chart iframe which theme to render with.
+fn post_theme(iframe: &web_sys::HtmlIFrameElement, dark: bool) {
+    if let Some(content_window) = iframe.content_window() {
+        let msg = js_sys::Object::new();
+        let _ = js_sys::Reflect::set(&msg, &"type".into(), &"theme".into());
+        let _ = js_sys::Reflect::set(&msg, &"dark".into(), &dark.into());
+        let _ = content_window.post_message(&msg, "*");
+    }
+}
+
 // ----------------------------------------------------------------------------
 // Types - matches API contract
 // ----------------------------------------------------------------------------
 
-#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "lowercase")]
-enum Role {
-    User,
-    Assistant,
-}
-
-#[derive(Clone)]
+/// Saved with its message as what drew it rather than the HTML, which is large and goes
+/// stale; `ChartView` draws a chart without HTML again from `/chart`.
+#[derive(Clone, Serialize, Deserialize)]
 struct Chart {
     symbol: String,
+    #[serde(skip)]
     html: String,
+    #[serde(default)]
+    indicators: Vec<Indicator>,
+    /// What the chart tool was called with, shown under the chart to tweak and redraw
+    #[serde(default)]
+    params: ChartParams,
+    /// Milliseconds since the epoch
+    #[serde(default)]
+    drawn_at: f64,
+}
+
+impl Chart {
+    fn new(symbol: String, html: String, indicators: Vec<Indicator>, params: ChartParams) -> Self {
+        Chart { symbol, html, indicators, params, drawn_at: js_sys::Date::now() }
+    }
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -43,372 +417,2180 @@ struct Message {
     id: usize,
     role: Role,
     content: String,
-    #[serde(skip)]
+    /// Excerpt of an earlier assistant message this user message replies to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    quoted: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     charts: Vec<Chart>,
+    #[serde(skip)]
+    sentiment: Option<Sentiment>,
+    #[serde(skip)]
+    meta: Option<ResponseMeta>,
+    /// Marked from the message menu; listed in the outline
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pinned: bool,
+    /// Quick reactions to a response, also sent as telemetry
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    reactions: Vec<Reaction>,
+    /// How far a user message sent in this session got
+    #[serde(skip)]
+    delivery: Option<Delivery>,
+    /// Why this turn has no answer; `content` keeps the error as text for copying and export
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<TurnError>,
+    /// The stream died before this response was done; `content` is what arrived
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    interrupted: bool,
+}
+
+/// What the API sees of a message when it's sent as history
+impl From<&Message> for wxve_chat_client::Message {
+    fn from(message: &Message) -> Self {
+        wxve_chat_client::Message {
+            role: message.role,
+            content: message.content.clone(),
+            quoted: message.quoted.clone(),
+            charts: message
+                .charts
+                .iter()
+                .map(|chart| ChartRef {
+                    symbol: chart.symbol.clone(),
+                    timeframe: chart.params.timeframe.clone(),
+                    drawn_at: chart.drawn_at,
+                })
+                .collect(),
+        }
+    }
 }
 
-#[derive(Clone, Serialize)]
-struct ChatRequest {
-    message: String,
-    history: Vec<Message>,
+/// A message as the backend keeps it, as in a conversation synced from another device.
+/// Its charts are redrawn from what they were drawn with.
+impl From<wxve_chat_client::Message> for Message {
+    fn from(message: wxve_chat_client::Message) -> Self {
+        Message {
+            id: 0,
+            role: message.role,
+            content: message.content,
+            quoted: message.quoted,
+            charts: message
+                .charts
+                .into_iter()
+                .map(|chart| Chart {
+                    symbol: chart.symbol,
+                    html: String::new(),
+                    indicators: Vec::new(),
+                    params: ChartParams { timeframe: chart.timeframe, ..ChartParams::default() },
+                    drawn_at: chart.drawn_at,
+                })
+                .collect(),
+            sentiment: None,
+            meta: None,
+            pinned: false,
+            reactions: Vec::new(),
+            delivery: None,
+            error: None,
+            interrupted: false,
+        }
+    }
+}
+
+/// Identifiers and timings for one assistant response, for bug reports
+#[derive(Clone, PartialEq)]
+struct ResponseMeta {
+    request_id: String,
+    model: Option<String>,
+    /// Milliseconds since the epoch
+    started_at: f64,
+    first_token_at: Option<f64>,
+    finished_at: Option<f64>,
+}
+
+impl ResponseMeta {
+    fn new(request_id: String) -> Self {
+        ResponseMeta {
+            request_id,
+            model: None,
+            started_at: js_sys::Date::now(),
+            first_token_at: None,
+            finished_at: None,
+        }
+    }
+
+    /// Seconds from `started_at` to `at`, e.g. `1.24s`.
+    fn elapsed(&self, at: Option<f64>) -> Option<String> {
+        at.map(|at| format!("{:.2}s", (at - self.started_at) / 1000.0))
+    }
+}
+
+/// Directional call for an analysis, from -1.0 (bearish) to 1.0 (bullish)
+#[derive(Clone, Copy, PartialEq)]
+struct Sentiment {
+    score: f64,
+}
+
+impl Sentiment {
+    fn label(self) -> &'static str {
+        match self.score {
+            s if s <= -0.2 => "Bearish",
+            s if s >= 0.2 => "Bullish",
+            _ => "Neutral",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct Quote {
+    symbol: String,
+    price: f64,
+    change_percent: f64,
+    /// ISO code the price is quoted in
+    #[serde(default = "default_currency")]
+    currency: String,
+}
+
+fn default_currency() -> String {
+    "USD".to_string()
 }
 
 #[derive(Deserialize)]
-#[serde(tag = "type", rename_all = "snake_case")]
-enum StreamChunk {
-    Text { content: String },
-    ToolStart { name: String },
-    #[allow(dead_code)]
-    ToolEnd { name: String },
-    Chart { symbol: String, html: String },
-    Done,
-    Error { message: String },
+struct QuotesResponse {
+    quotes: Vec<Quote>,
+}
+
+#[derive(Serialize)]
+struct AlertRequest {
+    symbol: String,
+    price: f64,
+}
+
+#[derive(Deserialize)]
+struct AlertResponse {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AlertTrigger {
+    id: String,
+    price: f64,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+struct Article {
+    symbol: String,
+    title: String,
+    url: String,
+    source: String,
+    published_at: String,
+}
+
+#[derive(Deserialize)]
+struct NewsResponse {
+    articles: Vec<Article>,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+struct EarningsReport {
+    symbol: String,
+    /// ISO date of the report
+    date: String,
+    /// "bmo" (before market open) or "amc" (after market close), when known
+    #[serde(default)]
+    time: Option<String>,
+    #[serde(default)]
+    eps_estimate: Option<f64>,
+    #[serde(default)]
+    revenue_estimate: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct EarningsResponse {
+    earnings: Vec<EarningsReport>,
+}
+
+#[derive(Clone, Deserialize)]
+struct HeatmapTile {
+    symbol: String,
+    name: String,
+    market_cap: f64,
+    change_percent: f64,
+}
+
+#[derive(Clone, Deserialize)]
+struct HeatmapSector {
+    name: String,
+    tiles: Vec<HeatmapTile>,
+}
+
+impl HeatmapSector {
+    fn market_cap(&self) -> f64 {
+        self.tiles.iter().map(|t| t.market_cap).sum()
+    }
+}
+
+#[derive(Deserialize)]
+struct HeatmapResponse {
+    sectors: Vec<HeatmapSector>,
+}
+
+#[derive(Clone, Deserialize)]
+struct SymbolStats {
+    name: String,
+    price: f64,
+    change_percent: f64,
+    #[serde(default = "default_currency")]
+    currency: String,
+    #[serde(default)]
+    market_cap: Option<f64>,
+    #[serde(default)]
+    pe_ratio: Option<f64>,
+    #[serde(default)]
+    high_52w: Option<f64>,
+    #[serde(default)]
+    low_52w: Option<f64>,
+    #[serde(default)]
+    day_high: Option<f64>,
+    #[serde(default)]
+    day_low: Option<f64>,
+    #[serde(default)]
+    volume: Option<f64>,
+}
+
+#[derive(Clone, Default, Serialize)]
+struct ScreenerRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_market_cap: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_market_cap: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sector: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rsi_min: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rsi_max: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    wave_pattern: Option<String>,
+}
+
+#[derive(Clone, Deserialize)]
+struct ScreenerResult {
+    symbol: String,
+    name: String,
+    sector: String,
+    market_cap: f64,
+    price: f64,
+    change_percent: f64,
+    rsi: f64,
+    #[serde(default)]
+    wave_pattern: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ScreenerResponse {
+    results: Vec<ScreenerResult>,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+struct EconomicEvent {
+    name: String,
+    /// ISO date of the release
+    date: String,
+    #[serde(default)]
+    time: Option<String>,
+    /// "high", "medium" or "low"
+    importance: String,
+    #[serde(default)]
+    forecast: Option<String>,
+    #[serde(default)]
+    previous: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct EconomicCalendarResponse {
+    events: Vec<EconomicEvent>,
+}
+
+#[derive(Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ServiceState {
+    Operational,
+    Degraded,
+    Outage,
+}
+
+#[derive(Clone, Deserialize)]
+struct ApiStatus {
+    status: ServiceState,
+    #[serde(default)]
+    message: Option<String>,
+    #[serde(default)]
+    incident_url: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ChartRequest {
+    symbol: String,
+    indicators: Vec<Indicator>,
+    #[serde(flatten)]
+    params: ChartParams,
+}
+
+#[derive(Deserialize)]
+struct ChartResponse {
+    html: String,
 }
 
 // ----------------------------------------------------------------------------
-// SSE Client - POST to /chat and stream response
+// Settings - persisted to localStorage
 // ----------------------------------------------------------------------------
 
-async fn send_message(
-    message: String,
-    history: Vec<Message>,
-    on_chunk: impl Fn(StreamChunk) + 'static,
+const SETTINGS_KEY: &str = "wxve-chat:settings";
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct Settings {
+    /// Indicators overlaid on newly rendered charts
+    indicators: Vec<Indicator>,
+    /// Symbols shown in the watchlist panel
+    watchlist: Vec<String>,
+    /// Scroll live watchlist quotes across the top of the page
+    show_ticker: bool,
+    /// Convert quoted prices into this currency (`None` shows them as quoted)
+    display_currency: Option<String>,
+    /// Chime volume (0.0-1.0) for finished responses and triggered alerts; `None` is off
+    completion_sound: Option<f64>,
+    /// Send anonymous usage events (opt-in)
+    telemetry: bool,
+    /// Send crash and failed-request reports (opt-in)
+    error_reports: bool,
+    /// Pace streamed text is shown at
+    typing_speed: TypingSpeed,
+    /// Messages kept in memory before the oldest move to IndexedDB (`history::cap`)
+    history_cap: Option<usize>,
+    /// Backend tools the model may not call, by name
+    disabled_tools: Vec<String>,
+    /// Ask before each tool call the model makes
+    approve_tools: bool,
+    /// Show each chat request for trimming before it's sent (`request_preview.rs`)
+    review_requests: bool,
+    /// Dark theme, unless the page embedding the app picks one
+    dark_mode: bool,
+    /// When anything kept in `/profile` last changed here (`profile.rs`); 0 if never
+    preferences_updated_at: f64,
+}
+
+impl Settings {
+    fn load() -> Self {
+        load_json(SETTINGS_KEY).unwrap_or_default()
+    }
+
+    fn save(&self) {
+        save_json(SETTINGS_KEY, self);
+    }
+
+    fn play_sound(&self) {
+        if let Some(volume) = self.completion_sound {
+            sound::chime(volume);
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+// API Client - fetch from the Wxve API (SSE streams for /chat and quotes)
+// ----------------------------------------------------------------------------
+
+async fn fetch(path: &str, opts: &RequestInit) -> Result<Response, String> {
+    if let Some(response) = replay::fetch(path) {
+        return response;
+    }
+    if mock::enabled() {
+        return mock::fetch(path, opts).await;
+    }
+    let result = fetch_unreported(path, opts).await;
+    if let Err(e) = &result {
+        reporting::request_failed(path, e);
+    }
+    result
+}
+
+/// Where the API is: the `<wxve-chat endpoint="...">` when set, `API_URL` otherwise.
+fn api_base() -> String {
+    element::endpoint().unwrap_or_else(|| API_URL.to_string())
+}
+
+async fn fetch_unreported(path: &str, opts: &RequestInit) -> Result<Response, String> {
+    let session_id = session::session_id();
+    let headers: Vec<(&str, &str)> =
+        session_id.iter().map(|id| ("X-Session-Id", id.as_str())).collect();
+    web::fetch(&format!("{}{path}", api_base()), opts, &headers).await
+}
+
+async fn post_json(path: &str, body: &impl Serialize) -> Result<Response, String> {
+    fetch(path, &web::post_json(body, &[])?).await
+}
+
+async fn get_json<T: for<'de> Deserialize<'de>>(path: &str) -> Result<T, String> {
+    let response = fetch(path, &RequestInit::new()).await?;
+    read_json(response).await
+}
+
+async fn read_json<T: for<'de> Deserialize<'de>>(response: Response) -> Result<T, String> {
+    web::read_json(response).await
+}
+
+/// `symbols` for a `?symbols=` query: each encoded, as they may come from user input.
+fn symbols_query(symbols: &[String]) -> String {
+    let encoded: Vec<String> =
+        symbols.iter().map(|s| String::from(js_sys::encode_uri_component(s))).collect();
+    encoded.join(",")
+}
+
+/// Re-render a chart with a different set of indicator overlays or tool parameters.
+async fn render_chart(
+    symbol: String,
+    indicators: Vec<Indicator>,
+    params: ChartParams,
+) -> Result<String, String> {
+    let response = post_json("/chart", &ChartRequest { symbol, indicators, params }).await?;
+    let chart: ChartResponse = read_json(response).await?;
+    Ok(chart.html)
+}
+
+async fn fetch_quotes(symbols: &[String]) -> Result<Vec<Quote>, String> {
+    let response: QuotesResponse = get_json(&format!("/quotes?symbols={}", symbols_query(symbols))).await?;
+    Ok(response.quotes)
+}
+
+/// Keep a streaming subscription open, reconnecting after failures until `signal` aborts.
+/// `connect` gets a callback to fire once the stream is open, which clears `label` from
+/// the retrying streams in `connection`.
+async fn keep_subscribed<F, Fut>(
+    label: &str,
+    connection: RwSignal<Connection>,
+    signal: &web_sys::AbortSignal,
+    mut connect: F,
+) where
+    F: FnMut(Callback<()>) -> Fut,
+    Fut: Future<Output = Result<(), String>>,
+{
+    let opened = {
+        let label = label.to_string();
+        Callback::new(move |()| {
+            let mut recovered = false;
+            connection.update(|c| recovered = c.opened(&label));
+            if recovered {
+                toast::success(format!("{label} stream reconnected"));
+            }
+        })
+    };
+    while !signal.aborted() {
+        let result = connect(opened).await;
+        if signal.aborted() {
+            break;
+        }
+        if let Err(e) = result {
+            log::warn!(target: "transport", "{label} stream failed, reconnecting: {e}");
+            connection.update(|c| c.failed(label, e));
+        }
+        sleep(RECONNECT_DELAY).await;
+    }
+    connection.update(|c| {
+        c.opened(label);
+    });
+}
+
+/// Stream quote updates until the server closes the stream or `signal` aborts it.
+async fn subscribe_quotes(
+    symbols: &[String],
+    signal: &web_sys::AbortSignal,
+    on_open: Callback<()>,
+    on_quote: impl Fn(Quote),
 ) -> Result<(), String> {
-    let window = web_sys::window().ok_or("no window")?;
+    let opts = RequestInit::new();
+    opts.set_signal(Some(signal));
+    let response = fetch(&format!("/quotes/stream?symbols={}", symbols_query(symbols)), &opts).await?;
+    on_open.run(());
 
-    let request_body = ChatRequest { message, history };
-    let body_json = serde_json::to_string(&request_body).map_err(|e| e.to_string())?;
+    read_sse(response, signal, |quote| {
+        on_quote(quote);
+        true
+    })
+    .await
+}
 
+async fn fetch_news(symbols: &[String]) -> Result<Vec<Article>, String> {
+    let response: NewsResponse = get_json(&format!("/news?symbols={}", symbols_query(symbols))).await?;
+    Ok(response.articles)
+}
+
+async fn fetch_earnings(symbols: &[String]) -> Result<Vec<EarningsReport>, String> {
+    let response: EarningsResponse =
+        get_json(&format!("/earnings?symbols={}", symbols_query(symbols))).await?;
+    Ok(response.earnings)
+}
+
+async fn fetch_heatmap() -> Result<Vec<HeatmapSector>, String> {
+    let response: HeatmapResponse = get_json("/heatmap").await?;
+    Ok(response.sectors)
+}
+
+#[derive(Deserialize)]
+struct FxResponse {
+    rates: FxRates,
+}
+
+/// Exchange rates as units per US dollar.
+async fn fetch_fx_rates() -> Result<FxRates, String> {
+    let response: FxResponse = get_json("/fx").await?;
+    Ok(response.rates)
+}
+
+async fn fetch_symbol_stats(symbol: &str) -> Result<SymbolStats, String> {
+    get_json(&format!("/symbols/{}", js_sys::encode_uri_component(symbol))).await
+}
+
+/// Run the backend screener tool with structured filters.
+async fn run_screener(request: &ScreenerRequest) -> Result<Vec<ScreenerResult>, String> {
+    let response = post_json("/screener", request).await?;
+    let screener: ScreenerResponse = read_json(response).await?;
+    Ok(screener.results)
+}
+
+async fn fetch_status() -> Result<ApiStatus, String> {
+    get_json("/status").await
+}
+
+async fn fetch_remote_config() -> Result<RemoteConfig, String> {
+    get_json("/config").await
+}
+
+async fn fetch_tools() -> Result<Vec<Tool>, String> {
+    let response: ToolsResponse = get_json("/tools").await?;
+    Ok(response.tools)
+}
+
+async fn fetch_economic_calendar() -> Result<Vec<EconomicEvent>, String> {
+    let response: EconomicCalendarResponse = get_json("/economic-calendar").await?;
+    Ok(response.events)
+}
+
+/// Register a price alert, returning the server-assigned id.
+async fn register_alert(symbol: String, price: f64) -> Result<String, String> {
+    let response = post_json("/alerts", &AlertRequest { symbol, price }).await?;
+    let alert: AlertResponse = read_json(response).await?;
+    Ok(alert.id)
+}
+
+#[derive(Deserialize)]
+struct DeleteConversationsResponse {
+    deleted: usize,
+}
+
+#[derive(Deserialize)]
+struct ConversationsResponse {
+    conversations: Vec<SyncedConversation>,
+}
+
+/// Every conversation the backend has stored for this user (`conversation_history`).
+async fn fetch_synced_conversations() -> Result<Vec<SyncedConversation>, String> {
+    let response: ConversationsResponse = get_json("/conversations").await?;
+    Ok(response.conversations)
+}
+
+/// Ask the backend to delete every conversation it has stored for this user.
+async fn delete_synced_conversations() -> Result<usize, String> {
+    let opts = RequestInit::new();
+    opts.set_method("DELETE");
+    let response: DeleteConversationsResponse = read_json(fetch("/conversations", &opts).await?).await?;
+    Ok(response.deleted)
+}
+
+async fn delete_alert(id: &str) -> Result<(), String> {
     let opts = RequestInit::new();
-    opts.set_method("POST");
-    opts.set_mode(RequestMode::Cors);
-    opts.set_body(&wasm_bindgen::JsValue::from_str(&body_json));
+    opts.set_method("DELETE");
+    fetch(&format!("/alerts/{id}"), &opts).await?;
+    Ok(())
+}
+
+/// Stream alert triggers for the given alert ids until `signal` aborts.
+async fn subscribe_alert_triggers(
+    ids: &[String],
+    signal: &web_sys::AbortSignal,
+    on_open: Callback<()>,
+    on_trigger: impl Fn(AlertTrigger),
+) -> Result<(), String> {
+    let opts = RequestInit::new();
+    opts.set_signal(Some(signal));
+    let response = fetch(&format!("/alerts/stream?ids={}", ids.join(",")), &opts).await?;
+    on_open.run(());
+
+    read_sse(response, signal, |trigger| {
+        on_trigger(trigger);
+        true
+    })
+    .await
+}
+
+/// The browser transport as the app uses it: requests go through `fetch` (replays, mocks,
+/// the session header and error reports), `/chat` bodies are compressed when the backend
+/// takes it, `signal` stops everything, and streams are recorded for the debug panel.
+struct ApiTransport<'a> {
+    signal: Option<&'a web_sys::AbortSignal>,
+    stream: RefCell<Option<StreamLog>>,
+}
+
+impl<'a> ApiTransport<'a> {
+    fn new(signal: Option<&'a web_sys::AbortSignal>) -> Self {
+        ApiTransport { signal, stream: RefCell::new(None) }
+    }
+
+    /// Start reading `response`, recording it in the debug panel until `close`.
+    fn open(&self, response: &Response) -> Result<web::Body, String> {
+        self.stream.replace(Some(StreamLog::open(&response.url(), response.status())));
+        web::Body::new(response)
+    }
+
+    fn close(&self, result: &Result<(), String>) {
+        if let Some(stream) = self.stream.take() {
+            stream.close(result);
+        }
+    }
+
+    fn record(&self, record: impl FnOnce(&StreamLog)) {
+        if let Some(stream) = self.stream.borrow().as_ref() {
+            record(stream);
+        }
+    }
+}
+
+impl Transport for ApiTransport<'_> {
+    type Body = web::Body;
+
+    async fn post(&self, path: &str, body: &str, headers: &[(&str, &str)]) -> Result<web::Body, String> {
+        let opts = compression::post_json(body, headers).await?;
+        opts.set_signal(self.signal);
+        let response = match fetch(path, &opts).await {
+            Err(e) if e == "HTTP 415" && compression::refused() => {
+                let opts = web::post_json_str(body, headers)?;
+                opts.set_signal(self.signal);
+                fetch(path, &opts).await?
+            }
+            response => response?,
+        };
+        self.open(&response)
+    }
+
+    async fn read(&self, body: &mut web::Body, timeout: Option<Duration>) -> Result<Read, String> {
+        web::read(body, timeout).await
+    }
+
+    async fn sleep(&self, duration: Duration) {
+        sleep(duration).await;
+    }
+
+    fn now(&self) -> f64 {
+        js_sys::Date::now()
+    }
+
+    fn cancelled(&self) -> bool {
+        self.signal.is_some_and(web_sys::AbortSignal::aborted)
+    }
 
-    let request = Request::new_with_str_and_init("https://api.wxve.io/chat", &opts)
-        .map_err(|e| format!("{e:?}"))?;
-    request
-        .headers()
-        .set("Content-Type", "application/json")
-        .map_err(|e| format!("{e:?}"))?;
+    fn line(&self, line: &str) {
+        self.record(|stream| stream.line(line));
+    }
 
-    let resp_value = JsFuture::from(window.fetch_with_request(&request))
-        .await
-        .map_err(|e| format!("{e:?}"))?;
-    let response: Response = resp_value.dyn_into().map_err(|e| format!("{e:?}"))?;
+    fn parsed(&self, event: &dyn std::fmt::Debug) {
+        self.record(|stream| stream.parsed(event));
+    }
 
-    if !response.ok() {
-        return Err(format!("HTTP {}", response.status()));
+    fn skipped(&self, error: &str) {
+        self.record(|stream| stream.failed(error));
     }
+}
 
-    let body = response.body().ok_or("no body")?;
-    let reader = body
-        .get_reader()
-        .dyn_into::<web_sys::ReadableStreamDefaultReader>()
-        .map_err(|e| format!("{e:?}"))?;
+/// Stream the reply to a chat request into `on_chunk` with `chat::send_message`, until it's
+/// done or `signal` aborts; no chunk is delivered after the abort. Requests and chunks are
+/// also shown to the inspector and recorded for replays.
+async fn send_message(
+    request: impl Serialize,
+    request_id: &str,
+    signal: Option<&web_sys::AbortSignal>,
+    on_chunk: impl Fn(StreamChunk),
+) -> Result<(), String> {
+    inspector::begin(request_id, &request);
+    replay::begin(request_id, &request);
+    let transport = ApiTransport::new(signal);
+    let result = chat::send_message(&transport, &request, request_id, |chunk| {
+        inspector::chunk(request_id, &chunk);
+        replay::chunk(request_id, &chunk);
+        on_chunk(chunk);
+    })
+    .await;
+    transport.close(&result);
+    result
+}
 
-    let mut buffer = String::new();
+/// Parse each SSE `data:` payload of a subscription as `T` and feed it to `on_event` until
+/// it returns false, the body ends or `signal` aborts (see `sse::read_events`). Everything
+/// read is also recorded for the debug panel.
+async fn read_sse<T: for<'de> Deserialize<'de> + std::fmt::Debug>(
+    response: Response,
+    signal: &web_sys::AbortSignal,
+    on_event: impl FnMut(T) -> bool,
+) -> Result<(), String> {
+    let transport = ApiTransport::new(Some(signal));
+    let result = async {
+        let mut body = transport.open(&response)?;
+        sse::read_events(&transport, &mut body, HEARTBEAT_TIMEOUT, on_event).await
+    }
+    .await;
+    transport.close(&result);
+    result
+}
 
-    loop {
-        let result = JsFuture::from(reader.read())
-            .await
-            .map_err(|e| format!("{e:?}"))?;
+// ----------------------------------------------------------------------------
+// UI Component
+// ----------------------------------------------------------------------------
 
-        let done = js_sys::Reflect::get(&result, &"done".into())
-            .map_err(|e| format!("{e:?}"))?
-            .as_bool()
-            .unwrap_or(true);
+const RECENT_SYMBOLS_KEY: &str = "wxve-chat:recent-symbols";
+const MAX_RECENT_SYMBOLS: usize = 8;
 
-        if done {
-            break;
-        }
+/// Messages of history sent with each request in an incognito chat
+const INCOGNITO_HISTORY: usize = 10;
 
-        let value = js_sys::Reflect::get(&result, &"value".into())
-            .map_err(|e| format!("{e:?}"))?;
-        let array = js_sys::Uint8Array::new(&value);
-        let mut bytes = vec![0u8; array.length() as usize];
-        array.copy_to(&mut bytes);
+/// Side panels; at most one is open at a time
+#[derive(Clone, Copy, PartialEq)]
+enum Panel {
+    Watchlist,
+    Alerts,
+    Portfolio,
+    News,
+    Economy,
+    Outline,
+    Conversations,
+}
 
-        buffer.push_str(&String::from_utf8_lossy(&bytes));
+fn toggle_panel(open_panel: RwSignal<Option<Panel>>, panel: Panel) {
+    open_panel.update(|open| *open = if *open == Some(panel) { None } else { Some(panel) });
+}
 
-        // Process complete SSE lines
-        while let Some(newline_pos) = buffer.find('\n') {
-            let line = buffer[..newline_pos].trim().to_string();
-            buffer = buffer[newline_pos + 1..].to_string();
+#[component]
+fn App() -> impl IntoView {
+    let (input, set_input) = signal(String::new());
+    let reply_quote = RwSignal::new(None::<String>);
+    // The composer shows its markdown rendered as it will be once sent
+    let previewing = RwSignal::new(false);
+    let editing = RwSignal::new(None::<usize>);
+    let (dark_mode, set_dark_mode) = signal(false);
+    let settings = RwSignal::new(Settings::load());
+    let quotes = RwSignal::new(HashMap::<String, Quote>::new());
+    let alerts = RwSignal::new(alerts::load_alerts());
+    let portfolio = RwSignal::new(portfolio::load_positions());
+    let open_panel = RwSignal::new(None::<Panel>);
+    let route = use_route();
+    let vault = RwSignal::new(Vault::load());
+    let fx_rates = use_fx_rates(settings);
+    let connection = RwSignal::new(Connection::default());
+    let remote_config = use_remote_config();
+    use_profile_sync(settings, remote_config);
+    let enabled = move |feature: &str| remote_config.with(|c| c.enabled(feature));
+    let flags = use_flags(remote_config);
+    let unread = use_unread();
+    let state = AppState::provide(settings, alerts, unread, remote_config);
+    let element = use_context::<ElementConfig>();
+    let embedded = embed::enabled() || element.is_some();
+    let AppState {
+        messages, forks, loading, current_response, tool_running, phase, conversations, incognito, ..
+    } = state;
+    let conversation_title = Memo::new(move |_| {
+        let id = state.conversation_id.get();
+        conversations
+            .with(|c| c.iter().find(|c| c.id == id).map(|c| c.title.clone()))
+            .filter(|title| !title.is_empty())
+    });
+    unread::use_document_title(unread, conversation_title);
+    sound::unlock_on_gesture();
+    use_telemetry(settings);
+    use_error_reporting(settings);
+    use_replay();
+    let dashboard_tiles = RwSignal::new(dashboard::load_tiles());
+    let pin = Callback::new(move |symbol: String| {
+        toast::info(format!("Pinned {symbol} to the dashboard"));
+        dashboard::pin_chart(dashboard_tiles, symbol);
+    });
 
-            if let Some(data) = line.strip_prefix("data: ") {
-                if let Ok(chunk) = serde_json::from_str::<StreamChunk>(data) {
-                    let is_done = matches!(chunk, StreamChunk::Done);
-                    on_chunk(chunk);
-                    if is_done {
-                        return Ok(());
-                    }
-                }
+    let watchlist_symbols = Memo::new(move |_| settings.with(|s| s.watchlist.clone()));
+    let held_symbols = Memo::new(move |_| {
+        let mut symbols: Vec<String> = portfolio.with(|p| p.iter().map(|p| p.symbol.clone()).collect());
+        for symbol in watchlist_symbols.get() {
+            if !symbols.contains(&symbol) {
+                symbols.push(symbol);
             }
         }
-    }
+        symbols
+    });
 
-    Ok(())
-}
+    // What `ticker_in` takes for held symbols: set before the first render so messages
+    // already mark single-letter ones, and by `discussed_symbols` before it reads messages
+    let hold = move |symbols: &Vec<String>| HELD_SYMBOLS.with(|h| h.borrow_mut().clone_from(symbols));
+    held_symbols.with_untracked(hold);
+    Effect::new(move |_| held_symbols.with(hold));
 
-// ----------------------------------------------------------------------------
-// UI Component
-// ----------------------------------------------------------------------------
+    // Symbols that came up in the conversation: charted first, then mentioned in text
+    let discussed_symbols = Memo::new(move |_| {
+        held_symbols.with(hold);
+        messages.with(|msgs| {
+            let mut symbols: Vec<String> = Vec::new();
+            let charted = msgs.iter().flat_map(|m| m.charts.iter().map(|c| c.symbol.clone()));
+            let mentioned = msgs.iter().flat_map(|m| extract_symbols(&m.content));
+            for symbol in charted.chain(mentioned) {
+                if !symbols.contains(&symbol) {
+                    symbols.push(symbol);
+                }
+            }
+            symbols
+        })
+    });
 
-#[component]
-fn App() -> impl IntoView {
-    let (messages, set_messages) = create_signal(Vec::<Message>::new());
-    let (input, set_input) = create_signal(String::new());
-    let (loading, set_loading) = create_signal(false);
-    let (current_response, set_current_response) = create_signal(String::new());
-    let (next_id, set_next_id) = create_signal(0usize);
-    let (tool_running, set_tool_running) = create_signal::<Option<String>>(None);
-    let (pending_charts, set_pending_charts) = create_signal(Vec::<Chart>::new());
-    let (dark_mode, set_dark_mode) = create_signal(false);
+    Effect::new(move |_| settings.with(Settings::save));
+    Effect::new(move |_| alerts.with(|a| alerts::save_alerts(a)));
+    Effect::new(move |_| portfolio.with(|p| portfolio::save_positions(p)));
+    Effect::new(move |_| vault.with(|v| conversations.with(|c| vault::save(v, c))));
+    Effect::new(move |_| dashboard_tiles.with(|t| dashboard::save_tiles(t)));
 
-    let toggle_dark_mode = move |_| {
-        let new_value = !dark_mode.get();
-        set_dark_mode.set(new_value);
-        if let Some(body) = web_sys::window()
-            .and_then(|w| w.document())
-            .and_then(|d| d.body())
-        {
-            if new_value {
-                let _ = body.class_list().add_1("dark");
+    // A custom element themes itself rather than the host page's body
+    let theme_root = StoredValue::new(element.as_ref().map(|e| e.host.clone()));
+    let set_dark = move |dark: bool| {
+        set_dark_mode.set(dark);
+        let root = theme_root.get_value().or_else(|| {
+            web_sys::window()
+                .and_then(|w| w.document())
+                .and_then(|d| d.body())
+        });
+        if let Some(root) = root {
+            if dark {
+                let _ = root.class_list().add_1("dark");
             } else {
-                let _ = body.class_list().remove_1("dark");
+                let _ = root.class_list().remove_1("dark");
             }
         }
     };
+    let toggle_dark_mode = move |_| {
+        let dark = !dark_mode.get();
+        set_dark(dark);
+        // A theme the host page chose isn't the user's to keep
+        if !embedded {
+            settings.update(|s| s.dark_mode = dark);
+        }
+    };
+    if !embedded {
+        Effect::new(move |_| set_dark(settings.with(|s| s.dark_mode)));
+    }
+    if embed::enabled() {
+        use_embed(set_input, Callback::new(set_dark));
+    }
+    if let Some(ElementConfig { dark, prompt, .. }) = &element {
+        if let Some(dark) = *dark {
+            set_dark(dark);
+        }
+        if let Some(prompt) = prompt {
+            set_input.set(prompt.clone());
+        }
+    } else if let Some(launch) = launch::take() {
+        // `?q=` links and content shared from other apps launch straight into a question
+        if !(launch.send && state.send(launch.question.clone(), None)) {
+            set_input.set(launch.question);
+        }
+    } else if let Some(link) = permalink::take() {
+        spawn_local(permalink::open(state, link));
+    }
 
     // Sync theme to chart iframes
-    create_effect(move |_| {
+    Effect::new(move |_| {
         let dark = dark_mode.get();
-        if let Some(document) = web_sys::window().and_then(|w| w.document()) {
-            if let Ok(iframes) = document.query_selector_all(".chart-container iframe") {
-                for i in 0..iframes.length() {
-                    if let Some(iframe) = iframes.get(i) {
-                        if let Some(iframe_el) = iframe.dyn_ref::<web_sys::HtmlIFrameElement>() {
-                            if let Some(content_window) = iframe_el.content_window() {
-                                let msg = js_sys::Object::new();
-                                let _ = js_sys::Reflect::set(&msg, &"type".into(), &"theme".into());
-                                let _ = js_sys::Reflect::set(&msg, &"dark".into(), &dark.into());
-                                let _ = content_window.post_message(&msg, "*");
-                            }
-                        }
-                    }
-                }
+        for iframe in query_all(".chart-container iframe") {
+            if let Some(iframe_el) = iframe.dyn_ref::<web_sys::HtmlIFrameElement>() {
+                post_theme(iframe_el, dark);
             }
         }
     });
 
-    let do_send = move || {
-        let msg = input.get();
-        if msg.trim().is_empty() || loading.get() {
-            return;
+    let send = move |msg: String| {
+        state.send(msg, None);
+    };
+
+    let comparing = Memo::new(move |_| state.compare_panes.with(|p| !p.is_empty()));
+    // The message swiped open to its quick actions on touch screens
+    let swiped = RwSignal::new(None::<usize>);
+    // The message whose action menu is open
+    let menu_open = RwSignal::new(None::<usize>);
+    message_menu::use_dismiss(menu_open);
+    let selection = selection::use_selection();
+    // The message j/k moved to, which y/r/d act on
+    let focused = RwSignal::new(None::<usize>);
+    message_keys::use_message_keys(focused);
+
+    // Hide saved conversations until the passphrase is entered again
+    let lock = move || {
+        if state.new_conversation() {
+            vault.set(Vault::Locked);
+            conversations.set(Vec::new());
         }
+    };
 
-        set_input.set(String::new());
-        set_loading.set(true);
-        set_current_response.set(String::new());
-        set_pending_charts.set(Vec::new());
+    let do_send = move || {
+        if state.send(input.get(), reply_quote.get()) {
+            set_input.set(String::new());
+            reply_quote.set(None);
+        }
+    };
 
-        // Capture history BEFORE adding user message to avoid duplication
-        let history = messages.get();
+    window_listener(leptos::ev::keydown, move |ev| {
+        if ev.key() == "?" && !is_typing(&ev) {
+            ev.prevent_default();
+            dialog::open(ShortcutsHelp);
+        }
+    });
 
-        // Add user message to history
-        let id = next_id.get();
-        set_next_id.set(id + 1);
-        set_messages.update(|msgs| {
-            msgs.push(Message {
-                id,
-                role: Role::User,
-                content: msg.clone(),
-                charts: Vec::new(),
+    // While the tab is hidden or the reader has paused, the stream keeps accumulating
+    // into `current_response`, but markdown rendering and scrolling wait for it
+    let (page_visible, set_page_visible) = signal(!document_hidden());
+    on_visibility_change(move |hidden| set_page_visible.set(!hidden));
+    let paused = RwSignal::new(false);
+    let displayed_response = Memo::new(move |previous: Option<&String>| {
+        if page_visible.get() && !paused.get() {
+            current_response.get()
+        } else {
+            previous.cloned().unwrap_or_default()
+        }
+    });
+    // A response that finishes while paused is committed whole, but its message stays as
+    // far as it had got (id, text shown) until Resume; the next response lets it go
+    let held_back = RwSignal::new(None::<(usize, String)>);
+    Effect::new(move |was_loading: Option<bool>| {
+        let is_loading = loading.get();
+        if is_loading && was_loading == Some(false) {
+            held_back.set(None);
+            paused.set(false);
+        } else if !is_loading && was_loading == Some(true) && paused.get_untracked() {
+            let finished = messages.with_untracked(|m| {
+                m.last().filter(|m| m.role == Role::Assistant && m.error.is_none()).map(|m| m.id)
             });
-        });
-
-        spawn_local(async move {
-            let result = send_message(msg, history, move |chunk| match chunk {
-                StreamChunk::Text { content } => {
-                    set_current_response.update(|r| r.push_str(&content));
-                }
-                StreamChunk::Chart { symbol, html } => {
-                    set_pending_charts.update(|charts| {
-                        charts.push(Chart { symbol, html });
-                    });
-                }
-                StreamChunk::Done => {
-                    let response = current_response.get();
-                    let charts = pending_charts.get();
-                    let id = next_id.get();
-                    set_next_id.set(id + 1);
-                    set_messages.update(|msgs| {
-                        msgs.push(Message {
-                            id,
-                            role: Role::Assistant,
-                            content: response,
-                            charts,
-                        });
-                    });
-                    set_current_response.set(String::new());
-                    set_pending_charts.set(Vec::new());
-                    set_loading.set(false);
-                }
-                StreamChunk::Error { message } => {
-                    let id = next_id.get();
-                    set_next_id.set(id + 1);
-                    set_messages.update(|msgs| {
-                        msgs.push(Message {
-                            id,
-                            role: Role::Assistant,
-                            content: format!("Error: {message}"),
-                            charts: Vec::new(),
-                        });
-                    });
-                    set_loading.set(false);
-                }
-                StreamChunk::ToolStart { name } => {
-                    set_tool_running.set(Some(name));
-                }
-                StreamChunk::ToolEnd { .. } => {
-                    set_tool_running.set(None);
-                    set_current_response.update(|r| r.push_str("\n\n"));
-                }
-            })
-            .await;
-
-            if let Err(e) = result {
-                let id = next_id.get();
-                set_next_id.set(id + 1);
-                set_messages.update(|msgs| {
-                    msgs.push(Message {
-                        id,
-                        role: Role::Assistant,
-                        content: format!("Error: {e}"),
-                        charts: Vec::new(),
-                    });
-                });
-                set_loading.set(false);
+            match finished {
+                Some(id) => held_back.set(Some((id, displayed_response.get_untracked()))),
+                None => paused.set(false),
             }
-        });
+        }
+        is_loading
+    });
+    let typed_response = use_typewriter(displayed_response, settings);
+    let buffered_chars = move || {
+        let shown = displayed_response.with(|r| r.chars().count());
+        current_response.with(|r| r.chars().count()).saturating_sub(shown)
     };
 
-    // Auto-scroll to bottom when streaming content
-    create_effect(move |_| {
-        current_response.get();
-        messages.get();
-        if let Some(window) = web_sys::window() {
-            if let Some(document) = window.document() {
-                if let Some(element) = document.document_element() {
-                    window.scroll_to_with_x_and_y(0.0, element.scroll_height() as f64);
-                }
-            }
+    let in_element = element.is_some();
+    // The host page owns the viewport around a custom element
+    let keyboard_inset = (!in_element).then(viewport::use_keyboard_inset);
+
+    // Only a new last message scrolls; earlier ones loaded above it don't
+    let last_message = Memo::new(move |_| messages.with(|m| m.last().map(|m| m.id)));
+    // Where each conversation was scrolled to when it was left, this session
+    let scroll_offsets = StoredValue::new(HashMap::<String, f64>::new());
+    let remember_scroll = move || {
+        let id = state.conversation_id.get_untracked();
+        scroll_offsets.update_value(|o| {
+            o.insert(id, scroll::position(in_element));
+        });
+    };
+    if !in_element {
+        window_listener(leptos::ev::scroll, move |_| remember_scroll());
+    }
+    // Auto-scroll to bottom when streaming content, or when the keyboard opens over it;
+    // switching back to a conversation returns to where it was left instead
+    Effect::new(move |shown: Option<String>| {
+        let conversation = state.conversation_id.get();
+        if !page_visible.get() {
+            return conversation;
+        }
+        typed_response.track();
+        last_message.track();
+        if let Some(inset) = keyboard_inset {
+            inset.track();
         }
+        let saved = shown
+            .is_some_and(|shown| shown != conversation)
+            .then(|| scroll_offsets.with_value(|o| o.get(&conversation).copied()))
+            .flatten();
+        scroll::scroll_to(in_element, saved);
+        conversation
     });
 
+    let show_throughput = move || loading.get() && flags.with(|f| f.enabled("throughput_indicator"));
+    // Each message paired with whether it continues a run from the same role
+    let grouped_messages = move || {
+        messages.with(|m| {
+            m.iter()
+                .enumerate()
+                .map(|(i, msg)| (msg.clone(), i > 0 && m[i - 1].role == msg.role))
+                .collect::<Vec<_>>()
+        })
+    };
     let has_messages = move || !messages.get().is_empty() || !current_response.get().is_empty();
 
     let container_class = move || {
         if has_messages() { "container has-messages" } else { "container empty" }
     };
 
+    let ticker_visible = move || settings.with(|s| s.show_ticker && !s.watchlist.is_empty());
+
     view! {
-        <div class=container_class>
-            <a
-                class="icon-btn github-link"
-                href="https://github.com/wxveio/wxve-chat"
-                target="_blank"
-                rel="noopener noreferrer"
-            >
-                <svg viewBox="0 0 24 24" fill="currentColor">
-                    <path d="M12 0c-6.626 0-12 5.373-12 12 0 5.302 3.438 9.8 8.207 11.387.599.111.793-.261.793-.577v-2.234c-3.338.726-4.033-1.416-4.033-1.416-.546-1.387-1.333-1.756-1.333-1.756-1.089-.745.083-.729.083-.729 1.205.084 1.839 1.237 1.839 1.237 1.07 1.834 2.807 1.304 3.492.997.107-.775.418-1.305.762-1.604-2.665-.305-5.467-1.334-5.467-5.931 0-1.311.469-2.381 1.236-3.221-.124-.303-.535-1.524.117-3.176 0 0 1.008-.322 3.301 1.23.957-.266 1.983-.399 3.003-.404 1.02.005 2.047.138 3.006.404 2.291-1.552 3.297-1.23 3.297-1.23.653 1.653.242 2.874.118 3.176.77.84 1.235 1.911 1.235 3.221 0 4.609-2.807 5.624-5.479 5.921.43.372.823 1.102.823 2.222v3.293c0 .319.192.694.801.576 4.765-1.589 8.199-6.086 8.199-11.386 0-6.627-5.373-12-12-12z"/>
-                </svg>
-            </a>
-            <button
-                class="icon-btn theme-toggle"
-                on:click=toggle_dark_mode
-            >
-                {move || if dark_mode.get() { "☀️" } else { "🌙" }}
-            </button>
-            <div class="logo">"wxve.io"</div>
+        <div
+            class=container_class
+            class:with-ticker=move || !embedded && ticker_visible()
+            class:incognito=incognito
+            class:embedded=embedded
+            class:element=in_element
+            style=move || keyboard_inset.map(|inset| format!("--keyboard-inset: {}px", inset.get()))
+            on:scroll=move |_| {
+                if in_element {
+                    remember_scroll();
+                }
+            }
+        >
+            // Embedded, the host page provides the chrome and drives the widget over postMessage
+            {(!embedded).then(|| view! {
+                <Ticker settings=settings quotes=quotes rates=fx_rates connection=connection/>
+                <a
+                    class="icon-btn github-link"
+                    href="https://github.com/wxveio/wxve-chat"
+                    target="_blank"
+                    rel="noopener noreferrer"
+                >
+                    <svg viewBox="0 0 24 24" fill="currentColor">
+                        <path d="M12 0c-6.626 0-12 5.373-12 12 0 5.302 3.438 9.8 8.207 11.387.599.111.793-.261.793-.577v-2.234c-3.338.726-4.033-1.416-4.033-1.416-.546-1.387-1.333-1.756-1.333-1.756-1.089-.745.083-.729.083-.729 1.205.084 1.839 1.237 1.839 1.237 1.07 1.834 2.807 1.304 3.492.997.107-.775.418-1.305.762-1.604-2.665-.305-5.467-1.334-5.467-5.931 0-1.311.469-2.381 1.236-3.221-.124-.303-.535-1.524.117-3.176 0 0 1.008-.322 3.301 1.23.957-.266 1.983-.399 3.003-.404 1.02.005 2.047.138 3.006.404 2.291-1.552 3.297-1.23 3.297-1.23.653 1.653.242 2.874.118 3.176.77.84 1.235 1.911 1.235 3.221 0 4.609-2.807 5.624-5.479 5.921.43.372.823 1.102.823 2.222v3.293c0 .319.192.694.801.576 4.765-1.589 8.199-6.086 8.199-11.386 0-6.627-5.373-12-12-12z"/>
+                    </svg>
+                </a>
+                <button
+                    class="icon-btn settings-toggle"
+                    title="Settings"
+                    on:click=move |_| {
+                        dialog::open(SettingsDialog {
+                            settings,
+                            tools: state.tools,
+                            vault,
+                            conversations,
+                            on_lock: Callback::new(move |_| lock()),
+                        });
+                    }
+                >
+                    "⚙"
+                </button>
+                <button
+                    class="icon-btn incognito-toggle"
+                    class:active=incognito
+                    title=move || if incognito.get() { "Leave incognito" } else { "New incognito chat" }
+                    on:click=move |_| state.toggle_incognito()
+                >
+                    "🕶"
+                </button>
+                <Inspector flags=flags/>
+                <button
+                    class="icon-btn theme-toggle"
+                    on:click=toggle_dark_mode
+                >
+                    {move || if dark_mode.get() { "☀️" } else { "🌙" }}
+                </button>
+                {move || enabled("alerts").then(|| view! {
+                    <Alerts alerts=alerts settings=settings open_panel=open_panel connection=connection/>
+                })}
+                <News
+                    symbols=discussed_symbols
+                    open_panel=open_panel
+                    on_summarize=Callback::new(move |url: String| {
+                        send(format!("Summarize this article: {url}"));
+                    })
+                />
+                <Outline open_panel=open_panel/>
+                <ConversationList open_panel=open_panel dark_mode=dark_mode config=remote_config/>
+                <EconomicCalendar
+                    held=held_symbols
+                    open_panel=open_panel
+                    on_ask=Callback::new(move |question: String| {
+                        send(question);
+                    })
+                />
+                <Portfolio
+                    positions=portfolio
+                    quotes=quotes
+                    open_panel=open_panel
+                    on_discuss=Callback::new(move |summary: PortfolioSummary| {
+                        state.attached_portfolio.set(Some(summary));
+                        send("Discuss my portfolio.".to_string());
+                    })
+                />
+                {move || enabled("heatmap").then(|| view! {
+                    <button
+                        class="icon-btn heatmap-toggle"
+                        class:active=move || route.get() == Route::Heatmap
+                        title="Market heatmap"
+                        on:click=move |_| toggle_route(route, Route::Heatmap)
+                    >
+                        "▦"
+                    </button>
+                })}
+                <button
+                    class="icon-btn dashboard-toggle"
+                    class:active=move || route.get() == Route::Dashboard
+                    title="Dashboard"
+                    on:click=move |_| toggle_route(route, Route::Dashboard)
+                >
+                    "⊞"
+                </button>
+                {move || enabled("screener").then(|| view! {
+                    <button
+                        class="icon-btn screener-toggle"
+                        class:active=move || route.get() == Route::Screener
+                        title="Screener"
+                        on:click=move |_| toggle_route(route, Route::Screener)
+                    >
+                        "⌕"
+                    </button>
+                })}
+                {move || match route.get() {
+                    page if page.feature().is_some_and(|f| !enabled(f)) => None,
+                    Route::Chat => None,
+                    Route::Heatmap => Some(view! {
+                        <Heatmap on_select=Callback::new(move |symbol: String| {
+                            navigate(route, Route::Chat);
+                            send(format!("What's the wave structure for {symbol}?"));
+                        })/>
+                    }.into_any()),
+                    Route::Screener => Some(view! {
+                        <Screener on_analyze=Callback::new(move |symbol: String| {
+                            navigate(route, Route::Chat);
+                            send(format!("What's the wave structure for {symbol}?"));
+                        })/>
+                    }.into_any()),
+                    Route::Dashboard => Some(view! {
+                        <Dashboard
+                            tiles=dashboard_tiles
+                            settings=settings
+                            quotes=quotes
+                            rates=fx_rates
+                            dark_mode=dark_mode
+                        />
+                    }.into_any()),
+                    Route::Symbol(symbol) => Some(view! {
+                        <SymbolPage
+                            symbol=symbol
+                            settings=settings
+                            rates=fx_rates
+                            dark_mode=dark_mode
+                            conversations=conversations
+                            on_pin=pin
+                            on_open=Callback::new(move |id: String| {
+                                state.switch_conversation(&id);
+                                navigate(route, Route::Chat);
+                            })
+                            on_ask=Callback::new(move |question: String| {
+                                if state.new_conversation() {
+                                    navigate(route, Route::Chat);
+                                    send(question);
+                                }
+                            })
+                        />
+                    }.into_any()),
+                }}
+                <div class="logo">"wxve.io"</div>
+            })}
+            {embedded.then(|| view! {
+                <button class="icon-btn embed-close" title="Close" on:click=|_| embed::close()>
+                    "×"
+                </button>
+            })}
+            <ConnectionStatus connection=connection/>
+            <Toaster/>
+            <DialogHost/>
+            <DebugPanel/>
+            <div class="banners">
+                {mock::enabled().then(|| view! {
+                    <div class="banner mock" role="status">
+                        <span class="banner-text">
+                            "Mock mode: replies, quotes and charts are generated in the browser, not by the API."
+                        </span>
+                    </div>
+                })}
+                {move || incognito.get().then(|| view! {
+                    <div class="banner incognito" role="status">
+                        <span class="banner-text">
+                            "Incognito: this chat isn't saved, only recent messages are sent as context, and it's gone when you close the tab."
+                        </span>
+                        <button class="banner-dismiss" title="Leave incognito" on:click=move |_| state.toggle_incognito()>
+                            "×"
+                        </button>
+                    </div>
+                })}
+                <HealthBanner/>
+                <LockedBanner vault=vault conversations=conversations/>
+                <ConfigBanners config=remote_config/>
+            </div>
+            <QuoteCard settings=settings rates=fx_rates/>
+            <QuoteSelection on_quote=Callback::new(move |quoted: String| reply_quote.set(Some(quoted)))/>
+            {(!embedded).then(|| view! {
+                <Watchlist
+                    settings=settings
+                    quotes=quotes
+                    rates=fx_rates
+                    open_panel=open_panel
+                    on_select=Callback::new(move |symbol: String| {
+                        send(format!("What's the wave structure for {symbol}?"));
+                    })
+                    on_details=Callback::new(move |symbol: String| {
+                        navigate(route, Route::Symbol(symbol));
+                    })
+                >
+                    <EarningsCalendar
+                        symbols=watchlist_symbols
+                        on_setup=Callback::new(move |report: EarningsReport| {
+                            send(format!(
+                                "What's the pre-earnings wave setup for {} ahead of its {} report?",
+                                report.symbol, report.date,
+                            ));
+                        })
+                    />
+                </Watchlist>
+            })}
 
             <div class="messages">
+                <PinnedContext/>
+                <EarlierMessages/>
                 <For
-                    each=move || messages.get()
-                    key=|msg| msg.id
-                    children=move |msg| {
+                    each=grouped_messages
+                    key=|(msg, _)| msg.id
+                    children=move |(msg, grouped)| {
                         let class = match msg.role {
                             Role::User => "message user",
                             Role::Assistant => "message",
                         };
                         let content_html = match msg.role {
-                            Role::User => msg.content.clone(),
+                            // Shown by the banner instead
+                            _ if msg.error.is_some() => String::new(),
+                            Role::User => user_markdown_to_html(&msg.content),
                             Role::Assistant => markdown_to_html(&msg.content),
                         };
-                        let charts = msg.charts.clone();
+                        let id = msg.id;
+                        let held = move || held_back.with(|h| {
+                            h.as_ref().filter(|(held, _)| *held == id).map(|(_, shown)| shown.clone())
+                        });
+                        let held_more = msg.content.chars().count();
+                        let shown_html = move || match held() {
+                            Some(shown) => markdown_to_html(&shown),
+                            None => content_html.clone(),
+                        };
+                        // Charts added from a chart's parameters form show up after the others
+                        let chart_count = Memo::new(move |_| {
+                            messages.with(|m| m.iter().find(|m| m.id == id).map_or(0, |m| m.charts.len()))
+                        });
+                        let role = msg.role;
+                        let fork_here = Memo::new(move |_| {
+                            let at = state.index_of(id)?;
+                            forks.with(|f| {
+                                f.iter().find(|f| f.at == at).map(|f| (f.active, f.branches.len()))
+                            })
+                        });
+                        let (draft, set_draft) = signal(msg.content.clone());
+                        let original = msg.content.clone();
+                        let shared = msg.content.clone();
+                        let copied = msg.content.clone();
+                        let menu_content = msg.content.clone();
+                        let swipe = Swipe::new(id, swiped);
+                        let press = LongPress::new();
+                        let pinned = Memo::new(move |_| {
+                            messages.with(|m| m.iter().any(|m| m.id == id && m.pinned))
+                        });
                         view! {
-                            <div class=class>
-                                <span inner_html=content_html></span>
-                                {charts.into_iter().map(|chart| {
-                                    let title = format!("{} Wave Analysis", chart.symbol);
-                                    view! {
-                                        <div class="chart-container">
-                                            <iframe
-                                                attr:srcdoc=chart.html
-                                                title=title
-                                                sandbox="allow-scripts allow-fullscreen"
-                                                allowfullscreen=true
-                                            ></iframe>
+                            {move || (state.unseen.get() == Some(id)).then(|| view! {
+                                <NewResponsesDivider page_visible=page_visible/>
+                            })}
+                            <div
+                                class=class
+                                id=move || state.index_of(id).map(|i| permalink::anchor(state.archived.get() + i))
+                                class:grouped=grouped
+                                class:swiping=move || swipe.dragging()
+                                class:swiped=move || swipe.offset() != 0.0
+                                class:pinned=pinned
+                                class:selecting=move || selection.with(Option::is_some)
+                                class:focused=move || focused.get() == Some(id)
+                                style:transform=move || swipe.transform()
+                                on:pointerdown=move |ev| {
+                                    swipe.down(&ev);
+                                    press.down(&ev, move || {
+                                        swipe.cancel();
+                                        swipe.close();
+                                        menu_open.set(Some(id));
+                                    });
+                                }
+                                on:pointermove=move |ev| {
+                                    swipe.moved(&ev);
+                                    press.moved(&ev);
+                                }
+                                on:pointerup=move |_| {
+                                    swipe.up();
+                                    press.cancel();
+                                }
+                                on:pointercancel=move |_| {
+                                    swipe.cancel();
+                                    press.cancel();
+                                }
+                                on:contextmenu=move |ev| {
+                                    if press.pressing() {
+                                        ev.prevent_default();
+                                    }
+                                }
+                            >
+                                <SelectCheckbox id=id selection=selection/>
+                                {(!grouped).then(|| view! { <Avatar role=msg.role state=AvatarState::Idle/> })}
+                                <BranchSwitcher
+                                    fork=fork_here
+                                    disabled=loading.read_only()
+                                    on_switch=Callback::new(move |index: usize| {
+                                        if let Some(at) = state.index_of(id) {
+                                            state.switch_branch(at, index);
+                                        }
+                                    })
+                                />
+                                {msg.quoted.clone().map(|quoted| view! {
+                                    <blockquote class="reply-quote">{quoted}</blockquote>
+                                })}
+                                {msg.sentiment.map(|sentiment| view! {
+                                    <SentimentGauge sentiment=sentiment/>
+                                })}
+                                {msg.error.clone().map(|error| view! { <TurnErrorBanner id=id error=error/> })}
+                                <Show
+                                    when=move || editing.get() == Some(id)
+                                    fallback=move || view! { <span inner_html=shown_html.clone()></span> }
+                                >
+                                    <div class="message-edit">
+                                        <textarea
+                                            prop:value=draft
+                                            on:input=move |ev| set_draft.set(event_target_value(&ev))
+                                        ></textarea>
+                                        <div class="dialog-actions">
+                                            <button class="dialog-button" on:click={
+                                                let original = original.clone();
+                                                move |_| {
+                                                    set_draft.set(original.clone());
+                                                    editing.set(None);
+                                                }
+                                            }>"Cancel"</button>
+                                            <button
+                                                class="dialog-button primary"
+                                                on:click=move |_| {
+                                                    if state.edit(id, draft.get()) {
+                                                        editing.set(None);
+                                                    }
+                                                }
+                                            >
+                                                "Save & send"
+                                            </button>
                                         </div>
+                                    </div>
+                                </Show>
+                                {move || held().map(|shown| view! {
+                                    <div class="paused-indicator">
+                                        {format!(
+                                            "Paused · {} more characters received",
+                                            held_more.saturating_sub(shown.chars().count()),
+                                        )}
+                                    </div>
+                                })}
+                                {msg.interrupted.then(|| view! { <InterruptedNote id=id/> })}
+                                {(role == Role::User).then(|| view! { <DeliveryStatus id=id/> })}
+                                <div class="message-actions">
+                                    {match role {
+                                        Role::User => view! {
+                                            <button
+                                                title="Edit and resend"
+                                                disabled=loading
+                                                on:click=move |_| editing.set(Some(id))
+                                            >
+                                                "✎"
+                                            </button>
+                                        }.into_any(),
+                                        Role::Assistant => view! {
+                                            <button
+                                                title="Regenerate"
+                                                disabled=loading
+                                                on:click=move |_| state.regenerate(id)
+                                            >
+                                                "↻"
+                                            </button>
+                                            <button
+                                                title="Regenerate and compare"
+                                                disabled=loading
+                                                on:click=move |_| state.regenerate_and_compare(id)
+                                            >
+                                                "⇄"
+                                            </button>
+                                            <button
+                                                title="Share"
+                                                on:click=move |_| share::share(shared.clone())
+                                            >
+                                                "⤴"
+                                            </button>
+                                        }.into_any(),
+                                    }}
+                                    <MessageMenu
+                                        id=id
+                                        role=role
+                                        content=menu_content
+                                        open=menu_open
+                                        on_edit=Callback::new(move |_| editing.set(Some(id)))
+                                        on_quote=Callback::new(move |quoted: String| reply_quote.set(Some(quoted)))
+                                        on_select=Callback::new(move |_| selection::start(selection, id))
+                                    />
+                                </div>
+                                <div class="swipe-actions">
+                                    <button on:click=move |_| {
+                                        swipe.close();
+                                        share::copy(copied.clone());
+                                    }>
+                                        "Copy"
+                                    </button>
+                                    {(role == Role::Assistant).then(|| view! {
+                                        <button
+                                            disabled=loading
+                                            on:click=move |_| {
+                                                swipe.close();
+                                                state.regenerate(id);
+                                            }
+                                        >
+                                            "Redo"
+                                        </button>
+                                    })}
+                                    <button
+                                        class="delete"
+                                        disabled=loading
+                                        on:click=move |_| {
+                                            swipe.close();
+                                            state.delete(id);
+                                        }
+                                    >
+                                        "Delete"
+                                    </button>
+                                </div>
+                                <For
+                                    each=move || 0..chart_count.get()
+                                    key=|i| *i
+                                    children=move |i| {
+                                        let chart = messages.with_untracked(|m| {
+                                            m.iter().find(|m| m.id == id).and_then(|m| m.charts.get(i).cloned())
+                                        });
+                                        chart.map(|chart| view! {
+                                            <ChartView
+                                                chart=chart
+                                                settings=settings
+                                                dark_mode=dark_mode
+                                                on_pin=pin
+                                                sheet=true
+                                                on_append=Callback::new(move |chart: Chart| state.append_chart(id, chart))
+                                                on_replace=Callback::new(move |chart: Chart| state.replace_chart(id, i, chart))
+                                            />
+                                        })
                                     }
-                                }).collect::<Vec<_>>()}
+                                />
+                                {(role == Role::Assistant).then(|| view! { <Reactions id=id/> })}
+                                {msg.meta.map(|meta| view! { <MetaFooter meta=meta/> })}
                             </div>
                         }
                     }
                 />
 
+                {move || comparing.get().then(|| view! {
+                    <Comparison dark_mode=dark_mode/>
+                })}
+
                 {move || {
-                    let response = current_response.get();
-                    let tool = tool_running.get();
-                    if !response.is_empty() || tool.is_some() {
-                        let html = markdown_to_html(&response);
-                        Some(view! {
-                            <div class="message">
-                                <span inner_html=html></span>
-                                {move || tool_running.get().map(|name| view! {
-                                    <div class="tool-indicator">
-                                        <span class="spinner"></span>
-                                        {format!("Using {name}...")}
-                                    </div>
-                                })}
-                            </div>
-                        })
-                    } else {
-                        None
+                    let phase = phase.get();
+                    if phase == StreamPhase::Idle {
+                        return None;
                     }
+                    let response = typed_response.get();
+                    let tool = tool_running.get();
+                    let html = markdown_to_html(&response);
+                    let state = if tool.is_some() { AvatarState::Tool } else { AvatarState::Thinking };
+                    let grouped = messages
+                        .with(|m| m.last().is_some_and(|last| last.role == Role::Assistant));
+                    // Nothing to show yet: say so rather than leaving an empty bubble
+                    let waiting = response.trim().is_empty() && tool.is_none();
+                    Some(view! {
+                        <div class="message streaming" class:grouped=grouped>
+                            <Avatar role=Role::Assistant state=state/>
+                            {waiting.then(|| view! {
+                                <div class="thinking-indicator">
+                                    "Xve is thinking"<span class="thinking-dots"></span>
+                                </div>
+                            })}
+                            <span
+                                class="streamed"
+                                class:typing=phase == StreamPhase::Typing && tool.is_none() && !paused.get()
+                                inner_html=html
+                            ></span>
+                            <ToolApproval/>
+                            {move || tool_running.get().map(|name| view! {
+                                <div class="tool-indicator">
+                                    <span class="spinner"></span>
+                                    {format!("Using {name}...")}
+                                </div>
+                            })}
+                            {move || paused.get().then(|| view! {
+                                <div class="paused-indicator">
+                                    {move || format!("Paused · {} more characters received", buffered_chars())}
+                                </div>
+                            })}
+                        </div>
+                    })
                 }}
+                {move || show_throughput().then(|| view! {
+                    <Throughput/>
+                })}
             </div>
 
             <div class="input-area">
+                <RecentSymbols/>
+                {move || reply_quote.get().map(|quoted| view! {
+                    <div class="reply-preview">
+                        <blockquote class="reply-quote">{quoted}</blockquote>
+                        <button class="panel-remove" title="Remove quote" on:click=move |_| reply_quote.set(None)>
+                            "×"
+                        </button>
+                    </div>
+                })}
+                <SelectionBar selection=selection/>
+                {move || state.queued.get().map(|(message, _)| view! {
+                    <div class="queued-hint">
+                        <span class="queued-text">"Sends after this response: " {message}</span>
+                        <button class="panel-remove" title="Don't send" on:click=move |_| state.queued.set(None)>
+                            "×"
+                        </button>
+                    </div>
+                })}
+                {move || (!input.with(String::is_empty)).then(|| view! {
+                    <button
+                        class="composer-preview-toggle"
+                        aria-pressed=move || previewing.get().to_string()
+                        on:click=move |_| previewing.update(|p| *p = !*p)
+                    >
+                        {move || if previewing.get() { "Hide preview" } else { "Preview" }}
+                    </button>
+                })}
+                {move || (previewing.get() && !input.with(String::is_empty)).then(|| view! {
+                    <div class="composer-preview" inner_html=move || user_markdown_to_html(&input.get())></div>
+                })}
                 <div class="input-box">
-                    <input
-                        type="text"
+                    <textarea
+                        rows="1"
                         placeholder="Ask Xve..."
                         prop:value=move || input.get()
                         on:input=move |ev| {
-                            set_input.set(leptos::event_target_value(&ev));
+                            set_input.set(event_target_value(&ev));
                         }
-                        on:keypress=move |ev| {
-                            if ev.key() == "Enter" {
+                        on:keydown=move |ev| {
+                            // Shift+Enter starts a new line, for lists and tables
+                            if ev.key() == "Enter" && !ev.shift_key() {
+                                ev.prevent_default();
                                 do_send();
                             }
                         }
-                    />
-                    <button on:click=move |_| do_send() prop:disabled=move || loading.get()>
+                    ></textarea>
+                    <button
+                        on:click=move |_| do_send()
+                        prop:disabled=move || loading.get() && state.queued.with(Option::is_some)
+                    >
                         "Send"
                     </button>
+                    {move || (phase.get() != StreamPhase::Idle || held_back.with(Option::is_some)).then(|| view! {
+                        <button
+                            class="pause"
+                            title=move || if paused.get() { "Show the rest" } else { "Hold the text where it is" }
+                            on:click=move |_| {
+                                paused.update(|p| *p = !*p);
+                                if !paused.get_untracked() {
+                                    held_back.set(None);
+                                }
+                            }
+                        >
+                            {move || if paused.get() { "Resume" } else { "Pause" }}
+                        </button>
+                    })}
+                    {move || (loading.get() && !comparing.get()).then(|| view! {
+                        <button class="stop" title="Stop generating" on:click=move |_| state.stop()>
+                            "Stop"
+                        </button>
+                    })}
                 </div>
+                {move || (!has_messages()).then(|| view! {
+                    <WelcomeCards on_select=Callback::new(move |prompt: String| {
+                        send(prompt);
+                    })/>
+                })}
+            </div>
+        </div>
+    }
+}
+
+/// Keys handled outside of text inputs, shown by `?`
+const SHORTCUTS: &[(&str, &str)] = &[
+    ("Enter", "Send message"),
+    ("Shift+Enter", "New line in the message"),
+    ("?", "Show keyboard shortcuts"),
+    ("j / k", "Focus next / previous message"),
+    ("y", "Copy focused message"),
+    ("r", "Regenerate focused response"),
+    ("d", "Delete focused message"),
+    ("Esc", "Close dialog"),
+    ("Ctrl+Shift+D", "Toggle stream debug panel"),
+];
+
+struct SettingsDialog {
+    settings: RwSignal<Settings>,
+    tools: RwSignal<Vec<Tool>>,
+    vault: RwSignal<Vault>,
+    conversations: RwSignal<Vec<Conversation>>,
+    on_lock: Callback<()>,
+}
+
+impl Dialog for SettingsDialog {
+    fn title(&self) -> String {
+        "Settings".to_string()
+    }
+
+    fn body(&self, _close: Callback<()>) -> AnyView {
+        let (settings, tools) = (self.settings, self.tools);
+        let (vault, conversations, on_lock) = (self.vault, self.conversations, self.on_lock);
+        let volume = move || settings.with(|s| s.completion_sound);
+        view! {
+            <div class="settings-form">
+                <label class="settings-row">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || volume().is_some()
+                        on:change=move |ev| {
+                            let on = event_target_checked(&ev);
+                            settings.update(|s| {
+                                s.completion_sound = on.then_some(sound::DEFAULT_VOLUME);
+                            });
+                            settings.with_untracked(Settings::play_sound);
+                        }
+                    />
+                    "Play a sound when a response finishes or an alert triggers"
+                </label>
+                <label class="settings-row">
+                    "Volume"
+                    <input
+                        type="range"
+                        min="0.05"
+                        max="1"
+                        step="0.05"
+                        prop:disabled=move || volume().is_none()
+                        prop:value=move || volume().unwrap_or(sound::DEFAULT_VOLUME).to_string()
+                        on:change=move |ev| {
+                            if let Ok(level) = event_target_value(&ev).parse::<f64>() {
+                                settings.update(|s| s.completion_sound = Some(level));
+                                settings.with_untracked(Settings::play_sound);
+                            }
+                        }
+                    />
+                </label>
+                <label class="settings-row">
+                    "Show responses"
+                    <TypingSpeedSelect settings=settings/>
+                </label>
+                <label class="settings-row">
+                    "Keep on screen"
+                    <HistoryCapSelect settings=settings/>
+                </label>
+                <label class="settings-row">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || settings.with(|s| s.review_requests)
+                        on:change=move |ev| {
+                            let on = event_target_checked(&ev);
+                            settings.update(|s| s.review_requests = on);
+                        }
+                    />
+                    "Review each request before it's sent, to leave out earlier messages or attachments"
+                </label>
+                <label class="settings-row">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || settings.with(|s| s.telemetry)
+                        on:change=move |ev| {
+                            let on = event_target_checked(&ev);
+                            settings.update(|s| s.telemetry = on);
+                        }
+                    />
+                    "Share anonymous usage statistics (event counts and timings, never message content)"
+                </label>
+                <label class="settings-row">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || settings.with(|s| s.error_reports)
+                        on:change=move |ev| {
+                            let on = event_target_checked(&ev);
+                            settings.update(|s| s.error_reports = on);
+                        }
+                    />
+                    "Send crash and failed-request reports with recent app events to help fix bugs"
+                </label>
+                <EncryptionSettings vault=vault conversations=conversations on_lock=on_lock/>
+                <ToolSettings settings=settings tools=tools/>
+                <SessionSettings/>
+                <DataSettings/>
+            </div>
+        }
+        .into_any()
+    }
+}
+
+struct ShortcutsHelp;
+
+impl Dialog for ShortcutsHelp {
+    fn title(&self) -> String {
+        "Keyboard shortcuts".to_string()
+    }
+
+    fn body(&self, _close: Callback<()>) -> AnyView {
+        view! {
+            <dl class="shortcuts">
+                {SHORTCUTS.iter().map(|&(key, action)| view! {
+                    <dt><kbd>{key}</kbd></dt>
+                    <dd>{action}</dd>
+                }).collect::<Vec<_>>()}
+            </dl>
+        }
+        .into_any()
+    }
+}
+
+/// True if `ev` comes from somewhere the user is typing.
+fn is_typing(ev: &web_sys::KeyboardEvent) -> bool {
+    ev.target()
+        .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+        .is_some_and(|el| matches!(el.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT"))
+}
+
+/// Rough characters-per-token ratio for English text
+const CHARS_PER_TOKEN: f64 = 4.0;
+
+/// No new text for this long while streaming counts as stalled
+const STALL_AFTER_MS: f64 = 10_000.0;
+
+/// Elapsed time and estimated tokens/sec for the response being streamed.
+#[component]
+fn Throughput() -> impl IntoView {
+    let state = use_app_state();
+    let (meta, response) = (state.pending_meta, state.current_response);
+    // Tool calls can run quietly for a while without counting as a stall
+    let tool = state.tool_running;
+    let (now, set_now) = signal(js_sys::Date::now());
+    let (last_change, set_last_change) = signal(js_sys::Date::now());
+
+    if let Ok(handle) = set_interval_with_handle(
+        move || set_now.set(js_sys::Date::now()),
+        Duration::from_millis(250),
+    ) {
+        on_cleanup(move || handle.clear());
+    }
+    Effect::new(move |_| {
+        response.track();
+        tool.track();
+        set_last_change.set(js_sys::Date::now());
+    });
+
+    move || {
+        let meta = meta.get()?;
+        let now = now.get();
+        let elapsed = format!("{:.1}s", (now - meta.started_at) / 1000.0);
+        let stalled = tool.with(Option::is_none) && now - last_change.get() > STALL_AFTER_MS;
+        let rate = meta.first_token_at.map(|first| {
+            let tokens = response.with(String::len) as f64 / CHARS_PER_TOKEN;
+            let seconds = ((now - first) / 1000.0).max(0.1);
+            format!("{:.0} tok/s", tokens / seconds)
+        });
+        let text = match (stalled, rate) {
+            (true, _) => format!("{elapsed} · stalled"),
+            (false, Some(rate)) => format!("{elapsed} · {rate}"),
+            (false, None) => format!("{elapsed} · waiting for first token"),
+        };
+        Some(view! { <div class="throughput" class:stalled=stalled>{text}</div> })
+    }
+}
+
+/// Expandable footer with the model, timings and request id of a response.
+#[component]
+fn MetaFooter(meta: ResponseMeta) -> impl IntoView {
+    let total = meta.elapsed(meta.finished_at);
+    let rows = [
+        ("Model", meta.model.clone()),
+        ("First token", meta.elapsed(meta.first_token_at)),
+        ("Total", total.clone()),
+        ("Request id", Some(meta.request_id.clone())),
+    ];
+
+    view! {
+        <details class="message-meta">
+            <summary>{total.unwrap_or_else(|| "details".to_string())}</summary>
+            <dl>
+                {rows.into_iter().filter_map(|(label, value)| value.map(|value| view! {
+                    <dt>{label}</dt>
+                    <dd>{value}</dd>
+                })).collect::<Vec<_>>()}
+            </dl>
+        </details>
+    }
+}
+
+/// Semicircle gauge from bearish (left) to bullish (right) for an analysis.
+#[component]
+fn SentimentGauge(sentiment: Sentiment) -> impl IntoView {
+    // Needle sweeps from 180° (bearish) to 0° (bullish) around the arc's center
+    let angle = std::f64::consts::PI * (1.0 - (sentiment.score + 1.0) / 2.0);
+    let (x, y) = (20.0 + 15.0 * angle.cos(), 22.0 - 15.0 * angle.sin());
+    let label = sentiment.label();
+    let class = format!("sentiment-gauge {}", label.to_lowercase());
+    let title = format!("{label} ({:+.2})", sentiment.score);
+
+    view! {
+        <div class=class title=title>
+            <svg viewBox="0 0 40 24">
+                <path class="gauge-track" d="M 2 22 A 18 18 0 0 1 38 22"/>
+                <line class="gauge-needle" x1="20" y1="22" x2=format!("{x:.2}") y2=format!("{y:.2}")/>
+                <circle class="gauge-hub" cx="20" cy="22" r="2"/>
+            </svg>
+            <span class="gauge-label">{label}</span>
+        </div>
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum AvatarState {
+    Idle,
+    Thinking,
+    /// A backend tool is running
+    Tool,
+}
+
+/// Role marker beside a message; the assistant's pulses while thinking or using a tool.
+#[component]
+fn Avatar(role: Role, state: AvatarState) -> impl IntoView {
+    let (class, label, title) = match (role, state) {
+        (Role::User, _) => ("avatar user", "Y", "You"),
+        (Role::Assistant, AvatarState::Idle) => ("avatar assistant", "X", "Xve"),
+        (Role::Assistant, AvatarState::Thinking) => ("avatar assistant thinking", "X", "Xve is thinking"),
+        (Role::Assistant, AvatarState::Tool) => ("avatar assistant tool", "X", "Xve is using a tool"),
+    };
+    view! { <span class=class title=title aria-hidden="true">{label}</span> }
+}
+
+/// Starter prompts shown on the empty state, as (title, prompt)
+const WELCOME_PROMPTS: &[(&str, &str)] = &[
+    ("Analyze AAPL's wave count", "What's the current Elliott Wave count for AAPL?"),
+    ("Compare SPY vs QQQ", "Compare the wave structure of SPY and QQQ."),
+    ("Explain Elliott Wave basics", "Explain the basics of Elliott Wave theory."),
+];
+
+/// Example prompt cards that send on click, for discovering what Xve can do.
+#[component]
+fn WelcomeCards(on_select: Callback<String>) -> impl IntoView {
+    view! {
+        <div class="welcome-cards">
+            {WELCOME_PROMPTS.iter().map(|&(title, prompt)| view! {
+                <button class="welcome-card" on:click=move |_| on_select.run(prompt.to_string())>
+                    <span class="welcome-title">{title}</span>
+                    <span class="welcome-prompt">{prompt}</span>
+                </button>
+            }).collect::<Vec<_>>()}
+        </div>
+    }
+}
+
+/// Chips for the most recently charted symbols, for one-tap follow-ups.
+#[component]
+fn RecentSymbols() -> impl IntoView {
+    let state = use_app_state();
+    let symbols = state.recent_symbols;
+    move || {
+        (!symbols.with(Vec::is_empty)).then(|| view! {
+            <div class="recent-symbols">
+                <For
+                    each=move || symbols.get()
+                    key=|symbol| symbol.clone()
+                    children=move |symbol| {
+                        let title = format!("Update the analysis for {symbol}");
+                        let select = symbol.clone();
+                        view! {
+                            <button
+                                class="symbol-chip"
+                                title=title
+                                on:click=move |_| {
+                                    state.send(format!("Update the analysis for {select}"), None);
+                                }
+                            >
+                                {symbol}
+                            </button>
+                        }
+                    }
+                />
+            </div>
+        })
+    }
+}
+
+/// How far a chart's bottom sheet is pulled up on phones
+#[derive(Clone, Copy, PartialEq)]
+enum SheetState {
+    Closed,
+    Half,
+    Full,
+}
+
+#[component]
+fn ChartView(
+    chart: Chart,
+    settings: RwSignal<Settings>,
+    dark_mode: ReadSignal<bool>,
+    /// Shows a pin button that adds the chart to the dashboard
+    #[prop(optional, into)]
+    on_pin: Option<Callback<String>>,
+    /// On phones, collapse to a button that opens the chart in a bottom sheet
+    #[prop(optional)]
+    sheet: bool,
+    /// Offers "Add below" in the parameters form, for charts in a message
+    #[prop(optional, into)]
+    on_append: Option<Callback<Chart>>,
+    /// Told about each redraw in place, so the message keeps the chart it now shows
+    #[prop(optional, into)]
+    on_replace: Option<Callback<Chart>>,
+) -> impl IntoView {
+    let Chart { symbol, html, indicators, params, .. } = chart;
+    let sheet_state = RwSignal::new(SheetState::Closed);
+    // While the handle is dragged: where the pointer went down, the sheet's height then,
+    // and its height now
+    let drag = RwSignal::new(None::<(f64, f64, f64)>);
+    let (html, set_html) = signal(html);
+    let (indicators, set_indicators) = signal(indicators);
+    let (rendering, set_rendering) = signal(false);
+    let (symbol, set_symbol) = signal(symbol);
+    let (params, set_params) = signal(params);
+
+    // A chart restored with its conversation only knows what drew it
+    if html.get_untracked().is_empty() {
+        set_rendering.set(true);
+        let indicators = indicators.get_untracked();
+        let (symbol, params) = (symbol.get_untracked(), params.get_untracked());
+        spawn_local(async move {
+            match render_chart(symbol, indicators, params).await {
+                Ok(new_html) => set_html.set(new_html),
+                Err(e) => log::error!(target: "ui", "Chart render failed: {e}"),
+            }
+            set_rendering.set(false);
+        });
+    }
+
+    let toggle = move |indicator: Indicator| {
+        if rendering.get() {
+            return;
+        }
+        let previous = indicators.get();
+        let mut selected = previous.clone();
+        if let Some(pos) = selected.iter().position(|&i| i == indicator) {
+            selected.remove(pos);
+        } else {
+            selected.push(indicator);
+        }
+        set_indicators.set(selected.clone());
+
+        set_rendering.set(true);
+        spawn_local(async move {
+            match render_chart(symbol.get_untracked(), selected.clone(), params.get_untracked()).await {
+                Ok(new_html) => {
+                    if let Some(on_replace) = on_replace {
+                        let chart = Chart::new(symbol.get_untracked(), new_html.clone(), selected.clone(), params.get_untracked());
+                        on_replace.run(chart);
+                    }
+                    set_html.set(new_html);
+                    // The most recent selection that drew becomes the default for new charts
+                    settings.update(|s| s.indicators = selected);
+                }
+                Err(e) => {
+                    log::error!(target: "ui", "Chart render failed: {e}");
+                    set_indicators.set(previous);
+                }
+            }
+            set_rendering.set(false);
+        });
+    };
+    let rerun = move |(new_symbol, new_params, placement): (String, ChartParams, Placement)| {
+        if rendering.get_untracked() {
+            return;
+        }
+        set_rendering.set(true);
+        let indicators = indicators.get_untracked();
+        spawn_local(async move {
+            match render_chart(new_symbol.clone(), indicators.clone(), new_params.clone()).await {
+                Ok(new_html) => match (placement, on_append) {
+                    (Placement::Append, Some(on_append)) => {
+                        on_append.run(Chart::new(new_symbol, new_html, indicators, new_params));
+                    }
+                    _ => {
+                        if let Some(on_replace) = on_replace {
+                            let chart = Chart::new(new_symbol.clone(), new_html.clone(), indicators, new_params.clone());
+                            on_replace.run(chart);
+                        }
+                        set_symbol.set(new_symbol);
+                        set_params.set(new_params);
+                        set_html.set(new_html);
+                    }
+                },
+                Err(e) => {
+                    log::error!(target: "ui", "Chart render failed: {e}");
+                    toast::error("Couldn't redraw the chart");
+                }
+            }
+            set_rendering.set(false);
+        });
+    };
+
+    let drag_start = move |ev: web_sys::PointerEvent| {
+        let handle = event_target::<web_sys::HtmlElement>(&ev);
+        let Some(sheet) = handle.parent_element() else {
+            return;
+        };
+        let _ = handle.set_pointer_capture(ev.pointer_id());
+        let height = sheet.get_bounding_client_rect().height();
+        drag.set(Some((ev.client_y() as f64, height, height)));
+    };
+    let drag_move = move |ev: web_sys::PointerEvent| {
+        drag.update(|drag| {
+            if let Some((start_y, start_height, height)) = drag {
+                *height = (*start_height + *start_y - ev.client_y() as f64).max(0.0);
+            }
+        });
+    };
+    let drag_end = move |_| {
+        let Some((_, start_height, height)) = drag.get_untracked() else {
+            return;
+        };
+        drag.set(None);
+        let viewport = web_sys::window()
+            .and_then(|w| w.inner_height().ok())
+            .and_then(|h| h.as_f64())
+            .unwrap_or(height);
+        let snapped = if (height - start_height).abs() < 8.0 {
+            // A tap on the handle flips between half and full height
+            match sheet_state.get_untracked() {
+                SheetState::Full => SheetState::Half,
+                _ => SheetState::Full,
+            }
+        } else if height < viewport * 0.3 {
+            SheetState::Closed
+        } else if height > viewport * 0.7 {
+            SheetState::Full
+        } else {
+            SheetState::Half
+        };
+        sheet_state.set(snapped);
+    };
+    let dragged_height =
+        move || drag.get().map(|(_, _, height)| format!("{height}px")).unwrap_or_default();
+
+    view! {
+        {sheet.then(|| view! {
+            <div
+                class="sheet-backdrop"
+                class:open=move || sheet_state.get() != SheetState::Closed
+                on:click=move |_| sheet_state.set(SheetState::Closed)
+            ></div>
+            <button class="chart-open" on:click=move |_| sheet_state.set(SheetState::Half)>
+                {move || format!("📈 {} chart", symbol.get())}
+            </button>
+        })}
+        <div
+            class="chart-container"
+            class:sheet=sheet
+            class:sheet-open=move || sheet_state.get() != SheetState::Closed
+            class:sheet-full=move || sheet_state.get() == SheetState::Full
+            class:dragging=move || drag.with(Option::is_some)
+            style:height=dragged_height
+        >
+            {sheet.then(|| view! {
+                <div
+                    class="sheet-handle"
+                    title="Drag to resize"
+                    on:pointerdown=drag_start
+                    on:pointermove=drag_move
+                    on:pointerup=drag_end
+                    on:pointercancel=drag_end
+                ></div>
+            })}
+            <div class="chart-toolbar">
+                {Indicator::ALL.into_iter().map(|indicator| {
+                    let class = move || {
+                        if indicators.get().contains(&indicator) {
+                            "indicator-toggle active"
+                        } else {
+                            "indicator-toggle"
+                        }
+                    };
+                    view! {
+                        <button
+                            class=class
+                            prop:disabled=move || rendering.get()
+                            on:click=move |_| toggle(indicator)
+                        >
+                            {indicator.label()}
+                        </button>
+                    }
+                }).collect::<Vec<_>>()}
+                <button
+                    class="indicator-toggle chart-refresh"
+                    title="Run the chart tool again for fresh data"
+                    prop:disabled=move || rendering.get()
+                    on:click=move |_| rerun((symbol.get_untracked(), params.get_untracked(), Placement::Replace))
+                >
+                    "↻"
+                </button>
+                {move || rendering.get().then(|| view! { <span class="spinner"></span> })}
+                {on_pin.map(|on_pin| view! {
+                    <button
+                        class="indicator-toggle chart-pin"
+                        title="Pin to dashboard"
+                        on:click=move |_| on_pin.run(symbol.get_untracked())
+                    >
+                        "📌"
+                    </button>
+                })}
             </div>
+            <iframe
+                srcdoc=move || html.get()
+                title=move || format!("{} Wave Analysis", symbol.get())
+                data-symbol=symbol
+                sandbox="allow-scripts allow-fullscreen"
+                allowfullscreen=true
+                on:load=move |ev| {
+                    let iframe = event_target::<web_sys::HtmlIFrameElement>(&ev);
+                    post_theme(&iframe, dark_mode.get_untracked());
+                }
+            ></iframe>
+            <ChartParamsForm
+                symbol=symbol
+                params=params
+                rendering=rendering
+                can_append=on_append.is_some()
+                on_run=Callback::new(rerun)
+            />
         </div>
     }
 }
@@ -418,5 +2600,9 @@ fn App() -> impl IntoView {
 // ----------------------------------------------------------------------------
 
 fn main() {
-    mount_to_body(|| view! { <App/> })
+    logging::init();
+    element::register();
+    if !element::is_element() {
+        mount_to_body(|| view! { <App/> })
+    }
 }
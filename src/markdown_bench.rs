@@ -0,0 +1,82 @@
+use wasm_bindgen::JsCast;
+use wasm_bindgen_test::{wasm_bindgen_bench, Criterion};
+
+use crate::markdown_to_html;
+
+/// Response sizes benchmarked, in bytes
+const SIZES: &[usize] = &[1_000, 8_000, 32_000];
+
+/// Bytes per streamed `text` chunk, about what the backend sends
+const CHUNK: usize = 24;
+
+/// A typical analysis: headings, lists, emphasis, ticker mentions, a table and code.
+const SECTION: &str = "## AAPL wave count\n\n\
+    **AAPL** is completing wave 4 of a larger impulse, while MSFT and NVDA lead the sector.\n\n\
+    - Support: 182.40, the wave 1 high\n\
+    - Resistance: 198.20, the prior peak\n\
+    - Invalidation: a daily close under *support*\n\n\
+    | Level | Price | Fib |\n|---|---|---|\n| Target | 205.10 | 1.618 |\n| Stop | 181.90 | 0.382 |\n\n\
+    Momentum (`RSI 14`) is cooling rather than reversing; see [the chart](https://wxve.io) for detail.\n\n";
+
+/// A response of about `size` bytes, cut on a character boundary.
+fn document(size: usize) -> String {
+    let mut doc = SECTION.repeat(size / SECTION.len() + 1);
+    let end = (0..=size).rev().find(|&i| doc.is_char_boundary(i)).unwrap_or_default();
+    doc.truncate(end);
+    doc
+}
+
+/// Every prefix the stream view renders as `doc` arrives `CHUNK` bytes at a time.
+fn prefixes(doc: &str) -> Vec<&str> {
+    (CHUNK..doc.len() + CHUNK)
+        .step_by(CHUNK)
+        .map(|end| {
+            let end = (end.min(doc.len())..=doc.len()).find(|&i| doc.is_char_boundary(i)).unwrap_or(doc.len());
+            &doc[..end]
+        })
+        .collect()
+}
+
+#[wasm_bindgen_bench]
+fn render_finished_message(c: &mut Criterion) {
+    for &size in SIZES {
+        let doc = document(size);
+        c.bench_function(&format!("markdown_to_html {size}B"), |b| {
+            b.iter(|| markdown_to_html(&doc))
+        });
+    }
+}
+
+/// The streaming view re-renders the whole response after every chunk, so a
+/// response costs the sum over all its prefixes.
+#[wasm_bindgen_bench]
+fn render_streamed_response(c: &mut Criterion) {
+    for &size in SIZES {
+        let doc = document(size);
+        let prefixes = prefixes(&doc);
+        c.bench_function(&format!("stream markdown {size}B"), |b| {
+            b.iter(|| prefixes.iter().map(|p| markdown_to_html(p).len()).sum::<usize>())
+        });
+    }
+}
+
+/// As above, including handing each render to the DOM the way the message view does.
+#[wasm_bindgen_bench]
+fn rerender_streamed_message(c: &mut Criterion) {
+    let document_element = web_sys::window().unwrap().document().unwrap();
+    let message: web_sys::HtmlElement = document_element.create_element("span").unwrap().unchecked_into();
+    document_element.body().unwrap().append_child(&message).unwrap();
+    for &size in SIZES {
+        let doc = document(size);
+        let prefixes = prefixes(&doc);
+        c.bench_function(&format!("stream message re-render {size}B"), |b| {
+            b.iter(|| {
+                for prefix in &prefixes {
+                    message.set_inner_html(&markdown_to_html(prefix));
+                }
+                message.offset_height()
+            })
+        });
+    }
+    message.remove();
+}
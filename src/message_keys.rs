@@ -0,0 +1,75 @@
+use leptos::prelude::{GetUntracked, RwSignal, Set, WithUntracked};
+use web_sys::{ScrollIntoViewOptions, ScrollLogicalPosition};
+
+use crate::state::{use_app_state, AppState};
+use crate::{dialog, is_typing, permalink, query_all, share, window_listener, Role};
+
+/// Show the message `id` as focused, scrolling it into view if it isn't.
+fn focus(state: AppState, focused: RwSignal<Option<usize>>, id: Option<usize>) {
+    focused.set(id);
+    let Some(at) = id.and_then(|id| state.index_of(id)) else {
+        return;
+    };
+    let anchor = permalink::anchor(state.archived.get_untracked() + at);
+    if let Some(message) = query_all(&format!("#{anchor}")).pop() {
+        let options = ScrollIntoViewOptions::new();
+        options.set_block(ScrollLogicalPosition::Nearest);
+        message.scroll_into_view_with_scroll_into_view_options(&options);
+    }
+}
+
+/// Vim-style keys over the conversation, for the life of the page: j/k move the focused
+/// message (starting from the latest), y copies it, r regenerates it if it's a response,
+/// d deletes it and Esc lets go. Listed in `SHORTCUTS`.
+pub fn use_message_keys(focused: RwSignal<Option<usize>>) {
+    let state = use_app_state();
+    window_listener(leptos::ev::keydown, move |ev| {
+        if is_typing(&ev) || ev.ctrl_key() || ev.meta_key() || ev.alt_key() || dialog::is_open() {
+            return;
+        }
+        let messages = state.messages;
+        // A message that's gone (deleted, regenerated) isn't focused any more
+        let at = focused.get_untracked().and_then(|id| state.index_of(id));
+        let id_at = |at: usize| messages.with_untracked(|m| m.get(at).map(|m| m.id));
+        let last = messages.with_untracked(Vec::len).checked_sub(1);
+        match ev.key().as_str() {
+            "j" => {
+                let next = match at {
+                    Some(at) => last.map(|last| (at + 1).min(last)),
+                    None => last,
+                };
+                focus(state, focused, next.and_then(id_at));
+            }
+            "k" => {
+                let previous = at.map_or(last, |at| Some(at.saturating_sub(1)));
+                focus(state, focused, previous.and_then(id_at));
+            }
+            "y" => {
+                if let Some(at) = at {
+                    share::copy(messages.with_untracked(|m| m[at].content.clone()));
+                }
+            }
+            "r" => {
+                if let Some(at) = at
+                    && messages.with_untracked(|m| m[at].role == Role::Assistant)
+                {
+                    state.regenerate(messages.with_untracked(|m| m[at].id));
+                }
+            }
+            "d" => {
+                if let Some(at) = at
+                    && !state.loading.get_untracked()
+                {
+                    state.delete(messages.with_untracked(|m| m[at].id));
+                    // Move on to what took its place, or the one before at the end
+                    let last = messages.with_untracked(Vec::len).checked_sub(1);
+                    let next = last.map(|last| at.min(last));
+                    focus(state, focused, next.and_then(id_at));
+                }
+            }
+            "Escape" if at.is_some() => focused.set(None),
+            _ => return,
+        }
+        ev.prevent_default();
+    });
+}
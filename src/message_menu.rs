@@ -0,0 +1,192 @@
+use std::time::Duration;
+
+use leptos::prelude::{
+    component, set_timeout_with_handle, view, Callable, Callback, Get,
+    GetUntracked, GetValue, IntoView, Memo, RwSignal, Set, SetValue, StoredValue, TimeoutHandle,
+    With,
+};
+use leptos::tachys::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::reply::excerpt;
+use crate::state::use_app_state;
+use crate::{permalink, share, window_listener, Role};
+
+/// How long a touch is held still before the menu opens
+const LONG_PRESS: Duration = Duration::from_millis(500);
+/// Movement that turns a press into a scroll or a swipe
+const SLOP: f64 = 10.0;
+/// Parts of a message that handle their own pointer gestures
+const IGNORED: &str = ".chart-container, .sheet-backdrop, .message-edit, button";
+
+/// Opens a message's menu when a touch is held still on it.
+#[derive(Clone, Copy)]
+pub struct LongPress {
+    /// Where the touch went down, while it may still become a long press
+    start: StoredValue<Option<(f64, f64)>>,
+    timer: StoredValue<Option<TimeoutHandle>>,
+}
+
+impl LongPress {
+    pub fn new() -> Self {
+        LongPress { start: StoredValue::new(None), timer: StoredValue::new(None) }
+    }
+
+    /// A touch is down and hasn't moved; the browser's own long-press menu is held back
+    pub fn pressing(self) -> bool {
+        self.start.get_value().is_some()
+    }
+
+    pub fn down(self, ev: &web_sys::PointerEvent, on_press: impl FnOnce() + 'static) {
+        let ignored = ev
+            .target()
+            .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+            .is_some_and(|el| el.closest(IGNORED).ok().flatten().is_some());
+        if ev.pointer_type() == "mouse" || !ev.is_primary() || ignored {
+            return;
+        }
+        self.cancel();
+        self.start.set_value(Some((ev.client_x() as f64, ev.client_y() as f64)));
+        let timer = set_timeout_with_handle(
+            move || {
+                self.timer.set_value(None);
+                on_press();
+            },
+            LONG_PRESS,
+        );
+        self.timer.set_value(timer.ok());
+    }
+
+    pub fn moved(self, ev: &web_sys::PointerEvent) {
+        if let Some((x, y)) = self.start.get_value()
+            && (ev.client_x() as f64 - x).hypot(ev.client_y() as f64 - y) > SLOP
+        {
+            self.cancel();
+        }
+    }
+
+    pub fn cancel(self) {
+        self.start.set_value(None);
+        if let Some(timer) = self.timer.get_value() {
+            timer.clear();
+            self.timer.set_value(None);
+        }
+    }
+}
+
+/// Close the open message menu on a click outside it or Escape, for the life of the page.
+pub fn use_dismiss(open: RwSignal<Option<usize>>) {
+    window_listener(leptos::ev::pointerdown, move |ev| {
+        if open.get_untracked().is_none() {
+            return;
+        }
+        let inside = ev
+            .target()
+            .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+            .is_some_and(|el| el.closest(".message-menu, .menu-toggle").ok().flatten().is_some());
+        if !inside {
+            open.set(None);
+        }
+    });
+    window_listener(leptos::ev::keydown, move |ev| {
+        if ev.key() == "Escape" && open.get_untracked().is_some() {
+            open.set(None);
+        }
+    });
+}
+
+/// "⋯" button and the menu of everything that can be done with one message, also opened
+/// by a long press on touch screens.
+#[component]
+pub fn MessageMenu(
+    id: usize,
+    role: Role,
+    content: String,
+    /// The message whose menu is open; one at a time
+    open: RwSignal<Option<usize>>,
+    on_edit: Callback<()>,
+    on_quote: Callback<String>,
+    on_select: Callback<()>,
+) -> impl IntoView {
+    let state = use_app_state();
+    let loading = state.loading;
+    let is_open = move || open.get() == Some(id);
+    let pinned = Memo::new(move |_| state.messages.with(|m| m.iter().any(|m| m.id == id && m.pinned)));
+    let content = StoredValue::new(content);
+    let close = move || open.set(None);
+
+    view! {
+        <button
+            class="menu-toggle"
+            title="More actions"
+            aria-haspopup="menu"
+            aria-expanded=move || is_open().to_string()
+            on:click=move |_| open.set(if is_open() { None } else { Some(id) })
+        >
+            "⋯"
+        </button>
+        {move || is_open().then(|| view! {
+            <div class="message-menu" role="menu">
+                <button role="menuitem" on:click=move |_| {
+                    close();
+                    share::copy(content.get_value());
+                }>
+                    "Copy"
+                </button>
+                {match role {
+                    Role::User => view! {
+                        <button role="menuitem" disabled=loading on:click=move |_| {
+                            close();
+                            on_edit.run(());
+                        }>
+                            "Edit & resend"
+                        </button>
+                    }.into_any(),
+                    Role::Assistant => view! {
+                        <button role="menuitem" on:click=move |_| {
+                            close();
+                            on_quote.run(excerpt(&content.get_value()));
+                        }>
+                            "Quote in reply"
+                        </button>
+                        <button role="menuitem" disabled=loading on:click=move |_| {
+                            close();
+                            state.regenerate(id);
+                        }>
+                            "Regenerate"
+                        </button>
+                    }.into_any(),
+                }}
+                {move || (!state.incognito.get()).then(|| view! {
+                    <button role="menuitem" on:click=move |_| {
+                        close();
+                        if let Some(at) = state.index_of(id) {
+                            let position = state.archived.get_untracked() + at;
+                            share::copy(permalink::url(&state.conversation_id.get_untracked(), position));
+                        }
+                    }>
+                        "Copy link"
+                    </button>
+                })}
+                <button role="menuitem" on:click=move |_| {
+                    close();
+                    on_select.run(());
+                }>
+                    "Select"
+                </button>
+                <button role="menuitem" on:click=move |_| {
+                    close();
+                    state.toggle_pin(id);
+                }>
+                    {move || if pinned.get() { "Unpin" } else { "Pin" }}
+                </button>
+                <button role="menuitem" class="danger" disabled=loading on:click=move |_| {
+                    close();
+                    state.delete(id);
+                }>
+                    "Delete"
+                </button>
+            </div>
+        })}
+    }
+}
@@ -0,0 +1,225 @@
+use std::time::Duration;
+
+use leptos::task::spawn_local;
+use serde_json::{json, Value};
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsValue;
+use web_sys::{ReadableStreamDefaultController, RequestInit, Response};
+
+use crate::{new_id, sleep};
+
+/// Symbol used when the message doesn't name one
+const DEFAULT_SYMBOL: &str = "AAPL";
+
+const REPLIES: &[&str] = &[
+    "**{symbol}** is working through what looks like a corrective wave 4. Price is holding above the \
+     38.2% retracement of the prior advance, which keeps the larger uptrend intact.\n\n\
+     - Support: the wave 1 high, roughly 6% below here\n\
+     - Invalidation: a daily close under that level\n\
+     - Target: a wave 5 extension toward the previous high\n\n\
+     Momentum is cooling rather than reversing, so a pullback into support would be the cleaner entry.",
+    "The structure on **{symbol}** reads as a completed five-wave decline, so an A-B-C bounce is the \
+     most likely next move.\n\n\
+     1. Wave A has already retraced about a third of the drop\n\
+     2. A shallow wave B would set up the final leg higher\n\
+     3. Wave C typically matches A in length\n\n\
+     Volume on the bounce has been light, which fits a correction rather than a new trend.",
+    "**{symbol}** is extended. The current leg is already 1.618 times wave 1, a common place for a \
+     third wave to stall.\n\n\
+     I'd watch for a sideways wave 4 before any further upside. A break of the rising trendline \
+     would be the first sign it has started.",
+];
+
+/// True when the page was opened with `?mock=1`: requests are answered locally
+/// with generated data instead of going to the API, for UI work and demos.
+pub fn enabled() -> bool {
+    let search = web_sys::window()
+        .and_then(|w| w.location().search().ok())
+        .unwrap_or_default();
+    search
+        .trim_start_matches('?')
+        .split('&')
+        .any(|pair| matches!(pair, "mock=1" | "mock=true"))
+}
+
+/// Answer a request the way the API would. Endpoints without a mock fail like a 404.
+pub async fn fetch(path: &str, opts: &RequestInit) -> Result<Response, String> {
+    let method = opts.get_method().unwrap_or_else(|| "GET".to_string());
+    let (route, query) = path.split_once('?').unwrap_or((path, ""));
+    let param = |name: &str| {
+        query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix(name)?.strip_prefix('='))
+            .map(|value| value.split(',').map(String::from).collect::<Vec<_>>())
+            .unwrap_or_default()
+    };
+    let body = || {
+        opts.get_body()
+            .as_string()
+            .and_then(|body| serde_json::from_str::<Value>(&body).ok())
+            .unwrap_or_default()
+    };
+
+    match (method.as_str(), route) {
+        ("POST", "/chat") => {
+            let message = body()["message"].as_str().unwrap_or_default().to_string();
+            sse(chat_events(&message).into_iter(), opts.get_signal())
+        }
+        ("POST", "/chart") => {
+            let symbol = body()["symbol"].as_str().unwrap_or(DEFAULT_SYMBOL).to_string();
+            json_response(&json!({ "html": chart_html(&symbol) }))
+        }
+        ("GET", "/quotes") => {
+            let quotes = param("symbols").iter().map(|s| quote(s)).collect::<Vec<_>>();
+            json_response(&json!({ "quotes": quotes }))
+        }
+        ("GET", "/quotes/stream") => {
+            let symbols = param("symbols");
+            if symbols.is_empty() {
+                return sse(std::iter::empty(), opts.get_signal());
+            }
+            let events = std::iter::repeat_with(move || {
+                let symbol = &symbols[(js_sys::Math::random() * symbols.len() as f64) as usize];
+                (Duration::from_millis(500), quote(symbol).to_string())
+            });
+            sse(events, opts.get_signal())
+        }
+        ("GET", "/alerts/stream") => sse(std::iter::empty(), opts.get_signal()),
+        ("POST", "/alerts") => json_response(&json!({ "id": new_id() })),
+        ("POST", "/tools/approval") => json_response(&json!({})),
+        ("GET", "/status") => json_response(&json!({ "status": "operational" })),
+        ("GET", "/config") => json_response(&json!({})),
+        ("GET", "/tools") => json_response(&json!({ "tools": [
+            { "name": "wave_analysis", "description": "Elliott wave count and chart for a symbol" },
+            { "name": "web_search", "description": "Recent news and filings from the web" },
+            { "name": "price_alerts", "description": "Set price alerts on your behalf" },
+        ] })),
+        ("GET", "/fx") => json_response(&json!({
+            "rates": { "USD": 1.0, "EUR": 0.92, "GBP": 0.79, "JPY": 149.5, "CHF": 0.88 }
+        })),
+        ("DELETE", "/conversations") => json_response(&json!({ "deleted": 0 })),
+        ("DELETE", _) | ("POST", "/telemetry") => json_response(&json!({})),
+        _ => Err(format!("HTTP 404 ({method} {route} has no mock)")),
+    }
+}
+
+fn json_response(value: &Value) -> Result<Response, String> {
+    Response::new_with_opt_str(Some(&value.to_string())).map_err(|e| format!("{e:?}"))
+}
+
+/// A response whose body streams each event as an SSE `data:` line after its delay.
+/// Ends when the events run out or `signal` aborts.
+pub fn sse(
+    events: impl Iterator<Item = (Duration, String)> + 'static,
+    signal: Option<web_sys::AbortSignal>,
+) -> Result<Response, String> {
+    let start = Closure::once_into_js(move |controller: ReadableStreamDefaultController| {
+        spawn_local(async move {
+            for (delay, data) in events {
+                sleep(delay).await;
+                if signal.as_ref().is_some_and(|s| s.aborted()) {
+                    break;
+                }
+                let line = format!("data: {data}\n\n");
+                let chunk = js_sys::Uint8Array::from(line.as_bytes());
+                if controller.enqueue_with_chunk(&chunk).is_err() {
+                    return;
+                }
+            }
+            let _ = controller.close();
+        });
+    });
+    let source = js_sys::Object::new();
+    js_sys::Reflect::set(&source, &JsValue::from_str("start"), &start).map_err(|e| format!("{e:?}"))?;
+    let stream = web_sys::ReadableStream::new_with_underlying_source(&source)
+        .map_err(|e| format!("{e:?}"))?;
+    Response::new_with_opt_readable_stream(Some(&stream)).map_err(|e| format!("{e:?}"))
+}
+
+fn random_between(low: f64, high: f64) -> f64 {
+    low + js_sys::Math::random() * (high - low)
+}
+
+fn delay(low_ms: f64, high_ms: f64) -> Duration {
+    Duration::from_millis(random_between(low_ms, high_ms) as u64)
+}
+
+/// First word that looks like a ticker, e.g. `NVDA` in "what about NVDA?"
+fn symbol_in(message: &str) -> String {
+    message
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .find(|word| (1..=5).contains(&word.len()) && word.chars().all(|c| c.is_ascii_uppercase()))
+        .unwrap_or(DEFAULT_SYMBOL)
+        .to_string()
+}
+
+/// A plausible reply: metadata, a chart tool call, then text streamed a few words at a time.
+fn chat_events(message: &str) -> Vec<(Duration, String)> {
+    let symbol = symbol_in(message);
+    let reply = REPLIES[(js_sys::Math::random() * REPLIES.len() as f64) as usize]
+        .replace("{symbol}", &symbol);
+
+    let mut events = vec![
+        (delay(150.0, 400.0), json!({ "type": "meta", "model": "mock", "request_id": new_id() })),
+        (delay(100.0, 300.0), json!({ "type": "tool_start", "name": "wave_analysis" })),
+        (
+            delay(600.0, 1_500.0),
+            json!({
+                "type": "chart",
+                "symbol": symbol,
+                "html": chart_html(&symbol),
+                "params": { "timeframe": "1d", "lookback": 250, "degree": "intermediate" },
+            }),
+        ),
+        (delay(50.0, 150.0), json!({ "type": "tool_end", "name": "wave_analysis" })),
+    ];
+    let words = reply.split_inclusive(' ').collect::<Vec<_>>();
+    for chunk in words.chunks(3) {
+        events.push((delay(30.0, 120.0), json!({ "type": "text", "content": chunk.concat() })));
+    }
+    events.push((
+        delay(50.0, 150.0),
+        json!({ "type": "sentiment", "score": (random_between(-1.0, 1.0) * 100.0).round() / 100.0 }),
+    ));
+    events.push((delay(50.0, 150.0), json!({ "type": "done" })));
+    events.into_iter().map(|(delay, event)| (delay, event.to_string())).collect()
+}
+
+fn quote(symbol: &str) -> Value {
+    json!({
+        "symbol": symbol,
+        "price": (random_between(50.0, 500.0) * 100.0).round() / 100.0,
+        "change_percent": (random_between(-3.0, 3.0) * 100.0).round() / 100.0,
+    })
+}
+
+/// A random walk drawn as an SVG line, standing in for a rendered chart.
+fn chart_html(symbol: &str) -> String {
+    const POINTS: usize = 120;
+    let mut price = 100.0;
+    let mut prices = Vec::with_capacity(POINTS);
+    for _ in 0..POINTS {
+        price += random_between(-2.0, 2.1);
+        prices.push(price);
+    }
+    let (low, high) = prices
+        .iter()
+        .fold((f64::MAX, f64::MIN), |(low, high), &p| (low.min(p), high.max(p)));
+    let points = prices
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let x = i as f64 / (POINTS - 1) as f64 * 1000.0;
+            let y = 380.0 - (p - low) / (high - low).max(1.0) * 360.0;
+            format!("{x:.1},{y:.1}")
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "<!doctype html><html><body style=\"margin:0;font-family:system-ui;background:#fff\">\
+         <p style=\"margin:8px 12px;font-size:14px\">{symbol} (mock data)</p>\
+         <svg viewBox=\"0 0 1000 400\" preserveAspectRatio=\"none\" style=\"width:100%;height:360px\">\
+         <polyline fill=\"none\" stroke=\"#2563eb\" stroke-width=\"2\" points=\"{points}\"/>\
+         </svg></body></html>"
+    )
+}
@@ -0,0 +1,109 @@
+use leptos::prelude::{
+    component, signal, view, Callable, Callback, Effect, For, Get, GetValue, IntoView, Memo,
+    RwSignal, Set, StoredValue, UpdateValue, With,
+};
+use leptos::tachys::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::{fetch_news, toggle_panel, Article, Panel};
+
+/// Cap on symbols per request so long conversations don't fan out
+const MAX_SYMBOLS: usize = 8;
+
+/// Articles link out with their `url`, so anything but a web page (`javascript:` above
+/// all) is left out.
+fn is_web_link(url: &str) -> bool {
+    let url = url.to_ascii_lowercase();
+    url.starts_with("https://") || url.starts_with("http://")
+}
+
+#[component]
+pub fn News(
+    symbols: Memo<Vec<String>>,
+    open_panel: RwSignal<Option<Panel>>,
+    on_summarize: Callback<String>,
+) -> impl IntoView {
+    let articles = RwSignal::new(Vec::<Article>::new());
+    let (loading, set_loading) = signal(false);
+    // Only the latest fetch may land, so a slow older one can't bring back stale headlines
+    let generation = StoredValue::new(0u64);
+
+    Effect::new(move |_| {
+        let mut symbols = symbols.get();
+        symbols.truncate(MAX_SYMBOLS);
+        generation.update_value(|g| *g += 1);
+        let current = generation.get_value();
+        if symbols.is_empty() {
+            articles.set(Vec::new());
+            set_loading.set(false);
+            return;
+        }
+        set_loading.set(true);
+        spawn_local(async move {
+            let fetched = fetch_news(&symbols).await;
+            if generation.get_value() != current {
+                return;
+            }
+            match fetched {
+                Ok(fetched) => {
+                    articles.set(fetched.into_iter().filter(|a| is_web_link(&a.url)).collect());
+                }
+                Err(e) => log::error!(target: "transport", "News fetch failed: {e}"),
+            }
+            set_loading.set(false);
+        });
+    });
+
+    view! {
+        <button
+            class="icon-btn news-toggle"
+            title="News"
+            on:click=move |_| toggle_panel(open_panel, Panel::News)
+        >
+            "📰"
+        </button>
+        <aside class="panel news" class:open=move || open_panel.get() == Some(Panel::News)>
+            <div class="panel-header">
+                "News"
+                {move || loading.get().then(|| view! { <span class="spinner"></span> })}
+            </div>
+            {move || {
+                (articles.with(Vec::is_empty) && !loading.get()).then(|| view! {
+                    <p class="panel-empty">"Headlines for symbols you discuss will show up here."</p>
+                })
+            }}
+            <ul class="panel-items">
+                <For
+                    each=move || articles.get()
+                    key=|article| article.url.clone()
+                    children=move |article| {
+                        let url = article.url.clone();
+                        view! {
+                            <li class="news-item">
+                                <a
+                                    class="news-title"
+                                    href=article.url
+                                    target="_blank"
+                                    rel="noopener noreferrer"
+                                >
+                                    {article.title}
+                                </a>
+                                <div class="news-meta">
+                                    <span class="news-symbol">{article.symbol}</span>
+                                    <span>{article.source}</span>
+                                    <span>{article.published_at}</span>
+                                    <button
+                                        class="news-summarize"
+                                        on:click=move |_| on_summarize.run(url.clone())
+                                    >
+                                        "Summarize"
+                                    </button>
+                                </div>
+                            </li>
+                        }
+                    }
+                />
+            </ul>
+        </aside>
+    }
+}
@@ -0,0 +1,149 @@
+use leptos::prelude::{component, request_animation_frame, view, For, Get, IntoView, Memo, RwSignal, With};
+use leptos::tachys::prelude::*;
+
+use crate::branches::Fork;
+use crate::state::{use_app_state, AppState};
+use crate::{query_all, toggle_panel, Message, Panel, Role};
+
+const LABEL_LENGTH: usize = 48;
+
+/// One message in the outline, and how to bring it onto the live path.
+#[derive(Clone, PartialEq)]
+pub struct OutlineRow {
+    pub depth: usize,
+    pub role: Role,
+    pub label: String,
+    /// Position in the path once `route` has been switched to
+    pub index: usize,
+    /// `(fork position, branch)` switches leading to this message, outermost first;
+    /// empty for messages already on the live path
+    pub route: Vec<(usize, usize)>,
+    /// `(branch, branch count)` on the first message of each branch
+    pub branch: Option<(usize, usize)>,
+    pub pinned: bool,
+}
+
+/// Flatten the conversation tree: at each fork the set-aside branches are listed,
+/// indented, ahead of the continuation they diverge from.
+fn outline(path: &[Message], forks: &[Fork]) -> Vec<OutlineRow> {
+    let mut rows = Vec::new();
+    walk(&mut rows, path, 0, forks, &[], 0);
+    rows
+}
+
+fn walk(
+    rows: &mut Vec<OutlineRow>,
+    messages: &[Message],
+    start: usize,
+    forks: &[Fork],
+    route: &[(usize, usize)],
+    depth: usize,
+) {
+    for (offset, message) in messages.iter().enumerate() {
+        let index = start + offset;
+        let fork = forks.iter().find(|f| f.at == index);
+        if let Some(fork) = fork {
+            for (branch, alternative) in fork.branches.iter().enumerate() {
+                if branch == fork.active {
+                    continue;
+                }
+                let first = rows.len();
+                let route = [route, &[(index, branch)]].concat();
+                walk(rows, &alternative.messages, index, &alternative.forks, &route, depth + 1);
+                if let Some(row) = rows.get_mut(first) {
+                    row.branch = Some((branch, fork.branches.len()));
+                }
+            }
+        }
+        rows.push(OutlineRow {
+            depth,
+            role: message.role,
+            label: label_for(&message.content),
+            index,
+            route: route.to_vec(),
+            branch: fork.map(|f| (f.active, f.branches.len())),
+            pinned: message.pinned,
+        });
+    }
+}
+
+fn label_for(content: &str) -> String {
+    let line = content.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or_default();
+    match line.char_indices().nth(LABEL_LENGTH) {
+        Some((end, _)) => format!("{}…", &line[..end]),
+        None => line.to_string(),
+    }
+}
+
+/// Bring an outline row onto the live path, then scroll to it.
+fn jump_to(state: AppState, row: &OutlineRow) {
+    if state.loading.get() {
+        return;
+    }
+    for &(at, branch) in &row.route {
+        state.switch_branch(at, branch);
+    }
+    let index = row.index;
+    request_animation_frame(move || {
+        if let Some(element) = query_all(".messages > .message").get(index) {
+            element.scroll_into_view();
+        }
+    });
+}
+
+/// Outline of the conversation tree; clicking a message switches to its branch and scrolls to it.
+#[component]
+pub fn Outline(open_panel: RwSignal<Option<Panel>>) -> impl IntoView {
+    let state = use_app_state();
+    let (messages, forks) = (state.messages, state.forks);
+    let rows = Memo::new(move |_| messages.with(|m| forks.with(|f| outline(m, f))));
+
+    view! {
+        <button
+            class="icon-btn outline-toggle"
+            title="Conversation outline"
+            on:click=move |_| toggle_panel(open_panel, Panel::Outline)
+        >
+            "🌳"
+        </button>
+        <aside class="panel outline" class:open=move || open_panel.get() == Some(Panel::Outline)>
+            <div class="panel-header">"Outline"</div>
+            {move || {
+                rows.with(Vec::is_empty).then(|| view! {
+                    <p class="panel-empty">"Messages and the branches you create by editing or regenerating show up here."</p>
+                })
+            }}
+            <ul class="panel-items">
+                <For
+                    each=move || rows.get().into_iter().enumerate()
+                    key=|(i, row)| (*i, row.route.clone(), row.index, row.pinned)
+                    children=move |(_, row)| {
+                        let live = row.route.is_empty();
+                        let style = format!("padding-left: {}rem", row.depth as f64 * 0.75);
+                        let role = match row.role {
+                            Role::User => "user",
+                            Role::Assistant => "assistant",
+                        };
+                        let branch = row.branch.map(|(branch, count)| {
+                            view! { <span class="outline-branch">{format!("{}/{}", branch + 1, count)}</span> }
+                        });
+                        let pinned = row.pinned;
+                        let label = if row.label.is_empty() { "(empty)".to_string() } else { row.label.clone() };
+                        view! {
+                            <li
+                                class=format!("outline-row {role}")
+                                class:live=live
+                                style=style
+                                on:click=move |_| jump_to(state, &row)
+                            >
+                                {branch}
+                                {pinned.then(|| view! { <span class="outline-pin" title="Pinned">"📌"</span> })}
+                                <span class="outline-label">{label}</span>
+                            </li>
+                        }
+                    }
+                />
+            </ul>
+        </aside>
+    }
+}
@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use leptos::prelude::{request_animation_frame, set_timeout, GetUntracked, WithUntracked};
+
+use crate::query_all;
+use crate::state::AppState;
+use crate::toast;
+
+const PATH_PREFIX: &str = "/c/";
+/// How long a linked message stays highlighted
+const HIGHLIGHT: Duration = Duration::from_secs(2);
+
+/// A link to a saved conversation, optionally to one message in it:
+/// `/c/:id#m42` is the 43rd message, counting from the start of the conversation.
+pub struct Permalink {
+    pub conversation: String,
+    pub message: Option<usize>,
+}
+
+/// The anchor of the message at `position` in its conversation, archived messages included.
+pub fn anchor(position: usize) -> String {
+    format!("m{position}")
+}
+
+/// Full URL of the message at `position` in `conversation`.
+pub fn url(conversation: &str, position: usize) -> String {
+    let origin = web_sys::window()
+        .and_then(|w| w.location().origin().ok())
+        .unwrap_or_default();
+    format!("{origin}{PATH_PREFIX}{conversation}#{}", anchor(position))
+}
+
+/// Read a permalink from the URL, then go back to `/` since the conversation it names
+/// becomes the open one (and a new one may be started from there).
+pub fn take() -> Option<Permalink> {
+    let window = web_sys::window()?;
+    let location = window.location();
+    let path = location.pathname().ok()?;
+    let conversation = path.strip_prefix(PATH_PREFIX)?.trim_end_matches('/');
+    if conversation.is_empty() {
+        return None;
+    }
+    let message = location
+        .hash()
+        .ok()
+        .and_then(|hash| hash.strip_prefix("#m")?.parse().ok());
+    let link = Permalink { conversation: conversation.to_string(), message };
+    if let Ok(history) = window.history() {
+        let url = format!("/{}", location.search().unwrap_or_default());
+        let _ = history.replace_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&url));
+    }
+    Some(link)
+}
+
+/// Open the linked conversation and scroll to the linked message, loading archived
+/// messages back in if it's among them.
+pub async fn open(state: AppState, link: Permalink) {
+    let found = state.conversations.with_untracked(|c| c.iter().any(|c| c.id == link.conversation));
+    if !found {
+        // Conversations only live in the browser that had them
+        toast::error("That conversation isn't saved in this browser");
+        return;
+    }
+    state.switch_conversation(&link.conversation);
+    let Some(position) = link.message else {
+        return;
+    };
+    while state.archived.get_untracked() > position {
+        let before = state.archived.get_untracked();
+        state.load_earlier().await;
+        if state.archived.get_untracked() == before {
+            break;
+        }
+    }
+    highlight(position);
+}
+
+/// Scroll the message at `position` into view and flash it.
+pub fn highlight(position: usize) {
+    request_animation_frame(move || {
+        let Some(message) = query_all(&format!("#{}", anchor(position))).pop() else {
+            toast::info("That message is no longer in the conversation");
+            return;
+        };
+        message.scroll_into_view();
+        let _ = message.class_list().add_1("highlighted");
+        set_timeout(
+            move || {
+                let _ = message.class_list().remove_1("highlighted");
+            },
+            HIGHLIGHT,
+        );
+    });
+}
@@ -0,0 +1,60 @@
+use leptos::prelude::{component, view, Get, IntoView, Set};
+use leptos::tachys::prelude::*;
+
+use crate::portfolio::pnl_class;
+use crate::state::use_app_state;
+
+/// What goes with every request of this conversation besides its messages, pinned above
+/// them so it's clear what Xve is assuming. Only the attached portfolio for now; collapsed
+/// to one line until opened.
+#[component]
+pub fn PinnedContext() -> impl IntoView {
+    let state = use_app_state();
+
+    move || {
+        state.attached_portfolio.get().map(|summary| {
+            let count = summary.positions.len();
+            let positions = summary
+                .positions
+                .into_iter()
+                .map(|p| view! {
+                    <li>
+                        <span class="position-symbol">{p.symbol}</span>
+                        <span class="position-quantity">
+                            {format!("{} @ {:.2}", p.quantity, p.cost_basis)}
+                        </span>
+                        {p.unrealized_pnl.map(|pnl| view! {
+                            <span class=pnl_class(pnl)>{format!("{pnl:+.2}")}</span>
+                        })}
+                    </li>
+                })
+                .collect::<Vec<_>>();
+            view! {
+                <details class="pinned-context">
+                    <summary>
+                        {format!(
+                            "Portfolio attached: {count} position{}, value {:.2}",
+                            if count == 1 { "" } else { "s" },
+                            summary.total_value,
+                        )}
+                    </summary>
+                    <p class="settings-hint">
+                        "Sent with every message in this conversation, as it was when you attached it."
+                    </p>
+                    <ul class="pinned-context-items">{positions}</ul>
+                    <div class="pinned-context-actions">
+                        <span class=pnl_class(summary.total_unrealized_pnl)>
+                            {format!("P&L {:+.2}", summary.total_unrealized_pnl)}
+                        </span>
+                        <button
+                            class="dialog-button"
+                            on:click=move |_| state.attached_portfolio.set(None)
+                        >
+                            "Detach"
+                        </button>
+                    </div>
+                </details>
+            }
+        })
+    }
+}
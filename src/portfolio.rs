@@ -0,0 +1,258 @@
+use std::collections::HashMap;
+
+use leptos::prelude::{
+    component, event_target, event_target_value, signal, view, Callable, Callback, For, Get,
+    IntoView, Memo, RwSignal, Set, Update, With,
+};
+use leptos::tachys::prelude::*;
+use leptos::task::spawn_local;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen_futures::JsFuture;
+
+use crate::dialog;
+use crate::toast;
+use crate::watchlist::{normalize_symbol, poll_quotes};
+use crate::{
+    load_json, save_json, toggle_panel, Panel, PortfolioSummary, PositionSummary, Quote,
+};
+
+const PORTFOLIO_KEY: &str = "wxve-chat:portfolio";
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Position {
+    pub symbol: String,
+    pub quantity: f64,
+    /// Average cost per share
+    pub cost_basis: f64,
+}
+
+pub fn load_positions() -> Vec<Position> {
+    load_json(PORTFOLIO_KEY).unwrap_or_default()
+}
+
+pub fn save_positions(positions: &[Position]) {
+    save_json(PORTFOLIO_KEY, &positions);
+}
+
+/// Add to an existing position (averaging the cost basis) or open a new one.
+fn merge_position(positions: &mut Vec<Position>, new: Position) {
+    match positions.iter_mut().find(|p| p.symbol == new.symbol) {
+        Some(existing) => {
+            let quantity = existing.quantity + new.quantity;
+            if quantity != 0.0 {
+                existing.cost_basis = (existing.cost_basis * existing.quantity
+                    + new.cost_basis * new.quantity)
+                    / quantity;
+            }
+            existing.quantity = quantity;
+        }
+        None => positions.push(new),
+    }
+}
+
+fn parse_position(symbol: &str, quantity: &str, cost_basis: &str) -> Option<Position> {
+    Some(Position {
+        symbol: normalize_symbol(symbol)?,
+        quantity: quantity.trim().parse().ok()?,
+        cost_basis: cost_basis.trim().trim_start_matches('$').parse().ok()?,
+    })
+}
+
+/// Parse `symbol,quantity,cost_basis` rows, skipping headers and malformed lines.
+fn parse_csv(text: &str) -> Vec<Position> {
+    text.lines()
+        .filter_map(|line| {
+            let mut fields = line.split(',');
+            parse_position(fields.next()?, fields.next()?, fields.next()?)
+        })
+        .collect()
+}
+
+fn summarize(positions: &[Position], quotes: &HashMap<String, Quote>) -> PortfolioSummary {
+    let positions: Vec<PositionSummary> = positions
+        .iter()
+        .map(|p| {
+            let price = quotes.get(&p.symbol).map(|q| q.price);
+            PositionSummary {
+                symbol: p.symbol.clone(),
+                quantity: p.quantity,
+                cost_basis: p.cost_basis,
+                price,
+                market_value: price.map(|price| price * p.quantity),
+                unrealized_pnl: price.map(|price| (price - p.cost_basis) * p.quantity),
+            }
+        })
+        .collect();
+
+    let total_cost = positions.iter().map(|p| p.cost_basis * p.quantity).sum();
+    let total_value = positions
+        .iter()
+        .map(|p| p.market_value.unwrap_or(p.cost_basis * p.quantity))
+        .sum();
+    let total_unrealized_pnl = positions.iter().filter_map(|p| p.unrealized_pnl).sum();
+
+    PortfolioSummary {
+        positions,
+        total_cost,
+        total_value,
+        total_unrealized_pnl,
+    }
+}
+
+pub fn pnl_class(pnl: f64) -> &'static str {
+    if pnl >= 0.0 { "change up" } else { "change down" }
+}
+
+#[component]
+pub fn Portfolio(
+    positions: RwSignal<Vec<Position>>,
+    quotes: RwSignal<HashMap<String, Quote>>,
+    open_panel: RwSignal<Option<Panel>>,
+    on_discuss: Callback<PortfolioSummary>,
+) -> impl IntoView {
+    let (symbol_draft, set_symbol_draft) = signal(String::new());
+    let (quantity_draft, set_quantity_draft) = signal(String::new());
+    let (cost_draft, set_cost_draft) = signal(String::new());
+    let symbols = Memo::new(move |_| {
+        positions.with(|p| p.iter().map(|p| p.symbol.clone()).collect::<Vec<_>>())
+    });
+    let summary = Memo::new(move |_| positions.with(|p| quotes.with(|q| summarize(p, q))));
+
+    poll_quotes(symbols, quotes);
+
+    let add = move || {
+        if let Some(position) =
+            parse_position(&symbol_draft.get(), &quantity_draft.get(), &cost_draft.get())
+        {
+            positions.update(|p| merge_position(p, position));
+            set_symbol_draft.set(String::new());
+            set_quantity_draft.set(String::new());
+            set_cost_draft.set(String::new());
+        }
+    };
+
+    let import = move |ev: leptos::ev::Event| {
+        let input = event_target::<web_sys::HtmlInputElement>(&ev);
+        let Some(file) = input.files().and_then(|files| files.get(0)) else {
+            return;
+        };
+        input.set_value("");
+        spawn_local(async move {
+            let Ok(text) = JsFuture::from(file.text()).await else {
+                return;
+            };
+            let imported = parse_csv(&text.as_string().unwrap_or_default());
+            if imported.is_empty() {
+                toast::error("No positions found in that CSV");
+                return;
+            }
+            toast::success(format!("Imported {} positions", imported.len()));
+            positions.update(|p| {
+                for position in imported {
+                    merge_position(p, position);
+                }
+            });
+        });
+    };
+
+    let remove = move |symbol: String| {
+        spawn_local(async move {
+            if dialog::confirm(format!("Remove {symbol} from your portfolio?")).await {
+                positions.update(|p| p.retain(|position| position.symbol != symbol));
+            }
+        });
+    };
+
+    view! {
+        <button
+            class="icon-btn portfolio-toggle"
+            title="Portfolio"
+            on:click=move |_| toggle_panel(open_panel, Panel::Portfolio)
+        >
+            "💼"
+        </button>
+        <aside class="panel portfolio" class:open=move || open_panel.get() == Some(Panel::Portfolio)>
+            <div class="panel-header">
+                "Portfolio"
+                <label class="panel-option" title="CSV rows: symbol,quantity,cost basis">
+                    "Import CSV"
+                    <input type="file" accept=".csv,text/csv" class="file-input" on:change=import/>
+                </label>
+            </div>
+            <form
+                class="panel-form portfolio-form"
+                on:submit=move |ev| {
+                    ev.prevent_default();
+                    add();
+                }
+            >
+                <input
+                    type="text"
+                    placeholder="Symbol"
+                    prop:value=move || symbol_draft.get()
+                    on:input=move |ev| set_symbol_draft.set(event_target_value(&ev))
+                />
+                <input
+                    type="text"
+                    inputmode="decimal"
+                    placeholder="Qty"
+                    prop:value=move || quantity_draft.get()
+                    on:input=move |ev| set_quantity_draft.set(event_target_value(&ev))
+                />
+                <input
+                    type="text"
+                    inputmode="decimal"
+                    placeholder="Cost"
+                    prop:value=move || cost_draft.get()
+                    on:input=move |ev| set_cost_draft.set(event_target_value(&ev))
+                />
+                <button type="submit">"Add"</button>
+            </form>
+            <ul class="panel-items">
+                <For
+                    each=move || summary.with(|s| s.positions.clone())
+                    key=|p| (p.symbol.clone(), p.quantity.to_bits(), p.price.map(f64::to_bits))
+                    children=move |p| {
+                        let symbol = p.symbol.clone();
+                        view! {
+                            <li class="panel-item">
+                                <span class="position-symbol">{p.symbol}</span>
+                                <span class="position-quantity">
+                                    {format!("{} @ {:.2}", p.quantity, p.cost_basis)}
+                                </span>
+                                {match p.unrealized_pnl {
+                                    Some(pnl) => view! {
+                                        <span class=pnl_class(pnl)>{format!("{pnl:+.2}")}</span>
+                                    }
+                                    .into_any(),
+                                    None => view! { <span class="change">"—"</span> }.into_any(),
+                                }}
+                                <button
+                                    class="panel-remove"
+                                    title="Remove"
+                                    on:click=move |_| remove(symbol.clone())
+                                >
+                                    "×"
+                                </button>
+                            </li>
+                        }
+                    }
+                />
+            </ul>
+            {move || {
+                let s = summary.get();
+                (!s.positions.is_empty()).then(|| view! {
+                    <div class="portfolio-total">
+                        <span>{format!("Value {:.2}", s.total_value)}</span>
+                        <span class=pnl_class(s.total_unrealized_pnl)>
+                            {format!("P&L {:+.2}", s.total_unrealized_pnl)}
+                        </span>
+                    </div>
+                    <button class="portfolio-discuss" on:click=move |_| on_discuss.run(summary.get())>
+                        "Discuss my portfolio"
+                    </button>
+                })
+            }}
+        </aside>
+    }
+}
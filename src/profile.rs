@@ -0,0 +1,175 @@
+use std::time::Duration;
+
+use leptos::prelude::{
+    Effect, GetValue, RwSignal, SetValue, StoredValue, Update, UpdateValue, With, WithUntracked,
+    WithValue,
+};
+use leptos::task::spawn_local;
+use serde::{Deserialize, Serialize};
+
+use crate::remote_config::RemoteConfig;
+use crate::typing::TypingSpeed;
+use crate::{fetch, get_json, sleep, web, workspace, Indicator, Settings};
+
+/// Changes are sent once settings have been left alone this long, not per slider step
+const SAVE_DELAY: Duration = Duration::from_secs(2);
+
+/// The settings that follow the user to other devices. Consent (telemetry, error reports)
+/// and the history cap stay with the device they were chosen on.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    dark_mode: bool,
+    watchlist: Vec<String>,
+    indicators: Vec<Indicator>,
+    show_ticker: bool,
+    display_currency: Option<String>,
+    completion_sound: Option<f64>,
+    typing_speed: TypingSpeed,
+    disabled_tools: Vec<String>,
+    approve_tools: bool,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Preferences::of(&Settings::default())
+    }
+}
+
+impl Preferences {
+    fn of(settings: &Settings) -> Self {
+        Preferences {
+            dark_mode: settings.dark_mode,
+            watchlist: settings.watchlist.clone(),
+            indicators: settings.indicators.clone(),
+            show_ticker: settings.show_ticker,
+            display_currency: settings.display_currency.clone(),
+            completion_sound: settings.completion_sound,
+            typing_speed: settings.typing_speed,
+            disabled_tools: settings.disabled_tools.clone(),
+            approve_tools: settings.approve_tools,
+        }
+    }
+
+    fn apply(self, settings: &mut Settings) {
+        settings.dark_mode = self.dark_mode;
+        settings.watchlist = self.watchlist;
+        settings.indicators = self.indicators;
+        settings.show_ticker = self.show_ticker;
+        settings.display_currency = self.display_currency;
+        settings.completion_sound = self.completion_sound;
+        settings.typing_speed = self.typing_speed;
+        settings.disabled_tools = self.disabled_tools;
+        settings.approve_tools = self.approve_tools;
+    }
+}
+
+/// Body of `GET`/`PUT /profile`
+#[derive(Serialize, Deserialize)]
+struct Profile {
+    /// When the preferences were last changed, on whichever device
+    updated_at: f64,
+    preferences: Preferences,
+}
+
+/// The user's saved profile, or `None` if they haven't got one yet.
+async fn fetch_profile() -> Result<Option<Profile>, String> {
+    match get_json("/profile").await {
+        Ok(profile) => Ok(Some(profile)),
+        Err(e) if e.starts_with("HTTP 404") => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+async fn save_profile(profile: &Profile) -> Result<(), String> {
+    let opts = web::post_json(profile, &[])?;
+    opts.set_method("PUT");
+    fetch("/profile", &opts).await?;
+    Ok(())
+}
+
+/// Keep `settings` in step with the profile stored for the signed-in user, once `/config`
+/// says the backend has one (`profile_sync`). The side changed last wins: the profile if it
+/// was saved after the local preferences last changed, otherwise the local preferences,
+/// which are then saved over it. Other workspaces keep their settings to this device.
+pub fn use_profile_sync(settings: RwSignal<Settings>, config: RwSignal<RemoteConfig>) {
+    if !workspace::is_default() {
+        return;
+    }
+    // What the profile holds as far as we know; `None` until it's been fetched
+    let synced = StoredValue::new(None::<Preferences>);
+    let generation = StoredValue::new(0u64);
+
+    Effect::new(move |started: Option<bool>| {
+        if started == Some(true) || !config.with(|c| c.profile_sync) {
+            return started.unwrap_or_default();
+        }
+        spawn_local(async move {
+            let profile = match fetch_profile().await {
+                Ok(profile) => profile,
+                Err(e) => {
+                    log::warn!(target: "transport", "Profile fetch failed, keeping local settings: {e}");
+                    return;
+                }
+            };
+            let local_at = settings.with_untracked(|s| s.preferences_updated_at);
+            match profile {
+                Some(Profile { updated_at, preferences }) if updated_at > local_at => {
+                    log::info!(target: "storage", "Applying preferences from the profile");
+                    synced.set_value(Some(preferences.clone()));
+                    settings.update(|s| {
+                        preferences.apply(s);
+                        s.preferences_updated_at = updated_at;
+                    });
+                }
+                profile => {
+                    let preferences = settings.with_untracked(Preferences::of);
+                    let unchanged = profile.is_some_and(|p| p.preferences == preferences);
+                    synced.set_value(Some(preferences.clone()));
+                    if !unchanged {
+                        let profile = Profile { updated_at: local_at, preferences };
+                        if let Err(e) = save_profile(&profile).await {
+                            log::warn!(target: "transport", "Profile save failed: {e}");
+                        }
+                    }
+                }
+            }
+        });
+        true
+    });
+
+    // Local changes are timestamped, and saved to the profile once it's been fetched. The
+    // first run is what was loaded rather than a change, and neither is taking the profile's.
+    Effect::new(move |previous: Option<Preferences>| {
+        let preferences = settings.with(Preferences::of);
+        let changed = previous.is_some_and(|p| p != preferences)
+            && synced.with_value(|s| s.as_ref() != Some(&preferences));
+        if !changed {
+            return preferences;
+        }
+        let updated_at = stamp(settings);
+        if synced.with_value(Option::is_some) {
+            synced.set_value(Some(preferences.clone()));
+            generation.update_value(|g| *g += 1);
+            let current = generation.get_value();
+            let profile = Profile { updated_at, preferences: preferences.clone() };
+            spawn_local(async move {
+                sleep(SAVE_DELAY).await;
+                if generation.get_value() != current {
+                    return;
+                }
+                if let Err(e) = save_profile(&profile).await {
+                    log::warn!(target: "transport", "Profile save failed: {e}");
+                }
+            });
+        }
+        preferences
+    });
+}
+
+/// Mark the local preferences as changed now.
+fn stamp(settings: RwSignal<Settings>) -> f64 {
+    let now = js_sys::Date::now();
+    settings.update(|s| s.preferences_updated_at = now);
+    now
+}
@@ -0,0 +1,81 @@
+use leptos::prelude::{Get, GetUntracked, GetValue, RwSignal, Set, SetValue, StoredValue, With};
+use wasm_bindgen::JsCast;
+
+/// How far a list is pulled before letting go refreshes it
+const THRESHOLD: f64 = 64.0;
+/// The list follows the finger at this fraction of its movement, so a pull feels stretched
+const RESISTANCE: f64 = 0.5;
+/// Furthest the list moves however far the finger goes
+const MAX_PULL: f64 = 96.0;
+
+/// Pull-to-refresh for a scrolling list on touch screens: dragged down while scrolled to
+/// the top and let go past `THRESHOLD`, it asks for a refresh. Touch events rather than
+/// pointer events, as the browser cancels a pointer once it starts scrolling.
+#[derive(Clone, Copy)]
+pub struct PullToRefresh {
+    /// Where the touch went down, while it may still become a pull
+    start_y: StoredValue<Option<f64>>,
+    /// How far the list is pulled down, while it is
+    pull: RwSignal<Option<f64>>,
+}
+
+impl PullToRefresh {
+    pub fn new() -> Self {
+        PullToRefresh { start_y: StoredValue::new(None), pull: RwSignal::new(None) }
+    }
+
+    pub fn pulling(self) -> bool {
+        self.pull.with(Option::is_some)
+    }
+
+    /// Pulled far enough that letting go refreshes
+    pub fn ready(self) -> bool {
+        self.pull.get().is_some_and(|pull| pull >= THRESHOLD)
+    }
+
+    /// How far down the list is pulled, in px
+    pub fn offset(self) -> f64 {
+        self.pull.get().unwrap_or_default()
+    }
+
+    pub fn start(self, ev: &web_sys::TouchEvent) {
+        let at_top = ev
+            .current_target()
+            .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+            .is_some_and(|list| list.scroll_top() <= 0);
+        let touch = ev.touches().get(0).filter(|_| ev.touches().length() == 1);
+        self.start_y.set_value(touch.filter(|_| at_top).map(|t| f64::from(t.client_y())));
+    }
+
+    pub fn moved(self, ev: &web_sys::TouchEvent) {
+        let Some(start_y) = self.start_y.get_value() else {
+            return;
+        };
+        let Some(touch) = ev.touches().get(0) else {
+            return;
+        };
+        let dy = f64::from(touch.client_y()) - start_y;
+        if dy <= 0.0 && !self.pulling() {
+            // Scrolling the list, not pulling it
+            self.start_y.set_value(None);
+            return;
+        }
+        // The pull takes over from the browser's own overscroll
+        if ev.cancelable() {
+            ev.prevent_default();
+        }
+        self.pull.set(Some((dy * RESISTANCE).clamp(0.0, MAX_PULL)));
+    }
+
+    /// The touch lifted: returns whether to refresh.
+    pub fn end(self) -> bool {
+        let ready = self.pull.get_untracked().is_some_and(|pull| pull >= THRESHOLD);
+        self.cancel();
+        ready
+    }
+
+    pub fn cancel(self) {
+        self.start_y.set_value(None);
+        self.pull.set(None);
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::{HashMap, HashSet};
+
+use leptos::prelude::{
+    component, view, Get, GetUntracked, IntoView, RwSignal, Set, StoredValue,
+    Update, UpdateValue, With, WithUntracked, WithValue,
+};
+use leptos::tachys::prelude::*;
+use leptos::task::spawn_local;
+use wasm_bindgen::JsCast;
+
+use crate::currency::{FxRates, Price};
+use crate::symbol::{day_range, format_large};
+use crate::watchlist::format_change;
+use crate::{fetch_symbol_stats, window_listener, Settings, SymbolStats};
+
+#[derive(Clone, PartialEq)]
+struct Hover {
+    symbol: String,
+    left: f64,
+    top: f64,
+}
+
+/// Hover card for `.ticker-mention` spans in assistant text. Stats are fetched on
+/// first hover and cached for the session (`None` records a failed lookup).
+#[component]
+pub fn QuoteCard(settings: RwSignal<Settings>, rates: RwSignal<FxRates>) -> impl IntoView {
+    let cache = RwSignal::new(HashMap::<String, Option<SymbolStats>>::new());
+    let pending = StoredValue::new(HashSet::<String>::new());
+    let hover = RwSignal::new(None::<Hover>);
+
+    let load = move |symbol: String| {
+        let known = cache.with_untracked(|c| c.contains_key(&symbol));
+        if known || pending.with_value(|p| p.contains(&symbol)) {
+            return;
+        }
+        pending.update_value(|p| {
+            p.insert(symbol.clone());
+        });
+        spawn_local(async move {
+            let stats = match fetch_symbol_stats(&symbol).await {
+                Ok(stats) => Some(stats),
+                Err(e) => {
+                    log::error!(target: "transport", "Quote card fetch failed: {e}");
+                    None
+                }
+            };
+            pending.update_value(|p| {
+                p.remove(&symbol);
+            });
+            cache.update(|c| {
+                c.insert(symbol, stats);
+            });
+        });
+    };
+
+    window_listener(leptos::ev::mouseover, move |ev| {
+        let mention = ev
+            .target()
+            .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+            .and_then(|el| el.closest(".ticker-mention").ok().flatten());
+        let next = mention.and_then(|el| {
+            let symbol = el.get_attribute("data-symbol")?;
+            let rect = el.get_bounding_client_rect();
+            Some(Hover { symbol, left: rect.left(), top: rect.bottom() + 4.0 })
+        });
+        if next != hover.get_untracked() {
+            if let Some(next) = &next {
+                load(next.symbol.clone());
+            }
+            hover.set(next);
+        }
+    });
+
+    move || {
+        let Hover { symbol, left, top } = hover.get()?;
+        let body = match cache.with(|c| c.get(&symbol).cloned()) {
+            None => view! { <span class="spinner"></span> }.into_any(),
+            Some(None) => view! { <span class="panel-empty">"Quote unavailable"</span> }.into_any(),
+            Some(Some(stats)) => {
+                let (change, class) = format_change(stats.change_percent);
+                view! {
+                    <div class="quote-card-price">
+                        <Price
+                            amount=stats.price
+                            currency=stats.currency.clone()
+                            settings=settings
+                            rates=rates
+                        />
+                        <span class=class>{change}</span>
+                    </div>
+                    {day_range(&stats).map(|range| view! {
+                        <div class="quote-card-row">"Day range " {range}</div>
+                    })}
+                    {stats.volume.map(|volume| view! {
+                        <div class="quote-card-row">"Volume " {format_large(volume)}</div>
+                    })}
+                }
+                .into_any()
+            }
+        };
+        Some(view! {
+            <div class="quote-card" style=format!("left: {left}px; top: {top}px")>
+                <div class="quote-card-symbol">{symbol}</div>
+                {body}
+            </div>
+        })
+    }
+}
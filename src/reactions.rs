@@ -0,0 +1,65 @@
+use leptos::prelude::{component, view, IntoView, Memo, With};
+use leptos::tachys::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::state::use_app_state;
+
+/// A quick reaction to a response, saved with it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reaction {
+    ThumbsUp,
+    Fire,
+    Question,
+}
+
+impl Reaction {
+    pub const ALL: [Reaction; 3] = [Reaction::ThumbsUp, Reaction::Fire, Reaction::Question];
+
+    pub fn emoji(self) -> &'static str {
+        match self {
+            Reaction::ThumbsUp => "👍",
+            Reaction::Fire => "🔥",
+            Reaction::Question => "❓",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Reaction::ThumbsUp => "Helpful",
+            Reaction::Fire => "Great call",
+            Reaction::Question => "Unclear",
+        }
+    }
+}
+
+/// Reactions under a response: the ones given stay, the rest show on hover.
+#[component]
+pub fn Reactions(id: usize) -> impl IntoView {
+    let state = use_app_state();
+    let given = Memo::new(move |_| {
+        state
+            .messages
+            .with(|m| m.iter().find(|m| m.id == id).map(|m| m.reactions.clone()))
+            .unwrap_or_default()
+    });
+
+    view! {
+        <div class="reactions">
+            {Reaction::ALL.into_iter().map(|reaction| {
+                let active = move || given.with(|g| g.contains(&reaction));
+                view! {
+                    <button
+                        class="reaction"
+                        class:active=active
+                        title=reaction.label()
+                        aria-pressed=move || active().to_string()
+                        on:click=move |_| state.toggle_reaction(id, reaction)
+                    >
+                        {reaction.emoji()}
+                    </button>
+                }
+            }).collect::<Vec<_>>()}
+        </div>
+    }
+}
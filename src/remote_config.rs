@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+
+use leptos::prelude::{component, view, For, Get, IntoView, RwSignal, Set, Update, With};
+use leptos::tachys::prelude::*;
+use leptos::task::spawn_local;
+use serde::Deserialize;
+
+use crate::{compression, fetch_remote_config, load_json, save_json};
+
+const DISMISSED_KEY: &str = "wxve-chat:dismissed-announcements";
+
+/// Upcoming maintenance is announced this far ahead
+const MAINTENANCE_NOTICE_MS: f64 = 24.0 * 60.0 * 60.0 * 1000.0;
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct Announcement {
+    pub id: String,
+    pub message: String,
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct MaintenanceWindow {
+    /// RFC 3339 timestamps
+    pub starts_at: String,
+    pub ends_at: String,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// Fetched from `/config` at startup. Everything defaults to "nothing to show, all enabled"
+/// so the app works unchanged when the endpoint is unreachable.
+#[derive(Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct RemoteConfig {
+    pub announcements: Vec<Announcement>,
+    pub maintenance: Vec<MaintenanceWindow>,
+    /// Kill switches for client features, e.g. `charts`, `alerts`, `screener`
+    pub disabled: Vec<String>,
+    /// Per-user feature flags, see `flags.rs`
+    pub flags: HashMap<String, bool>,
+    /// `Content-Encoding`s `/chat` takes request bodies in, see `compression.rs`
+    pub request_encodings: Vec<String>,
+    /// The backend keeps each conversation's history by `ChatRequest.conversation_id`, so
+    /// turns can leave out what it already has (see `delta.rs`)
+    pub conversation_history: bool,
+    /// The backend keeps the signed-in user's preferences at `/profile`, see `profile.rs`
+    pub profile_sync: bool,
+}
+
+impl RemoteConfig {
+    pub fn enabled(&self, feature: &str) -> bool {
+        !self.disabled.iter().any(|f| f == feature)
+    }
+}
+
+pub fn use_remote_config() -> RwSignal<RemoteConfig> {
+    let config = RwSignal::new(RemoteConfig::default());
+    spawn_local(async move {
+        match fetch_remote_config().await {
+            Ok(fetched) => {
+                compression::negotiate(&fetched.request_encodings);
+                config.set(fetched);
+            }
+            Err(e) => log::error!(target: "transport", "Config fetch failed: {e}"),
+        }
+    });
+    config
+}
+
+fn parse_time(rfc3339: &str) -> f64 {
+    js_sys::Date::parse(rfc3339)
+}
+
+fn local_time(ms: f64) -> String {
+    js_sys::Date::new(&ms.into())
+        .to_locale_string("default", &wasm_bindgen::JsValue::UNDEFINED)
+        .into()
+}
+
+/// Maintenance windows that are underway or start within a day, as (in progress, text).
+fn maintenance_notices(windows: &[MaintenanceWindow]) -> Vec<(bool, String)> {
+    let now = js_sys::Date::now();
+    windows
+        .iter()
+        .filter_map(|window| {
+            let (starts, ends) = (parse_time(&window.starts_at), parse_time(&window.ends_at));
+            let relevant = now < ends && starts - now <= MAINTENANCE_NOTICE_MS;
+            if starts.is_nan() || ends.is_nan() || !relevant {
+                return None;
+            }
+            let active = now >= starts;
+            let lead = if active {
+                format!("Maintenance in progress until {}.", local_time(ends))
+            } else {
+                let (starts, ends) = (local_time(starts), local_time(ends));
+                format!("Scheduled maintenance from {starts} to {ends}.")
+            };
+            let text = match &window.message {
+                Some(message) => format!("{lead} {message}"),
+                None => lead,
+            };
+            Some((active, text))
+        })
+        .collect()
+}
+
+/// Announcement and maintenance banners from the remote config.
+#[component]
+pub fn ConfigBanners(config: RwSignal<RemoteConfig>) -> impl IntoView {
+    let dismissed = RwSignal::new(load_json::<Vec<String>>(DISMISSED_KEY).unwrap_or_default());
+
+    let dismiss = move |id: String| {
+        dismissed.update(|d| d.push(id));
+        dismissed.with(|d| save_json(DISMISSED_KEY, d));
+    };
+    let announcements = move || {
+        let dismissed = dismissed.get();
+        config.with(|c| {
+            c.announcements
+                .iter()
+                .filter(|a| !dismissed.contains(&a.id))
+                .cloned()
+                .collect::<Vec<_>>()
+        })
+    };
+
+    view! {
+        {move || {
+            config
+                .with(|c| maintenance_notices(&c.maintenance))
+                .into_iter()
+                .map(|(active, text)| {
+                    let class = if active { "banner error" } else { "banner warning" };
+                    view! {
+                        <div class=class role="status">
+                            <span class="banner-text">{text}</span>
+                        </div>
+                    }
+                })
+                .collect::<Vec<_>>()
+        }}
+        <For
+            each=announcements
+            key=|announcement| announcement.id.clone()
+            children=move |announcement| {
+                let id = announcement.id.clone();
+                view! {
+                    <div class="banner info" role="status">
+                        <span class="banner-text">{announcement.message}</span>
+                        {announcement.url.map(|url| view! {
+                            <a href=url target="_blank" rel="noopener">"Learn more"</a>
+                        })}
+                        <button
+                            class="banner-dismiss"
+                            title="Dismiss"
+                            on:click=move |_| dismiss(id.clone())
+                        >
+                            "×"
+                        </button>
+                    </div>
+                }
+            }
+        />
+    }
+}
@@ -0,0 +1,248 @@
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use leptos::prelude::{
+    component, event_target, event_target_value, signal, view, Effect, Get, GetUntracked,
+    IntoView, RwSignal, Set, Update, With, WithUntracked,
+};
+use leptos::tachys::prelude::*;
+use leptos::task::spawn_local;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::Response;
+
+use crate::account::download;
+use crate::state::use_app_state;
+use crate::{mock, toast};
+
+/// Replay speed multipliers offered in the debug panel
+const SPEEDS: &[f64] = &[1.0, 2.0, 5.0, 20.0];
+
+#[derive(Clone, Serialize, Deserialize)]
+struct RecordedChunk {
+    /// Milliseconds after the request was sent
+    at: f64,
+    chunk: Value,
+}
+
+/// One chat request and the chunks it got back.
+#[derive(Clone, Serialize, Deserialize)]
+struct Turn {
+    #[serde(skip)]
+    request_id: String,
+    #[serde(skip)]
+    started_at: f64,
+    request: Value,
+    chunks: Vec<RecordedChunk>,
+}
+
+/// What a recording is saved as and loaded from.
+#[derive(Serialize, Deserialize)]
+struct Fixture {
+    app: String,
+    recorded_at: String,
+    turns: Vec<Turn>,
+}
+
+#[derive(Clone)]
+struct Replay {
+    turns: VecDeque<Turn>,
+    speed: f64,
+}
+
+#[derive(Clone, Copy)]
+struct State {
+    /// Turns recorded so far, while recording
+    recording: RwSignal<Option<Vec<Turn>>>,
+    /// Turns still to be played back, while replaying
+    replay: RwSignal<Option<Replay>>,
+}
+
+thread_local! {
+    /// Registered by `use_replay`
+    static STATE: Cell<Option<State>> = const { Cell::new(None) };
+}
+
+/// Record a chat request as it's sent, if recording.
+pub fn begin(request_id: &str, request: &impl Serialize) {
+    let Some(state) = STATE.with(Cell::get) else {
+        return;
+    };
+    if state.recording.with_untracked(Option::is_none) {
+        return;
+    }
+    let turn = Turn {
+        request_id: request_id.to_string(),
+        started_at: js_sys::Date::now(),
+        request: serde_json::to_value(request).unwrap_or_default(),
+        chunks: Vec::new(),
+    };
+    state.recording.update(|r| {
+        if let Some(turns) = r {
+            turns.push(turn);
+        }
+    });
+}
+
+/// Record a chunk received for `request_id`, if recording.
+pub fn chunk(request_id: &str, chunk: &impl Serialize) {
+    let Some(state) = STATE.with(Cell::get) else {
+        return;
+    };
+    state.recording.update(|r| {
+        if let Some(turn) = r
+            .as_mut()
+            .and_then(|turns| turns.iter_mut().rev().find(|t| t.request_id == request_id))
+        {
+            turn.chunks.push(RecordedChunk {
+                at: js_sys::Date::now() - turn.started_at,
+                chunk: serde_json::to_value(chunk).unwrap_or_default(),
+            });
+        }
+    });
+}
+
+/// While replaying, answer `/chat` with the next recorded turn instead of the API,
+/// keeping the original gaps between chunks divided by the replay speed.
+pub fn fetch(path: &str) -> Option<Result<Response, String>> {
+    if path != "/chat" {
+        return None;
+    }
+    let state = STATE.with(Cell::get)?;
+    let mut next = None;
+    state.replay.update(|r| {
+        if let Some(replay) = r {
+            next = replay.turns.pop_front().map(|turn| (turn, replay.speed));
+            if replay.turns.is_empty() {
+                *r = None;
+            }
+        }
+    });
+    let (turn, speed) = next?;
+    let mut previous = 0.0;
+    let events = turn.chunks.into_iter().map(move |c| {
+        let delay = ((c.at - previous) / speed).max(0.0);
+        previous = c.at;
+        (Duration::from_millis(delay as u64), c.chunk.to_string())
+    });
+    Some(mock::sse(events, None))
+}
+
+/// Set up recording and replay. While a replay is loaded, each recorded message is
+/// sent as soon as the previous response has finished.
+pub fn use_replay() {
+    let app = use_app_state();
+    let state = State { recording: RwSignal::new(None), replay: RwSignal::new(None) };
+    STATE.with(|s| s.set(Some(state)));
+
+    Effect::new(move |_| {
+        if app.loading.get() {
+            return;
+        }
+        let message = state.replay.with(|r| {
+            r.as_ref()
+                .and_then(|r| r.turns.front())
+                .and_then(|turn| turn.request["message"].as_str().map(String::from))
+        });
+        if let Some(message) = message {
+            app.send(message, None);
+        }
+    });
+}
+
+/// Record and replay controls for the debug panel.
+#[component]
+pub fn ReplayControls() -> impl IntoView {
+    let Some(state) = STATE.with(Cell::get) else {
+        return ().into_any();
+    };
+    let (speed, set_speed) = signal(1.0);
+
+    let stop_recording = move || {
+        let Some(turns) = state.recording.get_untracked() else {
+            return;
+        };
+        state.recording.set(None);
+        if turns.is_empty() {
+            toast::info("Nothing was recorded");
+            return;
+        }
+        let recorded_at = String::from(js_sys::Date::new_0().to_iso_string());
+        let filename = format!("wxve-chat-recording-{}.json", &recorded_at[..19].replace(':', "-"));
+        let fixture = Fixture { app: "wxve-chat".to_string(), recorded_at, turns };
+        let saved = serde_json::to_string_pretty(&fixture)
+            .map_err(|e| e.to_string())
+            .and_then(|json| download(&filename, &json, "application/json"));
+        if let Err(e) = saved {
+            toast::error(format!("Couldn't save the recording: {e}"));
+        }
+    };
+
+    let load = move |ev: leptos::ev::Event| {
+        let input = event_target::<web_sys::HtmlInputElement>(&ev);
+        let Some(file) = input.files().and_then(|files| files.get(0)) else {
+            return;
+        };
+        input.set_value("");
+        spawn_local(async move {
+            let Ok(text) = JsFuture::from(file.text()).await else {
+                return;
+            };
+            match serde_json::from_str::<Fixture>(&text.as_string().unwrap_or_default()) {
+                Ok(fixture) if !fixture.turns.is_empty() => {
+                    toast::info(format!("Replaying {} turn(s)", fixture.turns.len()));
+                    state.replay.set(Some(Replay {
+                        turns: fixture.turns.into(),
+                        speed: speed.get_untracked(),
+                    }));
+                }
+                Ok(_) => toast::error("That recording has no turns"),
+                Err(e) => toast::error(format!("Not a recording: {e}")),
+            }
+        });
+    };
+
+    view! {
+        {move || match state.recording.with(|r| r.as_ref().map(Vec::len)) {
+            Some(turns) => view! {
+                <button class="dialog-button danger" on:click=move |_| stop_recording()>
+                    {format!("■ Stop and save ({turns})")}
+                </button>
+            }.into_any(),
+            None => view! {
+                <button class="dialog-button" on:click=move |_| state.recording.set(Some(Vec::new()))>
+                    "● Record"
+                </button>
+            }.into_any(),
+        }}
+        {move || match state.replay.with(|r| r.as_ref().map(|r| r.turns.len())) {
+            Some(left) => view! {
+                <button class="dialog-button" on:click=move |_| state.replay.set(None)>
+                    {format!("Stop replay ({left} left)")}
+                </button>
+            }.into_any(),
+            None => view! {
+                <select
+                    class="debug-speed"
+                    title="Replay speed"
+                    on:change=move |ev| {
+                        set_speed.set(event_target_value(&ev).parse().unwrap_or(1.0));
+                    }
+                >
+                    {SPEEDS.iter().map(|&s| view! {
+                        <option value=s.to_string() selected=move || speed.get() == s>
+                            {format!("{s}×")}
+                        </option>
+                    }).collect::<Vec<_>>()}
+                </select>
+                <label class="dialog-button">
+                    "Replay…"
+                    <input type="file" accept=".json,application/json" class="file-input" on:change=load/>
+                </label>
+            }.into_any(),
+        }}
+    }
+    .into_any()
+}
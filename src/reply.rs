@@ -0,0 +1,96 @@
+use leptos::prelude::{
+    component, signal, view, Callable, Callback, Get, GetUntracked, IntoView, Set,
+};
+use leptos::tachys::prelude::*;
+use wasm_bindgen::JsCast;
+
+use crate::window_listener;
+
+/// Longest excerpt carried into a reply
+const MAX_QUOTE_CHARS: usize = 600;
+
+#[derive(Clone, PartialEq)]
+struct Selection {
+    text: String,
+    left: f64,
+    top: f64,
+}
+
+/// Text selected inside a single assistant message, with where to float the button.
+fn assistant_selection() -> Option<Selection> {
+    let selection = web_sys::window()?.get_selection().ok()??;
+    if selection.is_collapsed() || selection.range_count() == 0 {
+        return None;
+    }
+    let text = String::from(selection.to_string()).trim().to_string();
+    if text.is_empty() {
+        return None;
+    }
+    let message_of = |node: Option<web_sys::Node>| {
+        let node = node?;
+        let element = match node.dyn_ref::<web_sys::Element>() {
+            Some(element) => element.clone(),
+            None => node.parent_element()?,
+        };
+        element.closest(".message:not(.user)").ok()?
+    };
+    let anchor = message_of(selection.anchor_node())?;
+    let focus = message_of(selection.focus_node())?;
+    if anchor != focus {
+        return None;
+    }
+    let rect = selection.get_range_at(0).ok()?.get_bounding_client_rect();
+    Some(Selection { text: excerpt(&text), left: rect.left() + rect.width() / 2.0, top: rect.top() })
+}
+
+/// `text` cut to the longest excerpt carried into a reply.
+pub fn excerpt(text: &str) -> String {
+    let text = text.trim();
+    match text.char_indices().nth(MAX_QUOTE_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// Floating "Reply with quote" button over a selection in an assistant message.
+#[component]
+pub fn QuoteSelection(on_quote: Callback<String>) -> impl IntoView {
+    let (selection, set_selection) = signal(None::<Selection>);
+
+    window_listener(leptos::ev::mouseup, move |ev| {
+        // Clicking the button itself mustn't clear the selection first
+        let on_button = ev
+            .target()
+            .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+            .is_some_and(|el| el.closest(".quote-reply-button").ok().flatten().is_some());
+        if !on_button {
+            set_selection.set(assistant_selection());
+        }
+    });
+    window_listener(leptos::ev::scroll, move |_| {
+        if selection.get_untracked().is_some() {
+            set_selection.set(None);
+        }
+    });
+
+    move || {
+        selection.get().map(|Selection { text, left, top }| {
+            view! {
+                <button
+                    class="quote-reply-button"
+                    style=format!("left: {left}px; top: {top}px")
+                    on:mousedown=|ev| ev.prevent_default()
+                    on:click=move |_| {
+                        on_quote.run(text.clone());
+                        set_selection.set(None);
+                        if let Some(selection) = web_sys::window().and_then(|w| w.get_selection().ok().flatten()) {
+                            let _ = selection.remove_all_ranges();
+                        }
+                    }
+                >
+                    "❝ Reply with quote"
+                </button>
+            }
+        })
+    }
+}
@@ -0,0 +1,143 @@
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+
+use leptos::prelude::{Effect, RwSignal, With};
+use serde::Serialize;
+
+use crate::{api_base, mock, Settings};
+
+/// Only the most recent breadcrumbs are kept and sent with a report
+const MAX_BREADCRUMBS: usize = 30;
+
+/// Reports are cut to this length; anything longer is almost certainly not needed
+const MAX_MESSAGE: usize = 1_000;
+
+/// Paths whose failures are never reported, so reporting can't feed on itself
+const UNREPORTED_PATHS: &[&str] = &["/errors", "/telemetry"];
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    Panic,
+    Request,
+    /// Sent by the user from an error in the conversation
+    UserReport,
+}
+
+/// Something that happened shortly before an error. Only fixed labels and counts,
+/// never message text or symbols.
+#[derive(Clone, Serialize)]
+pub struct Breadcrumb {
+    /// Milliseconds since the epoch
+    at: f64,
+    category: &'static str,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct Report<'a> {
+    kind: ErrorKind,
+    message: &'a str,
+    version: &'static str,
+    /// Page path without the query string
+    path: String,
+    breadcrumbs: Vec<Breadcrumb>,
+}
+
+thread_local! {
+    /// Mirrors `Settings::error_reports`; nothing is sent while it's off
+    static ENABLED: Cell<bool> = const { Cell::new(false) };
+    static BREADCRUMBS: RefCell<VecDeque<Breadcrumb>> = const { RefCell::new(VecDeque::new()) };
+}
+
+/// Remember `message` for the next report. Kept whether or not reporting is on,
+/// but never leaves the browser unless a report is sent.
+pub fn breadcrumb(category: &'static str, message: impl Into<String>) {
+    BREADCRUMBS.with(|b| {
+        let mut b = b.borrow_mut();
+        if b.len() == MAX_BREADCRUMBS {
+            b.pop_front();
+        }
+        b.push_back(Breadcrumb { at: js_sys::Date::now(), category, message: message.into() });
+    });
+}
+
+/// `path` without its query string and with ids and tickers replaced,
+/// e.g. `/symbols/AAPL?range=1y` becomes `/symbols/:id`.
+pub fn sanitize_path(path: &str) -> String {
+    let path = path.split('?').next().unwrap_or_default();
+    path.split('/')
+        .map(|segment| {
+            if segment.chars().all(|c| c.is_ascii_lowercase() || c == '-') {
+                segment
+            } else {
+                ":id"
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// A request to `path` failed. Aborted requests are expected and ignored.
+pub fn request_failed(path: &str, error: &str) {
+    let path = sanitize_path(path);
+    if UNREPORTED_PATHS.contains(&path.as_str()) || error.contains("AbortError") {
+        return;
+    }
+    breadcrumb("request", format!("{path} failed"));
+    report(ErrorKind::Request, &format!("{path}: {error}"));
+}
+
+/// Report `message` because the user asked to, whatever the consent setting says.
+/// Returns false if it couldn't be sent.
+pub fn user_report(message: &str) -> bool {
+    breadcrumb("ui", "error reported");
+    send(ErrorKind::UserReport, message)
+}
+
+/// Send a report if the user consented.
+fn report(kind: ErrorKind, message: &str) {
+    if ENABLED.with(Cell::get) {
+        send(kind, message);
+    }
+}
+
+/// Uses `sendBeacon` so it still goes out from a panic hook, when the app can no longer
+/// run async code.
+fn send(kind: ErrorKind, message: &str) -> bool {
+    if mock::enabled() {
+        return false;
+    }
+    let message = match message.char_indices().nth(MAX_MESSAGE) {
+        Some((end, _)) => &message[..end],
+        None => message,
+    };
+    let Some(window) = web_sys::window() else {
+        return false;
+    };
+    let report = Report {
+        kind,
+        message,
+        version: env!("CARGO_PKG_VERSION"),
+        path: sanitize_path(&window.location().pathname().unwrap_or_default()),
+        breadcrumbs: BREADCRUMBS.with(|b| b.borrow().iter().cloned().collect()),
+    };
+    let Ok(body) = serde_json::to_string(&report) else {
+        return false;
+    };
+    window
+        .navigator()
+        .send_beacon_with_opt_str(&format!("{}/errors", api_base()), Some(&body))
+        .unwrap_or(false)
+}
+
+/// Follow the consent setting and report panics.
+pub fn use_error_reporting(settings: RwSignal<Settings>) {
+    Effect::new(move |_| {
+        ENABLED.with(|e| e.set(settings.with(|s| s.error_reports)));
+    });
+    std::panic::set_hook(Box::new(|info| {
+        log::error!(target: "ui", "{info}");
+        report(ErrorKind::Panic, &info.to_string());
+    }));
+}
@@ -0,0 +1,216 @@
+use leptos::prelude::{
+    component, event_target_checked, view, AnyView, Callable, Callback, Get, IntoView, Memo,
+    RwSignal, Update, With,
+};
+use leptos::tachys::prelude::*;
+use serde_json::Value;
+use wasm_bindgen::JsValue;
+use web_sys::AbortSignal;
+
+use crate::dialog::{self, Dialog};
+use crate::{ChatRequest, Role};
+
+/// History entries are listed by this much of their text
+const EXCERPT_CHARS: usize = 80;
+
+/// What the user left out of a request
+#[derive(Clone, Default, PartialEq)]
+struct Trim {
+    /// Positions in `history`
+    history: Vec<usize>,
+    portfolio: bool,
+    quoted: bool,
+    indicators: bool,
+}
+
+impl Trim {
+    fn apply(&self, request: &ChatRequest) -> ChatRequest {
+        let history = request.history.as_ref().map(|history| {
+            history
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !self.history.contains(i))
+                .map(|(_, message)| message.clone())
+                .collect()
+        });
+        ChatRequest {
+            history,
+            portfolio: request.portfolio.clone().filter(|_| !self.portfolio),
+            quoted: request.quoted.clone().filter(|_| !self.quoted),
+            indicators: if self.indicators { Vec::new() } else { request.indicators.clone() },
+            ..request.clone()
+        }
+    }
+}
+
+fn excerpt(text: &str) -> String {
+    match text.char_indices().nth(EXCERPT_CHARS) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// A checkbox to keep or leave out one part of the request.
+#[component]
+fn TrimRow(
+    trim: RwSignal<Trim>,
+    label: String,
+    is_dropped: fn(&Trim) -> bool,
+    set: fn(&mut Trim, bool),
+) -> impl IntoView {
+    view! {
+        <label class="settings-row">
+            <input
+                type="checkbox"
+                prop:checked=move || !trim.with(is_dropped)
+                on:change=move |ev| {
+                    let keep = event_target_checked(&ev);
+                    trim.update(|t| set(t, !keep));
+                }
+            />
+            {label}
+        </label>
+    }
+}
+
+struct Preview {
+    request: ChatRequest,
+    /// Resolves the promise `review()` awaits with the trimmed JSON, or null if cancelled
+    resolve: js_sys::Function,
+}
+
+impl Dialog for Preview {
+    fn title(&self) -> String {
+        "Review request".to_string()
+    }
+
+    fn body(&self, close: Callback<()>) -> AnyView {
+        let request = self.request.clone();
+        let trim = RwSignal::new(Trim::default());
+        let json = {
+            let request = request.clone();
+            Memo::new(move |_| {
+                trim.with(|t| serde_json::to_string_pretty(&t.apply(&request)).unwrap_or_default())
+            })
+        };
+        let resolve = self.resolve.clone();
+        let send = move |_| {
+            let _ = resolve.call1(&JsValue::NULL, &JsValue::from_str(&json.get()));
+            close.run(());
+        };
+
+        let history = match &request.history {
+            None => view! {
+                <p class="settings-hint">"Earlier messages: the server already has them."</p>
+            }
+            .into_any(),
+            Some(history) if history.is_empty() => ().into_any(),
+            Some(history) => {
+                let rows = history
+                    .iter()
+                    .enumerate()
+                    .map(|(i, message)| {
+                        let who = match message.role {
+                            Role::User => "You",
+                            Role::Assistant => "Xve",
+                        };
+                        view! {
+                            <label class="settings-row">
+                                <input
+                                    type="checkbox"
+                                    prop:checked=move || !trim.with(|t| t.history.contains(&i))
+                                    on:change=move |ev| {
+                                        let keep = event_target_checked(&ev);
+                                        trim.update(|t| {
+                                            t.history.retain(|&h| h != i);
+                                            if !keep {
+                                                t.history.push(i);
+                                            }
+                                        });
+                                    }
+                                />
+                                <span class="request-preview-message">
+                                    <strong>{who}</strong>
+                                    {format!(" {}", excerpt(&message.content))}
+                                </span>
+                            </label>
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                view! {
+                    <h3>{format!("Earlier messages ({})", history.len())}</h3>
+                    <div class="request-preview-history">{rows}</div>
+                }
+                .into_any()
+            }
+        };
+        let portfolio = request.portfolio.as_ref().map(|p| view! {
+            <TrimRow
+                trim=trim
+                label=format!("Portfolio ({} positions)", p.positions.len())
+                is_dropped=|t| t.portfolio
+                set=|t, drop| t.portfolio = drop
+            />
+        });
+        let quoted = request.quoted.as_ref().map(|q| view! {
+            <TrimRow
+                trim=trim
+                label=format!("Replying to: {}", excerpt(q))
+                is_dropped=|t| t.quoted
+                set=|t, drop| t.quoted = drop
+            />
+        });
+        let indicators = (!request.indicators.is_empty()).then(|| {
+            let names: Vec<_> = request.indicators.iter().map(|i| i.label()).collect();
+            view! {
+                <TrimRow
+                    trim=trim
+                    label=format!("Chart indicators: {}", names.join(", "))
+                    is_dropped=|t| t.indicators
+                    set=|t, drop| t.indicators = drop
+                />
+            }
+        });
+
+        view! {
+            <p class="settings-hint">"This is what will be sent. Untick anything to leave it out."</p>
+            <div class="request-preview">
+                {history}
+                {(portfolio.is_some() || quoted.is_some() || indicators.is_some()).then(|| view! {
+                    <h3>"Also sent"</h3>
+                })}
+                {portfolio}
+                {quoted}
+                {indicators}
+                <details>
+                    <summary>"Request body"</summary>
+                    <pre class="inspector-json">{move || json.get()}</pre>
+                </details>
+            </div>
+            <div class="dialog-actions">
+                <button class="dialog-button" on:click=move |_| close.run(())>"Cancel"</button>
+                <button class="dialog-button primary" autofocus=true on:click=send>"Send"</button>
+            </div>
+        }
+        .into_any()
+    }
+
+    fn class(&self) -> &'static str {
+        "inspector"
+    }
+
+    fn on_close(&self) {
+        let _ = self.resolve.call1(&JsValue::NULL, &JsValue::NULL);
+    }
+}
+
+/// `request` as the user trimmed it in the preview, or `None` if they cancelled or
+/// `signal` aborted (Stop).
+pub async fn review(request: &ChatRequest, signal: &AbortSignal) -> Option<Value> {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        let id = dialog::open(Preview { request: request.clone(), resolve });
+        dialog::close_on_abort(id, signal);
+    });
+    let trimmed = wasm_bindgen_futures::JsFuture::from(promise).await.ok()?.as_string()?;
+    serde_json::from_str(&trimmed).ok()
+}
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+use crate::conversations::Conversation;
+use crate::{load_json, local_storage, save_json, workspace, Chart, Message, Role};
+
+const IN_FLIGHT_KEY: &str = "wxve-chat:in-flight";
+
+/// Least time between saves of the response being streamed
+pub const SAVE_EVERY_MS: f64 = 1000.0;
+
+/// The response being streamed as of its last save, so a reload midway doesn't lose it
+#[derive(Serialize, Deserialize)]
+pub struct InFlight {
+    pub conversation_id: String,
+    /// Messages the conversation had when the response started; it goes after them
+    pub after: usize,
+    pub content: String,
+    #[serde(default)]
+    pub charts: Vec<Chart>,
+}
+
+impl InFlight {
+    /// Add the response to its saved conversation, marked interrupted, and return the
+    /// conversation's id. Nothing happens if the conversation has changed since: it's gone,
+    /// or doesn't end with the message the response was answering.
+    pub fn restore_into(self, conversations: &mut [Conversation]) -> Option<String> {
+        let conversation = conversations.iter_mut().find(|c| c.id == self.conversation_id)?;
+        let answering = conversation.messages.last().map(|m| m.role);
+        if conversation.messages.len() != self.after || answering != Some(Role::User) {
+            return None;
+        }
+        conversation.messages.push(Message {
+            id: 0,
+            role: Role::Assistant,
+            content: self.content,
+            quoted: None,
+            charts: self.charts,
+            sentiment: None,
+            meta: None,
+            pinned: false,
+            reactions: Vec::new(),
+            delivery: None,
+            error: None,
+            interrupted: true,
+        });
+        Some(self.conversation_id)
+    }
+}
+
+pub fn save(in_flight: &InFlight) {
+    save_json(IN_FLIGHT_KEY, in_flight);
+}
+
+/// The response is over, or was never worth keeping.
+pub fn clear() {
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(&workspace::scoped(IN_FLIGHT_KEY));
+    }
+}
+
+/// The response a reload interrupted, if any; it's only offered back once.
+pub fn take() -> Option<InFlight> {
+    let in_flight = load_json(IN_FLIGHT_KEY);
+    clear();
+    in_flight
+}
@@ -0,0 +1,79 @@
+use leptos::prelude::{GetUntracked, RwSignal, Set};
+
+use crate::watchlist::normalize_symbol;
+use crate::window_listener;
+
+/// Client-side routes. CloudFront serves `index.html` for unknown paths, so any
+/// of these can be loaded directly.
+#[derive(Clone, PartialEq)]
+pub enum Route {
+    Chat,
+    Dashboard,
+    Heatmap,
+    Screener,
+    Symbol(String),
+}
+
+impl Route {
+    fn from_path(path: &str) -> Self {
+        let path = path.trim_end_matches('/');
+        if let Some(symbol) = path.strip_prefix("/symbol/").and_then(normalize_symbol) {
+            return Route::Symbol(symbol);
+        }
+        match path {
+            "/dashboard" => Route::Dashboard,
+            "/heatmap" => Route::Heatmap,
+            "/screener" => Route::Screener,
+            _ => Route::Chat,
+        }
+    }
+
+    /// Remote-config kill switch that turns this page off, if any.
+    pub fn feature(&self) -> Option<&'static str> {
+        match self {
+            Route::Heatmap => Some("heatmap"),
+            Route::Screener => Some("screener"),
+            Route::Chat | Route::Dashboard | Route::Symbol(_) => None,
+        }
+    }
+
+    fn path(&self) -> String {
+        match self {
+            Route::Chat => "/".to_string(),
+            Route::Dashboard => "/dashboard".to_string(),
+            Route::Heatmap => "/heatmap".to_string(),
+            Route::Screener => "/screener".to_string(),
+            Route::Symbol(symbol) => format!("/symbol/{symbol}"),
+        }
+    }
+}
+
+fn current_path() -> String {
+    web_sys::window()
+        .and_then(|w| w.location().pathname().ok())
+        .unwrap_or_default()
+}
+
+/// Track the current route, following browser back/forward navigation.
+pub fn use_route() -> RwSignal<Route> {
+    let route = RwSignal::new(Route::from_path(&current_path()));
+    window_listener(leptos::ev::popstate, move |_| {
+        route.set(Route::from_path(&current_path()));
+    });
+    route
+}
+
+/// Push `to` onto the history stack and switch to it.
+pub fn navigate(route: RwSignal<Route>, to: Route) {
+    crate::reporting::breadcrumb("navigation", crate::reporting::sanitize_path(&to.path()));
+    if let Some(history) = web_sys::window().and_then(|w| w.history().ok()) {
+        let _ = history.push_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&to.path()));
+    }
+    route.set(to);
+}
+
+/// Open `page`, or go back to the chat if it's already open.
+pub fn toggle_route(route: RwSignal<Route>, page: Route) {
+    let to = if route.get_untracked() == page { Route::Chat } else { page };
+    navigate(route, to);
+}
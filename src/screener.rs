@@ -0,0 +1,288 @@
+use std::cmp::Ordering;
+
+use leptos::prelude::{
+    component, event_target_value, signal, view, Callable, Callback, Get, IntoView, RwSignal, Set,
+    Update, With,
+};
+use leptos::tachys::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::symbol::format_large;
+use crate::watchlist::format_change;
+use crate::{run_screener, ScreenerRequest, ScreenerResult};
+
+const SECTORS: &[&str] = &[
+    "Communication Services",
+    "Consumer Discretionary",
+    "Consumer Staples",
+    "Energy",
+    "Financials",
+    "Healthcare",
+    "Industrials",
+    "Materials",
+    "Real Estate",
+    "Technology",
+    "Utilities",
+];
+
+/// (value sent to the screener tool, label)
+const WAVE_PATTERNS: &[(&str, &str)] = &[
+    ("impulse_wave_3", "In impulse wave 3"),
+    ("impulse_wave_5", "In impulse wave 5"),
+    ("corrective_abc", "ABC correction"),
+    ("triangle", "Triangle"),
+    ("ending_diagonal", "Ending diagonal"),
+];
+
+/// (label, min, max) market cap buckets
+const MARKET_CAPS: &[(&str, Option<f64>, Option<f64>)] = &[
+    ("Mega (>200B)", Some(200e9), None),
+    ("Large (10-200B)", Some(10e9), Some(200e9)),
+    ("Mid (2-10B)", Some(2e9), Some(10e9)),
+    ("Small (<2B)", None, Some(2e9)),
+];
+
+#[derive(Clone, Copy, PartialEq)]
+enum Column {
+    Symbol,
+    Sector,
+    MarketCap,
+    Price,
+    Change,
+    Rsi,
+}
+
+impl Column {
+    const ALL: [Column; 6] = [
+        Column::Symbol,
+        Column::Sector,
+        Column::MarketCap,
+        Column::Price,
+        Column::Change,
+        Column::Rsi,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Column::Symbol => "Symbol",
+            Column::Sector => "Sector",
+            Column::MarketCap => "Market cap",
+            Column::Price => "Price",
+            Column::Change => "Change",
+            Column::Rsi => "RSI",
+        }
+    }
+
+    fn compare(self, a: &ScreenerResult, b: &ScreenerResult) -> Ordering {
+        match self {
+            Column::Symbol => a.symbol.cmp(&b.symbol),
+            Column::Sector => a.sector.cmp(&b.sector),
+            Column::MarketCap => a.market_cap.total_cmp(&b.market_cap),
+            Column::Price => a.price.total_cmp(&b.price),
+            Column::Change => a.change_percent.total_cmp(&b.change_percent),
+            Column::Rsi => a.rsi.total_cmp(&b.rsi),
+        }
+    }
+}
+
+fn wave_label(pattern: &str) -> &str {
+    WAVE_PATTERNS
+        .iter()
+        .find(|(value, _)| *value == pattern)
+        .map_or(pattern, |(_, label)| label)
+}
+
+fn parse_optional(raw: &str) -> Option<f64> {
+    raw.trim().parse().ok()
+}
+
+fn non_empty(raw: String) -> Option<String> {
+    (!raw.is_empty()).then_some(raw)
+}
+
+/// `/screener` - structured filters for the backend screener tool, results as a sortable table.
+#[component]
+pub fn Screener(on_analyze: Callback<String>) -> impl IntoView {
+    let (market_cap, set_market_cap) = signal(String::new());
+    let (sector, set_sector) = signal(String::new());
+    let (rsi_min, set_rsi_min) = signal(String::new());
+    let (rsi_max, set_rsi_max) = signal(String::new());
+    let (wave_pattern, set_wave_pattern) = signal(String::new());
+    let results = RwSignal::new(None::<Vec<ScreenerResult>>);
+    let (sort, set_sort) = signal((Column::MarketCap, false));
+    let (loading, set_loading) = signal(false);
+    let (error, set_error) = signal(None::<String>);
+
+    let run = move || {
+        let (min_market_cap, max_market_cap) = MARKET_CAPS
+            .iter()
+            .find(|(label, _, _)| *label == market_cap.get())
+            .map_or((None, None), |(_, min, max)| (*min, *max));
+        let request = ScreenerRequest {
+            min_market_cap,
+            max_market_cap,
+            sector: non_empty(sector.get()),
+            rsi_min: parse_optional(&rsi_min.get()),
+            rsi_max: parse_optional(&rsi_max.get()),
+            wave_pattern: non_empty(wave_pattern.get()),
+        };
+        set_loading.set(true);
+        spawn_local(async move {
+            match run_screener(&request).await {
+                Ok(fetched) => {
+                    results.set(Some(fetched));
+                    set_error.set(None);
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+            set_loading.set(false);
+        });
+    };
+
+    let sort_by = move |column: Column| {
+        set_sort.update(|(current, ascending)| {
+            if *current == column {
+                *ascending = !*ascending;
+            } else {
+                *current = column;
+                *ascending = matches!(column, Column::Symbol | Column::Sector);
+            }
+        });
+    };
+
+    let sorted = move || {
+        let (column, ascending) = sort.get();
+        results.with(|results| {
+            results.clone().map(|mut rows| {
+                rows.sort_by(|a, b| {
+                    let ordering = column.compare(a, b);
+                    if ascending { ordering } else { ordering.reverse() }
+                });
+                rows
+            })
+        })
+    };
+
+    view! {
+        <div class="page screener-page">
+            <div class="page-title">"Screener"</div>
+            <form
+                class="screener-form"
+                on:submit=move |ev| {
+                    ev.prevent_default();
+                    run();
+                }
+            >
+                <label>
+                    "Market cap"
+                    <select on:change=move |ev| set_market_cap.set(event_target_value(&ev))>
+                        <option value="">"Any"</option>
+                        {MARKET_CAPS.iter().map(|(label, _, _)| view! {
+                            <option value=*label>{*label}</option>
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </label>
+                <label>
+                    "Sector"
+                    <select on:change=move |ev| set_sector.set(event_target_value(&ev))>
+                        <option value="">"Any"</option>
+                        {SECTORS.iter().map(|sector| view! {
+                            <option value=*sector>{*sector}</option>
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </label>
+                <label>
+                    "RSI"
+                    <span class="screener-range">
+                        <input
+                            type="number"
+                            min="0"
+                            max="100"
+                            placeholder="min"
+                            on:input=move |ev| set_rsi_min.set(event_target_value(&ev))
+                        />
+                        <input
+                            type="number"
+                            min="0"
+                            max="100"
+                            placeholder="max"
+                            on:input=move |ev| set_rsi_max.set(event_target_value(&ev))
+                        />
+                    </span>
+                </label>
+                <label>
+                    "Wave pattern"
+                    <select on:change=move |ev| set_wave_pattern.set(event_target_value(&ev))>
+                        <option value="">"Any"</option>
+                        {WAVE_PATTERNS.iter().map(|(value, label)| view! {
+                            <option value=*value>{*label}</option>
+                        }).collect::<Vec<_>>()}
+                    </select>
+                </label>
+                <button type="submit" prop:disabled=move || loading.get()>"Screen"</button>
+                {move || loading.get().then(|| view! { <span class="spinner"></span> })}
+            </form>
+            {move || error.get().map(|e| view! {
+                <p class="panel-empty">{format!("Screener failed: {e}")}</p>
+            })}
+            {move || sorted().map(|rows| {
+                if rows.is_empty() {
+                    return view! { <p class="panel-empty">"No matches."</p> }.into_any();
+                }
+                view! {
+                    <table class="screener-table">
+                        <thead>
+                            <tr>
+                                {Column::ALL.into_iter().map(|column| {
+                                    let indicator = move || {
+                                        let (current, ascending) = sort.get();
+                                        match (current == column, ascending) {
+                                            (false, _) => "",
+                                            (true, true) => " ▲",
+                                            (true, false) => " ▼",
+                                        }
+                                    };
+                                    view! {
+                                        <th on:click=move |_| sort_by(column)>
+                                            {column.label()}{indicator}
+                                        </th>
+                                    }
+                                }).collect::<Vec<_>>()}
+                                <th>"Wave"</th>
+                                <th></th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            {rows.into_iter().map(|row| {
+                                let (change, class) = format_change(row.change_percent);
+                                let symbol = row.symbol.clone();
+                                view! {
+                                    <tr>
+                                        <td title=row.name>
+                                            <strong>{row.symbol}</strong>
+                                        </td>
+                                        <td>{row.sector}</td>
+                                        <td>{format_large(row.market_cap)}</td>
+                                        <td class="price">{format!("{:.2}", row.price)}</td>
+                                        <td class=class>{change}</td>
+                                        <td>{format!("{:.0}", row.rsi)}</td>
+                                        <td>{row.wave_pattern.as_deref().map(wave_label).unwrap_or("—").to_string()}</td>
+                                        <td>
+                                            <button
+                                                class="screener-analyze"
+                                                on:click=move |_| on_analyze.run(symbol.clone())
+                                            >
+                                                "Analyze"
+                                            </button>
+                                        </td>
+                                    </tr>
+                                }
+                            }).collect::<Vec<_>>()}
+                        </tbody>
+                    </table>
+                }
+                .into_any()
+            })}
+        </div>
+    }
+}
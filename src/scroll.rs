@@ -0,0 +1,26 @@
+use crate::query_all;
+
+/// How far the conversation is scrolled, in px. A custom element scrolls its container;
+/// the page scrolls the window.
+pub fn position(in_element: bool) -> f64 {
+    if in_element {
+        query_all(".container").first().map_or(0.0, |c| c.scroll_top() as f64)
+    } else {
+        web_sys::window().and_then(|w| w.scroll_y().ok()).unwrap_or(0.0)
+    }
+}
+
+/// Scroll the conversation to `top`, or to the bottom for `None`.
+pub fn scroll_to(in_element: bool, top: Option<f64>) {
+    if in_element {
+        if let Some(container) = query_all(".container").first() {
+            let top = top.map_or(container.scroll_height(), |top| top as i32);
+            container.set_scroll_top(top);
+        }
+    } else if let Some(window) = web_sys::window()
+        && let Some(document) = window.document()
+        && let Some(element) = document.document_element()
+    {
+        window.scroll_to_with_x_and_y(0.0, top.unwrap_or(element.scroll_height() as f64));
+    }
+}
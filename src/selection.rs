@@ -0,0 +1,142 @@
+use leptos::prelude::{
+    component, event_target_checked, view, Effect, Get, GetUntracked, IntoView, RwSignal, Set,
+    Track, Update, With, WithUntracked,
+};
+use leptos::task::spawn_local;
+use leptos::tachys::prelude::*;
+
+use crate::account::download;
+use crate::state::{use_app_state, AppState};
+use crate::{dialog, share, toast, Message, Role};
+
+/// Messages picked for a bulk action, in the order they were picked; `None` outside
+/// selection mode.
+pub type Selection = RwSignal<Option<Vec<usize>>>;
+
+/// Selection mode for the open conversation; switching conversations leaves it.
+pub fn use_selection() -> Selection {
+    let state = use_app_state();
+    let selection = RwSignal::new(None);
+    Effect::new(move |_| {
+        state.conversation_id.track();
+        selection.set(None);
+    });
+    selection
+}
+
+/// Enter selection mode with `id` picked, or pick it too if already selecting.
+pub fn start(selection: Selection, id: usize) {
+    selection.update(|s| {
+        let picked = s.get_or_insert_with(Vec::new);
+        if !picked.contains(&id) {
+            picked.push(id);
+        }
+    });
+}
+
+fn toggle(selection: Selection, id: usize, on: bool) {
+    selection.update(|s| {
+        if let Some(picked) = s {
+            picked.retain(|&p| p != id);
+            if on {
+                picked.push(id);
+            }
+        }
+    });
+}
+
+/// The selected messages in conversation order, whichever order they were picked in.
+fn selected(state: AppState, selection: Selection) -> Vec<Message> {
+    let picked = selection.get_untracked().unwrap_or_default();
+    state
+        .messages
+        .with_untracked(|m| m.iter().filter(|m| picked.contains(&m.id)).cloned().collect())
+}
+
+/// Messages as Markdown, for pasting into notes: who said it, any quote it replied to, the
+/// text, and which charts it had (the charts themselves are HTML and stay behind).
+fn to_markdown(messages: &[Message]) -> String {
+    messages
+        .iter()
+        .map(|message| {
+            let mut markdown = match message.role {
+                Role::User => "**You**\n\n".to_string(),
+                Role::Assistant => "**Xve**\n\n".to_string(),
+            };
+            if let Some(quoted) = &message.quoted {
+                for line in quoted.lines() {
+                    markdown.push_str(&format!("> {line}\n"));
+                }
+                markdown.push('\n');
+            }
+            markdown.push_str(message.content.trim());
+            for chart in &message.charts {
+                markdown.push_str(&format!("\n\n_Chart: {}_", chart.symbol));
+            }
+            markdown
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n---\n\n")
+}
+
+/// Checkbox on a message while selecting.
+#[component]
+pub fn SelectCheckbox(id: usize, selection: Selection) -> impl IntoView {
+    move || {
+        selection.with(Option::is_some).then(|| view! {
+            <input
+                class="message-select"
+                type="checkbox"
+                aria-label="Select message"
+                prop:checked=move || selection.with(|s| s.as_ref().is_some_and(|s| s.contains(&id)))
+                on:change=move |ev| toggle(selection, id, event_target_checked(&ev))
+            />
+        })
+    }
+}
+
+/// Bulk actions on the selected messages, shown over the input while selecting.
+#[component]
+pub fn SelectionBar(selection: Selection) -> impl IntoView {
+    let state = use_app_state();
+    let count = move || selection.with(|s| s.as_ref().map_or(0, Vec::len));
+    let copy = move |_| share::copy(to_markdown(&selected(state, selection)));
+    let export = move |_| {
+        let markdown = to_markdown(&selected(state, selection));
+        match download("xve-chat-selection.md", &markdown, "text/markdown") {
+            Ok(()) => toast::success("Selection exported"),
+            Err(e) => toast::error(format!("Couldn't export: {e}")),
+        }
+    };
+    let delete = move |_| {
+        let n = count();
+        spawn_local(async move {
+            let noun = if n == 1 { "message" } else { "messages" };
+            if dialog::confirm(format!("Delete {n} {noun}?")).await {
+                let ids = selection.get_untracked().unwrap_or_default();
+                state.delete_many(&ids);
+                selection.set(None);
+            }
+        });
+    };
+
+    move || {
+        selection.with(Option::is_some).then(|| view! {
+            <div class="selection-bar">
+                <span class="selection-count">{move || format!("{} selected", count())}</span>
+                <button class="panel-option" disabled=move || count() == 0 on:click=copy>"Copy"</button>
+                <button class="panel-option" disabled=move || count() == 0 on:click=export>
+                    "Export .md"
+                </button>
+                <button
+                    class="panel-option danger"
+                    disabled=move || count() == 0 || state.loading.get()
+                    on:click=delete
+                >
+                    "Delete"
+                </button>
+                <button class="panel-option" on:click=move |_| selection.set(None)>"Done"</button>
+            </div>
+        })
+    }
+}
@@ -0,0 +1,78 @@
+use leptos::prelude::{component, event_target_checked, signal, view, Get, IntoView, Set};
+use leptos::tachys::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{load_json, new_id, save_json};
+
+const SESSION_KEY: &str = "wxve-chat:session";
+
+/// Anonymous id the backend uses to correlate requests across reconnects and reloads
+#[derive(Serialize, Deserialize)]
+struct Session {
+    enabled: bool,
+    id: String,
+}
+
+impl Session {
+    fn load() -> Self {
+        load_json(SESSION_KEY).unwrap_or_else(|| {
+            let session = Session { enabled: true, id: new_id() };
+            save_json(SESSION_KEY, &session);
+            session
+        })
+    }
+}
+
+/// The id to send as `X-Session-Id`, or `None` if the user opted out.
+pub fn session_id() -> Option<String> {
+    let session = Session::load();
+    session.enabled.then_some(session.id)
+}
+
+fn set_enabled(enabled: bool) {
+    let session = Session { enabled, ..Session::load() };
+    save_json(SESSION_KEY, &session);
+}
+
+/// Replace the id so later requests can't be linked to earlier ones.
+fn rotate() -> String {
+    let session = Session { id: new_id(), ..Session::load() };
+    save_json(SESSION_KEY, &session);
+    session.id
+}
+
+/// Session id section of the settings dialog.
+#[component]
+pub fn SessionSettings() -> impl IntoView {
+    let session = Session::load();
+    let (enabled, set_session_enabled) = signal(session.enabled);
+    let (id, set_id) = signal(session.id);
+
+    view! {
+        <section class="settings-section">
+            <h3>"Session id"</h3>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=enabled
+                    on:change=move |ev| {
+                        let on = event_target_checked(&ev);
+                        set_enabled(on);
+                        set_session_enabled.set(on);
+                    }
+                />
+                "Send an anonymous session id so the server can pick up a conversation after a reconnect"
+            </label>
+            <div class="settings-row">
+                <code class="session-id">{id}</code>
+                <button
+                    class="dialog-button"
+                    disabled=move || !enabled.get()
+                    on:click=move |_| set_id.set(rotate())
+                >
+                    "Rotate"
+                </button>
+            </div>
+        </section>
+    }
+}
@@ -0,0 +1,63 @@
+use leptos::task::spawn_local;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_futures::JsFuture;
+
+use crate::toast;
+
+/// Hand `text` to the system share sheet, or copy it where `navigator.share` isn't
+/// available (most desktop browsers, and iframes without the `web-share` permission).
+pub fn share(text: String) {
+    spawn_local(async move {
+        match share_sheet(&text).await {
+            Ok(()) => {}
+            // The user closed the share sheet
+            Err(e) if error_name(&e) == "AbortError" => {}
+            Err(e) => {
+                log::debug!(target: "ui", "Share unavailable, copying instead: {e:?}");
+                copy_now(&text).await;
+            }
+        }
+    });
+}
+
+/// Copy `text` to the clipboard, confirming with a toast.
+pub fn copy(text: String) {
+    spawn_local(async move { copy_now(&text).await });
+}
+
+async fn copy_now(text: &str) {
+    match write_clipboard(text).await {
+        Ok(()) => toast::success("Copied to clipboard"),
+        Err(e) => toast::error(format!("Couldn't copy: {}", error_name(&e))),
+    }
+}
+
+async fn share_sheet(text: &str) -> Result<(), JsValue> {
+    let navigator = web_sys::window().ok_or(JsValue::NULL)?.navigator();
+    if !js_sys::Reflect::has(&navigator, &"share".into())? {
+        return Err(JsValue::from_str("navigator.share unsupported"));
+    }
+    let data = web_sys::ShareData::new();
+    data.set_title("Xve");
+    data.set_text(text);
+    JsFuture::from(navigator.share_with_data(&data)).await?;
+    Ok(())
+}
+
+async fn write_clipboard(text: &str) -> Result<(), JsValue> {
+    let navigator = web_sys::window().ok_or(JsValue::NULL)?.navigator();
+    // Only exposed on secure origins
+    if !js_sys::Reflect::has(&navigator, &"clipboard".into())? {
+        return Err(JsValue::from_str("navigator.clipboard unsupported"));
+    }
+    JsFuture::from(navigator.clipboard().write_text(text)).await?;
+    Ok(())
+}
+
+/// The `name` of a DOMException, e.g. `AbortError` or `NotAllowedError`
+fn error_name(error: &JsValue) -> String {
+    js_sys::Reflect::get(error, &"name".into())
+        .ok()
+        .and_then(|name| name.as_string())
+        .unwrap_or_else(|| format!("{error:?}"))
+}
@@ -0,0 +1,63 @@
+use std::cell::RefCell;
+
+use web_sys::{AudioContext, AudioContextState, OscillatorType};
+
+use crate::window_listener;
+
+/// Volume used when the sound is first switched on
+pub const DEFAULT_VOLUME: f64 = 0.5;
+
+thread_local! {
+    static CONTEXT: RefCell<Option<AudioContext>> = const { RefCell::new(None) };
+}
+
+/// Browsers only allow audio once the page has had a user gesture, so the
+/// context is created (or resumed) from the first click or key press.
+pub fn unlock_on_gesture() {
+    let unlock = || {
+        CONTEXT.with(|context| {
+            let mut context = context.borrow_mut();
+            if context.is_none() {
+                *context = AudioContext::new().ok();
+            }
+            if let Some(context) = context.as_ref()
+                && context.state() == AudioContextState::Suspended
+            {
+                let _ = context.resume();
+            }
+        });
+    };
+    window_listener(leptos::ev::pointerdown, move |_| unlock());
+    window_listener(leptos::ev::keydown, move |_| unlock());
+}
+
+/// Two-note chime at `volume` (0.0-1.0). Silently skipped before the first gesture.
+pub fn chime(volume: f64) {
+    CONTEXT.with(|context| {
+        let context = context.borrow();
+        let Some(context) = context.as_ref() else {
+            return;
+        };
+        if context.state() != AudioContextState::Running {
+            return;
+        }
+        let start = context.current_time();
+        for (i, frequency) in [880.0, 1320.0].into_iter().enumerate() {
+            let (Ok(oscillator), Ok(gain)) = (context.create_oscillator(), context.create_gain())
+            else {
+                return;
+            };
+            let at = start + i as f64 * 0.12;
+            oscillator.set_type(OscillatorType::Sine);
+            oscillator.frequency().set_value(frequency);
+            // Quick attack, exponential decay so there's no click at the end
+            let _ = gain.gain().set_value_at_time(0.0, at);
+            let _ = gain.gain().linear_ramp_to_value_at_time((volume * 0.3) as f32, at + 0.01);
+            let _ = gain.gain().exponential_ramp_to_value_at_time(0.0001, at + 0.25);
+            let _ = oscillator.connect_with_audio_node(&gain);
+            let _ = gain.connect_with_audio_node(&context.destination());
+            let _ = oscillator.start_with_when(at);
+            let _ = oscillator.stop_with_when(at + 0.3);
+        }
+    });
+}
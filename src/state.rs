@@ -0,0 +1,1113 @@
+use std::cell::Cell;
+use std::rc::Rc;
+
+use leptos::prelude::{
+    expect_context, on_cleanup, provide_context, untrack, Effect, Get, GetUntracked, GetValue,
+    IsDisposed, RwSignal, Set, SetValue, StoredValue, Update, With, WithUntracked, WithValue,
+};
+use leptos::task::spawn_local;
+use serde_json::Value;
+use web_sys::{AbortController, AbortSignal};
+
+use crate::alerts::{add_alert, PriceAlert};
+use crate::branches::{fork, prepend, remove, remove_front, switch_branch, Fork};
+use crate::compare::{stream_variants, Pane};
+use crate::conversations::{load_conversations, upsert_conversation, Conversation};
+use crate::delivery::Delivery;
+use crate::delta::Held;
+use crate::history;
+use crate::reactions::Reaction;
+use crate::remote_config::RemoteConfig;
+use crate::resume::{self, InFlight};
+use crate::telemetry::{self, ErrorSource, TelemetryEvent};
+use crate::tool_approval::{send_decision, ToolRequest};
+use crate::tools::{enabled_tools, use_tools, Tool};
+use crate::turn_error::{Failure, TurnError};
+use crate::unread::mark_unread;
+use crate::vault::Vault;
+use crate::{
+    embed, inspector, load_json, request_preview, new_id, post_chart_update, save_json, send_message, toast, Chart,
+    ChatRequest, Message, PortfolioSummary, ResponseMeta, Role, Sentiment, Settings, StreamChunk,
+    INCOGNITO_HISTORY, MAX_RECENT_SYMBOLS, RECENT_SYMBOLS_KEY,
+};
+
+/// The same message sent again within this long is taken for a double press
+const DUPLICATE_WINDOW_MS: f64 = 2000.0;
+
+/// Lifecycle of a `/chat` response, driven by its stream rather than by what text has
+/// arrived, so the UI can tell "nothing yet" from "paused between tokens".
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StreamPhase {
+    Idle,
+    /// Sent, or back from a tool, and waiting for text
+    Thinking,
+    /// Text is arriving
+    Typing,
+}
+
+/// The conversation and the response being streamed into it, shared through context so
+/// any component can read it or act on it with `use_app_state()`.
+///
+/// Actions refuse to run while `loading` (except `stop`), so a conversation never has
+/// two responses in flight; a message sent meanwhile waits in `queued`.
+#[derive(Clone, Copy)]
+pub struct AppState {
+    /// The active path of the conversation (unique ids for keyed rendering)
+    pub messages: RwSignal<Vec<Message>>,
+    /// Branches off the active path in `messages`, saved with the conversation
+    pub forks: RwSignal<Vec<Fork>>,
+    /// A response (or a comparison) is being streamed
+    pub loading: RwSignal<bool>,
+    /// Text of the response being streamed, moved into `messages` when it's done
+    pub current_response: RwSignal<String>,
+    /// Name of the backend tool running for the response being streamed
+    pub tool_running: RwSignal<Option<String>>,
+    /// A tool call the response is waiting on the user to approve
+    pub tool_request: RwSignal<Option<ToolRequest>>,
+    /// Where the response being streamed is in its lifecycle
+    pub phase: RwSignal<StreamPhase>,
+    pub pending_meta: RwSignal<Option<ResponseMeta>>,
+    /// Variants streamed side by side by "Regenerate and compare"
+    pub compare_panes: RwSignal<Vec<Pane>>,
+    /// Saved conversations and the id the current `messages` are saved under
+    pub conversations: RwSignal<Vec<Conversation>>,
+    pub conversation_id: RwSignal<String>,
+    /// Messages before `messages` moved out to IndexedDB by the history cap
+    pub archived: RwSignal<usize>,
+    /// Id of the first response that finished while the tab was hidden, marked by a divider
+    /// until it's been seen
+    pub unseen: RwSignal<Option<usize>>,
+    /// Ephemeral chat: nothing is saved and it's gone when the tab closes
+    pub incognito: RwSignal<bool>,
+    /// Sent with every request once the user discusses their portfolio
+    pub attached_portfolio: RwSignal<Option<PortfolioSummary>>,
+    /// Most recently charted symbols, newest first
+    pub recent_symbols: RwSignal<Vec<String>>,
+    pub settings: RwSignal<Settings>,
+    /// The backend's tools, some of which the settings may switch off
+    pub tools: RwSignal<Vec<Tool>>,
+    /// A message (and its quote) sent while a response was streaming, sent once it finishes
+    pub queued: RwSignal<Option<(String, Option<String>)>>,
+    /// The last message sent and when, to drop accidental repeats
+    last_sent: StoredValue<Option<(String, f64)>>,
+    next_id: RwSignal<usize>,
+    pending_charts: RwSignal<Vec<Chart>>,
+    pending_sentiment: RwSignal<Option<Sentiment>>,
+    alerts: RwSignal<Vec<PriceAlert>>,
+    unread: RwSignal<usize>,
+    remote_config: RwSignal<RemoteConfig>,
+    /// Aborts the `/chat` request being streamed
+    controller: StoredValue<Option<AbortController>>,
+    /// History the backend keeps from the last turn that finished, see `delta.rs`
+    held: StoredValue<Option<Held>>,
+}
+
+/// The `AppState` provided by `App`.
+pub fn use_app_state() -> AppState {
+    expect_context()
+}
+
+impl AppState {
+    /// Create the state for a new conversation and provide it to every component below.
+    pub fn provide(
+        settings: RwSignal<Settings>,
+        alerts: RwSignal<Vec<PriceAlert>>,
+        unread: RwSignal<usize>,
+        remote_config: RwSignal<RemoteConfig>,
+    ) -> Self {
+        let state = AppState {
+            messages: RwSignal::new(Vec::new()),
+            forks: RwSignal::new(Vec::new()),
+            loading: RwSignal::new(false),
+            current_response: RwSignal::new(String::new()),
+            tool_running: RwSignal::new(None),
+            tool_request: RwSignal::new(None),
+            phase: RwSignal::new(StreamPhase::Idle),
+            pending_meta: RwSignal::new(None),
+            compare_panes: RwSignal::new(Vec::new()),
+            conversations: RwSignal::new(load_conversations()),
+            conversation_id: RwSignal::new(new_id()),
+            archived: RwSignal::new(0),
+            unseen: RwSignal::new(None),
+            incognito: RwSignal::new(false),
+            attached_portfolio: RwSignal::new(None),
+            recent_symbols: RwSignal::new(load_json(RECENT_SYMBOLS_KEY).unwrap_or_default()),
+            settings,
+            tools: use_tools(),
+            queued: RwSignal::new(None),
+            last_sent: StoredValue::new(None),
+            next_id: RwSignal::new(0),
+            pending_charts: RwSignal::new(Vec::new()),
+            pending_sentiment: RwSignal::new(None),
+            alerts,
+            unread,
+            remote_config,
+            controller: StoredValue::new(None),
+            held: StoredValue::new(None),
+        };
+
+        Effect::new(move |_| state.recent_symbols.with(|r| save_json(RECENT_SYMBOLS_KEY, r)));
+
+        // A response the page was reloaded in the middle of comes back, interrupted
+        if let Some(in_flight) = resume::take()
+            && let Some(id) = state.conversations.try_update(|c| in_flight.restore_into(c)).flatten()
+        {
+            state.switch_conversation(&id);
+            toast::info("The response cut off by the reload was kept; continue or regenerate it");
+        }
+
+        // Keep the response being streamed where a reload can find it, at most once a second
+        let saved_at = StoredValue::new(0.0);
+        Effect::new(move |_| {
+            let content = state.current_response.get();
+            let charts = state.pending_charts.get();
+            if content.is_empty() && charts.is_empty() {
+                resume::clear();
+                return;
+            }
+            let now = js_sys::Date::now();
+            // Like archiving, nothing unencrypted is kept for incognito chats or behind the vault
+            if now - saved_at.get_value() < resume::SAVE_EVERY_MS
+                || state.incognito.get_untracked()
+                || !matches!(Vault::load(), Vault::Off)
+            {
+                return;
+            }
+            saved_at.set_value(now);
+            resume::save(&InFlight {
+                conversation_id: state.conversation_id.get_untracked(),
+                after: state.messages.with_untracked(Vec::len),
+                content,
+                charts,
+            });
+        });
+
+        // Keep the current conversation's saved copy up to date
+        Effect::new(move |_| {
+            if state.incognito.get() {
+                return;
+            }
+            let id = state.conversation_id.get();
+            let archived = state.archived.get();
+            state.messages.with(|msgs| {
+                if !msgs.is_empty() {
+                    state.forks.with(|f| {
+                        state
+                            .conversations
+                            .update(|c| upsert_conversation(c, &id, msgs, f, archived));
+                    });
+                }
+            });
+        });
+
+        // Turns go one at a time: a queued message goes out when the response before it ends
+        Effect::new(move |_| {
+            if state.loading.get() {
+                return;
+            }
+            if let Some((message, quoted)) = state.queued.get_untracked() {
+                state.queued.set(None);
+                untrack(|| state.send(message, quoted));
+            }
+        });
+
+        // Streams end with the app: an unmounted `App` (an embed taken down, or between
+        // tests) mustn't keep reading into signals that are gone
+        on_cleanup(move || {
+            if let Some(controller) = state.controller.get_value() {
+                controller.abort();
+            }
+        });
+
+        provide_context(state);
+        state
+    }
+
+    fn enabled(self, feature: &str) -> bool {
+        self.remote_config.with_untracked(|c| c.enabled(feature))
+    }
+
+    fn enabled_tools(self) -> Option<Vec<String>> {
+        self.tools.with_untracked(|t| self.settings.with_untracked(|s| enabled_tools(t, s)))
+    }
+
+    /// Reserve the id for a new message.
+    fn take_id(self) -> usize {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        id
+    }
+
+    /// Position of message `id` on the active path.
+    pub fn index_of(self, id: usize) -> Option<usize> {
+        self.messages.with(|m| m.iter().position(|msg| msg.id == id))
+    }
+
+    /// Incognito chats only send the most recent turns. Errors in place of answers are
+    /// left out.
+    fn capped_history(self, history: &[Message]) -> Vec<wxve_chat_client::Message> {
+        let excess = if self.incognito.get_untracked() {
+            history.len().saturating_sub(INCOGNITO_HISTORY)
+        } else {
+            0
+        };
+        history[excess..]
+            .iter()
+            .filter(|m| m.error.is_none())
+            .map(wxve_chat_client::Message::from)
+            .collect()
+    }
+
+    /// Send `message` (replying to `quoted`), returning false if it was rejected. While a
+    /// response is streaming one message is queued behind it; more are turned away.
+    pub fn send(self, message: String, quoted: Option<String>) -> bool {
+        if message.trim().is_empty() {
+            return false;
+        }
+        let now = js_sys::Date::now();
+        let repeated = self.last_sent.with_value(|last| {
+            last.as_ref().is_some_and(|(text, at)| {
+                text.trim() == message.trim() && now - at < DUPLICATE_WINDOW_MS
+            })
+        });
+        if repeated {
+            log::debug!(target: "ui", "Repeated send ignored");
+            toast::info("Ignored a repeat of the message you just sent");
+            return false;
+        }
+        if self.queued.with(Option::is_some) {
+            log::debug!(target: "ui", "Send suppressed");
+            toast::info("Please wait for the current response");
+            return false;
+        }
+        if self.loading.get() {
+            log::info!(target: "ui", "Queued a message behind the current response");
+            self.queued.set(Some((message, quoted)));
+            return true;
+        }
+        self.last_sent.set_value(Some((message.clone(), now)));
+        self.evict();
+
+        // Capture history BEFORE adding user message to avoid duplication
+        let history = self.messages.with(|m| self.capped_history(m));
+        log::info!(target: "ui", "Sending message with {} messages of history", history.len());
+        telemetry::track(TelemetryEvent::MessageSent {
+            quoted: quoted.is_some(),
+            history_len: history.len(),
+        });
+        let request = ChatRequest {
+            message: message.clone(),
+            history: Some(history),
+            conversation_id: None,
+            indicators: self.settings.get().indicators,
+            portfolio: self.attached_portfolio.get(),
+            quoted: quoted.clone(),
+            temperature: None,
+            tools: self.enabled_tools(),
+            approve_tools: self.settings.get().approve_tools,
+            partial: None,
+            idempotency_key: new_id(),
+        };
+
+        let id = self.take_id();
+        self.messages.update(|msgs| {
+            msgs.push(Message {
+                id,
+                role: Role::User,
+                content: message,
+                quoted,
+                charts: Vec::new(),
+                sentiment: None,
+                meta: None,
+                pinned: false,
+                reactions: Vec::new(),
+                delivery: Some(Delivery::Sending),
+                error: None,
+                interrupted: false,
+            });
+        });
+
+        self.respond(request, Some(id));
+        true
+    }
+
+    /// Move the oldest messages past the history cap out to IndexedDB. Stops short of the
+    /// first fork, and keeps everything in memory in incognito chats (nothing may be saved)
+    /// and behind the vault (the archive isn't encrypted).
+    fn evict(self) {
+        if self.incognito.get_untracked() || !matches!(Vault::load(), Vault::Off) {
+            return;
+        }
+        let cap = self.settings.with_untracked(history::cap);
+        let first_fork = self.forks.with_untracked(|f| f.iter().map(|f| f.at).min());
+        let excess = self
+            .messages
+            .with_untracked(|m| m.len().saturating_sub(cap).min(first_fork.unwrap_or(m.len())));
+        if excess == 0 {
+            return;
+        }
+        let conversation = self.conversation_id.get_untracked();
+        let from = self.archived.get_untracked();
+        let evicted = self.messages.with_untracked(|m| m[..excess].to_vec());
+        spawn_local(async move {
+            // Only dropped from memory once safely stored
+            if let Err(e) = history::archive(&conversation, from, &evicted).await {
+                log::warn!(target: "ui", "Couldn't archive old messages: {e:?}");
+                return;
+            }
+            if self.conversation_id.get_untracked() != conversation
+                || self.archived.get_untracked() != from
+            {
+                return;
+            }
+            self.forks.update(|f| self.messages.update(|m| remove_front(m, f, excess)));
+            self.archived.set(from + excess);
+            log::info!(target: "ui", "Archived {excess} old messages");
+        });
+    }
+
+    /// Bring back the archived messages just before the first one in `messages`.
+    pub async fn load_earlier(self) {
+        let conversation = self.conversation_id.get_untracked();
+        let end = self.archived.get_untracked();
+        let start = end.saturating_sub(history::PAGE);
+        if end == 0 {
+            return;
+        }
+        match history::load(&conversation, start, end).await {
+            Ok(earlier) if earlier.len() == end - start => {
+                if self.conversation_id.get_untracked() != conversation
+                    || self.archived.get_untracked() != end
+                {
+                    return;
+                }
+                let first_id = self.next_id.get_untracked();
+                self.next_id.set(first_id + earlier.len());
+                let earlier = earlier
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, message)| Message { id: first_id + i, ..message })
+                    .collect();
+                self.forks.update(|f| self.messages.update(|m| prepend(m, f, earlier)));
+                self.archived.set(start);
+            }
+            Ok(_) => {
+                // Site data cleared since: what's left is the whole conversation now
+                log::warn!(target: "ui", "Archived messages missing for {conversation}");
+                self.archived.set(0);
+            }
+            Err(e) => {
+                log::warn!(target: "ui", "Couldn't load archived messages: {e:?}");
+                toast::error("Couldn't load earlier messages");
+            }
+        }
+    }
+
+    /// Bring every archived message back into its conversation and forget the archive,
+    /// which isn't encrypted, before the vault seals the conversations.
+    pub async fn unarchive_all(self) -> Result<(), String> {
+        let archived: Vec<(String, usize)> = self.conversations.with_untracked(|c| {
+            c.iter().filter(|c| c.archived > 0).map(|c| (c.id.clone(), c.archived)).collect()
+        });
+        for (id, count) in archived {
+            let earlier = history::load(&id, 0, count)
+                .await
+                .map_err(|e| format!("Couldn't load archived messages: {e:?}"))?;
+            if self.conversation_id.get_untracked() == id && self.archived.get_untracked() == count {
+                let first_id = self.next_id.get_untracked();
+                self.next_id.set(first_id + earlier.len());
+                let restored = earlier
+                    .iter()
+                    .cloned()
+                    .enumerate()
+                    .map(|(i, message)| Message { id: first_id + i, ..message })
+                    .collect();
+                self.forks.update(|f| self.messages.update(|m| prepend(m, f, restored)));
+                self.archived.set(0);
+            }
+            self.conversations.update(|c| {
+                if let Some(conversation) = c.iter_mut().find(|c| c.id == id)
+                    && conversation.archived == count
+                {
+                    prepend(&mut conversation.messages, &mut conversation.forks, earlier);
+                    conversation.archived = 0;
+                }
+            });
+            history::delete(&id);
+        }
+        Ok(())
+    }
+
+    /// Stamp the in-flight response's metadata and take it for the finished message.
+    fn take_meta(self) -> Option<ResponseMeta> {
+        let mut meta = self.pending_meta.get();
+        if let Some(meta) = &mut meta {
+            meta.finished_at = Some(js_sys::Date::now());
+        }
+        self.pending_meta.set(None);
+        meta
+    }
+
+    /// Forget the response being streamed and stop loading.
+    fn clear_response(self) {
+        self.controller.set_value(None);
+        self.current_response.set(String::new());
+        self.pending_charts.set(Vec::new());
+        self.pending_sentiment.set(None);
+        self.tool_running.set(None);
+        self.tool_request.set(None);
+        self.pending_meta.set(None);
+        self.phase.set(StreamPhase::Idle);
+        self.loading.set(false);
+    }
+
+    /// Some text or a chart of the response being streamed has arrived.
+    fn something_arrived(self) -> bool {
+        self.current_response.with(|r| !r.trim().is_empty())
+            || self.pending_charts.with(|c| !c.is_empty())
+    }
+
+    /// Move the response streamed so far into `messages`, marked `interrupted` if the stream
+    /// died before it was done.
+    fn commit_response(self, interrupted: bool) -> Message {
+        let message = Message {
+            id: self.take_id(),
+            role: Role::Assistant,
+            content: self.current_response.get(),
+            quoted: None,
+            charts: self.pending_charts.get(),
+            sentiment: self.pending_sentiment.get(),
+            meta: self.take_meta(),
+            pinned: false,
+            reactions: Vec::new(),
+            delivery: None,
+            error: None,
+            interrupted,
+        };
+        self.messages.update(|msgs| msgs.push(message.clone()));
+        self.clear_response();
+        message
+    }
+
+    fn push_error(self, error: TurnError) {
+        embed::response_failed(&error.detail);
+        let id = self.take_id();
+        let meta = self.take_meta();
+        self.messages.update(|msgs| {
+            msgs.push(Message {
+                id,
+                role: Role::Assistant,
+                content: format!("Error: {}", error.detail),
+                quoted: None,
+                charts: Vec::new(),
+                sentiment: None,
+                meta,
+                pinned: false,
+                reactions: Vec::new(),
+                delivery: None,
+                error: Some(error),
+                interrupted: false,
+            });
+        });
+        self.clear_response();
+    }
+
+    /// End the response being streamed with `error`. What arrived is kept, interrupted, to
+    /// be continued; with nothing, the error takes the answer's place.
+    fn fail_response(self, error: TurnError) {
+        if self.something_arrived() {
+            log::warn!(target: "transport", "Response interrupted: {}", error.detail);
+            self.commit_response(true);
+            embed::response_failed(&error.detail);
+        } else {
+            self.push_error(error);
+        }
+    }
+
+    /// Name the conversation on `request` when the backend keeps histories, except for
+    /// incognito chats, which it shouldn't keep.
+    fn with_conversation(self, request: ChatRequest) -> ChatRequest {
+        let kept = self.remote_config.with_untracked(|c| c.conversation_history)
+            && !self.incognito.get_untracked();
+        let conversation_id = kept.then(|| self.conversation_id.get_untracked());
+        ChatRequest { conversation_id, ..request }
+    }
+
+    /// Update how far the user message `id` got; once delivered it stays delivered.
+    fn set_delivery(self, id: usize, delivery: Option<Delivery>) {
+        let current = self.messages.with_untracked(|m| {
+            m.iter().find(|m| m.id == id).map(|m| m.delivery.clone())
+        });
+        let Some(current) = current else {
+            return;
+        };
+        if current == delivery || current == Some(Delivery::Delivered) {
+            return;
+        }
+        self.messages.update(|m| {
+            if let Some(message) = m.iter_mut().find(|m| m.id == id) {
+                message.delivery = delivery;
+            }
+        });
+    }
+
+    /// The body to send for `request`: as the user left it in the review step if they asked
+    /// for one (or in the dev inspector), or `None` if they cancelled or pressed Stop.
+    async fn review(self, request: &ChatRequest, signal: &AbortSignal) -> Option<Value> {
+        if self.settings.with_untracked(|s| s.review_requests) {
+            request_preview::review(request, signal).await
+        } else {
+            inspector::review(request, signal).await
+        }
+    }
+
+    /// Stream the assistant's reply to `request` onto the end of `messages`, leaving out the
+    /// history if the backend already holds it. The delivery of the user message `prompt`
+    /// follows the request.
+    fn respond(self, request: ChatRequest, prompt: Option<usize>) {
+        let Ok(controller) = AbortController::new() else {
+            return;
+        };
+        let signal = controller.signal();
+        self.controller.set_value(Some(controller));
+        self.loading.set(true);
+        self.phase.set(StreamPhase::Thinking);
+        self.current_response.set(String::new());
+        self.pending_charts.set(Vec::new());
+        self.pending_sentiment.set(None);
+        let request_id = new_id();
+        self.pending_meta.set(Some(ResponseMeta::new(request_id.clone())));
+        let indicators = request.indicators.clone();
+        let request = self.with_conversation(request);
+        let archived = self.archived.get_untracked();
+        let delta = self.held.with_value(|held| held.as_ref()?.delta(&request, archived));
+
+        spawn_local(async move {
+            let key = request.idempotency_key.clone();
+            let failed = Some(Delivery::Failed { idempotency_key: key });
+            let not_sent = {
+                let failed = failed.clone();
+                move || {
+                    if let Some(prompt) = prompt {
+                        self.set_delivery(prompt, failed.clone());
+                    }
+                    // A continuation's partial goes back as it was, to be continued
+                    if self.something_arrived() {
+                        self.commit_response(true);
+                    } else {
+                        self.clear_response();
+                    }
+                    toast::info("Request not sent");
+                }
+            };
+            let proposed = delta.as_ref().unwrap_or(&request);
+            // Stop closes the review, so this is also where a stopped request ends
+            let Some(body) = self.review(proposed, &signal).await else {
+                not_sent();
+                return;
+            };
+            if signal.aborted() {
+                not_sent();
+                return;
+            }
+            if request.partial.is_some() {
+                self.take_partial();
+            }
+
+            // Trimmed or edited on review, the body no longer says what the backend will hold
+            let as_proposed = serde_json::to_value(proposed).ok().as_ref() == Some(&body);
+            let sent = Rc::new(Cell::new(as_proposed));
+            let on_chunk = {
+                let sent = sent.clone();
+                let sent_request = request.clone();
+                move |chunk: StreamChunk| match chunk {
+                    StreamChunk::Text { content } => {
+                        self.pending_meta.update(|meta| {
+                            if let Some(meta) = meta {
+                                meta.first_token_at.get_or_insert_with(js_sys::Date::now);
+                            }
+                        });
+                        self.current_response.update(|r| r.push_str(&content));
+                        self.phase.set(StreamPhase::Typing);
+                    }
+                    StreamChunk::Meta { model, request_id } => {
+                        self.pending_meta.update(|meta| {
+                            if let Some(meta) = meta {
+                                meta.model = Some(model);
+                                if let Some(request_id) = request_id {
+                                    meta.request_id = request_id;
+                                }
+                            }
+                        });
+                    }
+                    // The chart tool can be switched off remotely
+                    StreamChunk::Chart { .. } if !self.enabled("charts") => {}
+                    StreamChunk::Chart { symbol, html, params } => {
+                        if !self.incognito.get_untracked() {
+                            self.recent_symbols.update(|recent| {
+                                recent.retain(|s| *s != symbol);
+                                recent.insert(0, symbol.clone());
+                                recent.truncate(MAX_RECENT_SYMBOLS);
+                            });
+                        }
+                        telemetry::track(TelemetryEvent::ChartRendered);
+                        self.pending_charts.update(|charts| {
+                            charts.push(Chart::new(symbol, html, indicators.clone(), params));
+                        });
+                    }
+                    StreamChunk::ChartUpdate { .. } if !self.enabled("charts") => {}
+                    StreamChunk::ChartUpdate { symbol, patch } => post_chart_update(&symbol, &patch),
+                    StreamChunk::Sentiment { score } => {
+                        self.pending_sentiment.set(Some(Sentiment { score: score.clamp(-1.0, 1.0) }));
+                    }
+                    StreamChunk::Alert { symbol, price } => {
+                        if self.enabled("alerts") {
+                            spawn_local(async move {
+                                add_alert(self.alerts, symbol, price).await;
+                            });
+                        }
+                    }
+                    StreamChunk::Done => {
+                        let message = self.commit_response(false);
+                        let reply = (&message).into();
+                        let sent = sent.get().then_some(&sent_request);
+                        self.held.set_value(sent.and_then(|s| Held::after(s, archived, reply)));
+                        telemetry::track(TelemetryEvent::ResponseCompleted {
+                            duration_ms: message
+                                .meta
+                                .as_ref()
+                                .and_then(|m| m.finished_at.map(|at| (at - m.started_at) as u64))
+                                .unwrap_or_default(),
+                            charts: message.charts.len(),
+                        });
+                        embed::response_finished(&message);
+                        mark_unread(self.unread, self.unseen, message.id);
+                        self.settings.with_untracked(Settings::play_sound);
+                    }
+                    StreamChunk::Error { message } => {
+                        telemetry::track(TelemetryEvent::ErrorShown { source: ErrorSource::Stream });
+                        self.fail_response(TurnError { kind: Failure::Server, detail: message });
+                    }
+                    StreamChunk::ToolRequest { id, name, input } => {
+                        // Only asked for with `approve_tools`, but never leave a call hanging
+                        if self.settings.with_untracked(|s| s.approve_tools) {
+                            self.tool_request.set(Some(ToolRequest { id, name, input }));
+                        } else {
+                            send_decision(id, true);
+                        }
+                    }
+                    StreamChunk::ToolStart { name } => {
+                        self.tool_running.set(Some(name));
+                    }
+                    StreamChunk::ToolEnd { .. } => {
+                        self.tool_running.set(None);
+                        self.current_response.update(|r| r.push_str("\n\n"));
+                        // Working on what the tool returned until the text resumes
+                        self.phase.set(StreamPhase::Thinking);
+                    }
+                }
+            };
+            // Anything back means the backend has the message
+            let finished = Rc::new(Cell::new(false));
+            let on_chunk = {
+                let finished = finished.clone();
+                move |chunk: StreamChunk| {
+                    if let Some(prompt) = prompt {
+                        self.set_delivery(prompt, Some(Delivery::Delivered));
+                    }
+                    if matches!(chunk, StreamChunk::Done | StreamChunk::Error { .. }) {
+                        finished.set(true);
+                    }
+                    on_chunk(chunk);
+                }
+            };
+
+            let mut result = send_message(body, &request_id, Some(&signal), on_chunk.clone()).await;
+            // The backend doesn't have the history the request left out, so send it after all;
+            // that history wasn't in what was reviewed, so the whole request is reviewed again
+            let unknown = matches!(&result, Err(e) if e == "HTTP 409");
+            if delta.is_some() && unknown && !signal.aborted() {
+                log::info!(target: "transport", "Backend lost the conversation, resending its history");
+                self.held.set_value(None);
+                let Some(body) = self.review(&request, &signal).await else {
+                    not_sent();
+                    return;
+                };
+                if signal.aborted() {
+                    not_sent();
+                    return;
+                }
+                sent.set(serde_json::to_value(&request).ok().as_ref() == Some(&body));
+                result = send_message(body, &request_id, Some(&signal), on_chunk).await;
+            }
+            // The app went away mid-stream (see `provide`), so there's nothing left to update
+            if self.messages.is_disposed() {
+                return;
+            }
+            // A body that ends without `done` was cut off as surely as one that errors
+            if result.is_ok() && !finished.get() && !signal.aborted() {
+                result = Err("The response ended early".to_string());
+            }
+            // Nothing came back: failed, unless it was stopped first
+            if let Some(prompt) = prompt {
+                let delivery = match &result {
+                    Ok(()) => Some(Delivery::Delivered),
+                    Err(_) if signal.aborted() => None,
+                    Err(_) => failed,
+                };
+                self.set_delivery(prompt, delivery);
+            }
+
+            // A stopped response was already committed by `stop`
+            if let Err(e) = result
+                && !signal.aborted()
+            {
+                telemetry::track(TelemetryEvent::ErrorShown { source: ErrorSource::Request });
+                self.fail_response(TurnError { kind: Failure::of_request(&e), detail: e });
+            }
+        });
+    }
+
+    /// Stop the response being streamed, keeping whatever arrived as the assistant's message.
+    /// A request still open for review is cancelled: aborting closes the review, and
+    /// `respond` marks its message as not sent.
+    pub fn stop(self) {
+        self.queued.set(None);
+        let Some(controller) = self.controller.get_value() else {
+            return;
+        };
+        controller.abort();
+        log::info!(target: "ui", "Response stopped");
+        if self.something_arrived() {
+            embed::response_finished(&self.commit_response(false));
+        } else {
+            self.clear_response();
+        }
+    }
+
+    /// Resend the earlier user message `id` with new text; what followed it is kept as a branch.
+    pub fn edit(self, id: usize, text: String) -> bool {
+        if text.trim().is_empty() || self.loading.get() {
+            return false;
+        }
+        let Some(at) = self.index_of(id) else {
+            return false;
+        };
+        let quoted = self.messages.with(|m| m[at].quoted.clone());
+        self.forks.update(|f| self.messages.update(|m| fork(m, f, at)));
+        self.send(text, quoted)
+    }
+
+    /// Delete the message `id` from the conversation.
+    pub fn delete(self, id: usize) {
+        if self.loading.get() {
+            return;
+        }
+        if let Some(at) = self.index_of(id) {
+            self.forks.update(|f| self.messages.update(|m| remove(m, f, at)));
+        }
+    }
+
+    /// Delete the messages `ids` from the conversation.
+    pub fn delete_many(self, ids: &[usize]) {
+        if self.loading.get() {
+            return;
+        }
+        let mut at: Vec<usize> = ids.iter().filter_map(|&id| self.index_of(id)).collect();
+        // Last first, so the earlier positions still hold
+        at.sort_unstable_by(|a, b| b.cmp(a));
+        self.forks.update(|f| {
+            self.messages.update(|m| {
+                for at in at {
+                    remove(m, f, at);
+                }
+            });
+        });
+    }
+
+    /// Pin or unpin the message `id`.
+    pub fn toggle_pin(self, id: usize) {
+        self.messages.update(|m| {
+            if let Some(message) = m.iter_mut().find(|m| m.id == id) {
+                message.pinned = !message.pinned;
+            }
+        });
+    }
+
+    /// Add `chart` after the others in the message `id`.
+    pub fn append_chart(self, id: usize, chart: Chart) {
+        self.messages.update(|m| {
+            if let Some(message) = m.iter_mut().find(|m| m.id == id) {
+                message.charts.push(chart);
+            }
+        });
+    }
+
+    /// Put `chart` in place of the one at `index` in the message `id`.
+    pub fn replace_chart(self, id: usize, index: usize, chart: Chart) {
+        self.messages.update(|m| {
+            if let Some(slot) = m.iter_mut().find(|m| m.id == id).and_then(|m| m.charts.get_mut(index)) {
+                *slot = chart;
+            }
+        });
+    }
+
+    /// Give or take back `reaction` on the response `id`.
+    pub fn toggle_reaction(self, id: usize, reaction: Reaction) {
+        let mut given = false;
+        self.messages.update(|m| {
+            if let Some(message) = m.iter_mut().find(|m| m.id == id) {
+                given = !message.reactions.contains(&reaction);
+                if given {
+                    message.reactions.push(reaction);
+                } else {
+                    message.reactions.retain(|&r| r != reaction);
+                }
+            }
+        });
+        if given {
+            telemetry::track(TelemetryEvent::Reacted { reaction });
+        }
+    }
+
+    /// Set the response `id` aside as a branch and return the request that produced it.
+    fn fork_for_regenerate(self, id: usize) -> Option<ChatRequest> {
+        if self.loading.get() {
+            return None;
+        }
+        let at = self.index_of(id).filter(|&at| at > 0)?;
+        if self.messages.with(|m| m[at - 1].role != Role::User) {
+            return None;
+        }
+        self.forks.update(|f| self.messages.update(|m| fork(m, f, at)));
+        Some(self.request_at(at - 1))
+    }
+
+    /// The request for the user message at `at`, with the messages before it as history.
+    fn request_at(self, at: usize) -> ChatRequest {
+        let prompt = self.messages.with(|m| m[at].clone());
+        ChatRequest {
+            message: prompt.content,
+            history: Some(self.messages.with(|m| self.capped_history(&m[..at]))),
+            conversation_id: None,
+            indicators: self.settings.get().indicators,
+            portfolio: self.attached_portfolio.get(),
+            quoted: prompt.quoted,
+            temperature: None,
+            tools: self.enabled_tools(),
+            approve_tools: self.settings.get().approve_tools,
+            partial: None,
+            idempotency_key: new_id(),
+        }
+    }
+
+    /// Send the user message `id` again, exactly as it was, in place of the error it got.
+    /// A message that never got through keeps its idempotency key; one the backend failed
+    /// answering is a new send.
+    pub fn retry(self, id: usize) {
+        if self.loading.get() {
+            return;
+        }
+        let Some(at) = self.index_of(id) else {
+            return;
+        };
+        // Only while nothing but errors followed it, or it would answer out of turn
+        if self.messages.with(|m| m[at + 1..].iter().any(|m| m.error.is_none())) {
+            toast::info("Later messages were sent since; edit this one to send it again");
+            return;
+        }
+        let request = self.request_at(at);
+        let idempotency_key = match self.messages.with(|m| m[at].delivery.clone()) {
+            Some(Delivery::Failed { idempotency_key }) => idempotency_key,
+            _ => request.idempotency_key.clone(),
+        };
+        log::info!(target: "ui", "Retrying a failed message");
+        self.forks.update(|f| {
+            self.messages.update(|m| {
+                while m.len() > at + 1 {
+                    remove(m, f, m.len() - 1);
+                }
+                m[at].delivery = Some(Delivery::Sending);
+            });
+        });
+        self.respond(ChatRequest { idempotency_key, ..request }, Some(id));
+    }
+
+    /// Ask the backend to finish the interrupted response `id`, streaming the rest onto what
+    /// arrived before.
+    pub fn continue_response(self, id: usize) {
+        if self.loading.get() {
+            return;
+        }
+        let Some(at) = self.index_of(id).filter(|&at| at > 0) else {
+            return;
+        };
+        let (interrupted, last) = self.messages.with(|m| (m[at].interrupted, at + 1 == m.len()));
+        if !interrupted || self.messages.with(|m| m[at - 1].role != Role::User) {
+            return;
+        }
+        // Only the latest answer, or it would carry on out of turn
+        if !last {
+            toast::info("Later messages were sent since; regenerate this one instead");
+            return;
+        }
+        log::info!(target: "ui", "Continuing an interrupted response");
+        let partial = self.messages.with(|m| m[at].content.clone());
+        let request = ChatRequest { partial: Some(partial), ..self.request_at(at - 1) };
+        // The partial stays where it is until `respond` sends the request
+        self.respond(request, None);
+    }
+
+    /// Take the interrupted response at the end of `messages` back into the one being
+    /// streamed, so the rest streams on from where it stopped.
+    fn take_partial(self) {
+        let Some(at) = self.messages.with_untracked(|m| {
+            m.len().checked_sub(1).filter(|&at| m[at].interrupted)
+        }) else {
+            return;
+        };
+        let partial = self.messages.with_untracked(|m| m[at].clone());
+        self.forks.update(|f| self.messages.update(|m| remove(m, f, at)));
+        self.current_response.set(partial.content);
+        self.pending_charts.set(partial.charts);
+    }
+
+    /// Ask again for the response `id`, keeping the current one as a branch.
+    pub fn regenerate(self, id: usize) {
+        if let Some(request) = self.fork_for_regenerate(id) {
+            self.respond(request, None);
+        }
+    }
+
+    /// Stream two variants of the response `id` side by side until one is kept.
+    pub fn regenerate_and_compare(self, id: usize) {
+        let Ok(controller) = AbortController::new() else {
+            return;
+        };
+        if let Some(request) = self.fork_for_regenerate(id) {
+            self.loading.set(true);
+            let signal = controller.signal();
+            self.controller.set_value(Some(controller));
+            stream_variants(request, self.compare_panes, self.enabled("charts"), signal);
+        }
+    }
+
+    /// Keep compared pane `index`; the others become branches at the same point.
+    pub fn keep_variant(self, index: usize) {
+        let mut panes = self.compare_panes.get();
+        if index >= panes.len() {
+            return;
+        }
+        let kept = panes.remove(index);
+        let others = panes.len();
+        let at = self.messages.with(Vec::len);
+        let first_id = self.next_id.get();
+        self.forks.update(|f| {
+            self.messages.update(|m| {
+                for (i, pane) in panes.into_iter().enumerate() {
+                    m.push(pane.into_message(first_id + i));
+                    fork(m, f, at);
+                }
+                m.push(kept.into_message(first_id + others));
+            });
+        });
+        self.next_id.set(first_id + others + 1);
+        self.compare_panes.set(Vec::new());
+        self.controller.set_value(None);
+        self.loading.set(false);
+    }
+
+    /// Make branch `index` of the fork at position `at` the active path.
+    pub fn switch_branch(self, at: usize, index: usize) {
+        if self.loading.get() {
+            return;
+        }
+        let mut next = self.next_id.get();
+        self.forks.update(|f| {
+            self.messages.update(|m| next = switch_branch(m, f, at, index, next));
+        });
+        self.next_id.set(next);
+    }
+
+    /// Start an empty conversation, returning false while a response is streaming.
+    pub fn new_conversation(self) -> bool {
+        if self.loading.get() {
+            return false;
+        }
+        // Id first so the autosave effect never pairs the new id with old messages
+        self.conversation_id.set(new_id());
+        self.archived.set(0);
+        self.unseen.set(None);
+        self.messages.set(Vec::new());
+        self.forks.set(Vec::new());
+        self.next_id.set(0);
+        self.attached_portfolio.set(None);
+        true
+    }
+
+    /// Entering or leaving incognito always starts a fresh conversation,
+    /// so incognito messages are never saved and saved ones never leak in
+    pub fn toggle_incognito(self) {
+        if self.new_conversation() {
+            self.incognito.update(|on| *on = !*on);
+        }
+    }
+
+    /// Open a copy of the saved conversation `id`, so an analysis can go a different way
+    /// without touching the original.
+    pub fn duplicate_conversation(self, id: &str) {
+        if self.loading.get() {
+            return;
+        }
+        let Some(original) = self.conversations.with(|c| c.iter().find(|c| c.id == id).cloned())
+        else {
+            return;
+        };
+        spawn_local(async move {
+            let copy_id = new_id();
+            if original.archived > 0 {
+                let copied = match history::load(&original.id, 0, original.archived).await {
+                    Ok(earlier) => history::archive(&copy_id, 0, &earlier).await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = copied {
+                    log::warn!(target: "ui", "Couldn't copy archived messages: {e:?}");
+                    toast::error("Couldn't copy the conversation's earlier messages");
+                    return;
+                }
+            }
+            let copy = Conversation {
+                id: copy_id.clone(),
+                title: format!("{} (copy)", original.title),
+                updated_at: js_sys::Date::now(),
+                archived_at: None,
+                renamed: true,
+                ..original
+            };
+            self.conversations.update(|c| c.insert(0, copy));
+            self.switch_conversation(&copy_id);
+        });
+    }
+
+    /// Replace the current conversation with the saved conversation `id`.
+    pub fn switch_conversation(self, id: &str) {
+        if self.loading.get() {
+            return;
+        }
+        let Some(conversation) =
+            self.conversations.with(|c| c.iter().find(|c| c.id == id).cloned())
+        else {
+            return;
+        };
+        let first_id = self.next_id.get();
+        let restored = conversation.restored_messages(first_id);
+        self.next_id.set(first_id + restored.len());
+        self.messages.set(restored);
+        self.forks.set(conversation.forks);
+        self.archived.set(conversation.archived);
+        self.unseen.set(None);
+        self.attached_portfolio.set(None);
+        self.conversation_id.set(conversation.id);
+        self.incognito.set(false);
+    }
+}
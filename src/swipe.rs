@@ -0,0 +1,121 @@
+use leptos::prelude::{Get, GetUntracked, GetValue, RwSignal, Set, SetValue, StoredValue, With};
+use wasm_bindgen::JsCast;
+
+/// How far a swiped message slides to uncover its actions; the width of `.swipe-actions`
+const REVEAL: f64 = 144.0;
+/// Movement before a touch counts as a swipe or a scroll
+const SLOP: f64 = 10.0;
+/// Parts of a message that handle their own pointer gestures
+const IGNORED: &str = ".chart-container, .sheet-backdrop, .message-edit";
+
+#[derive(Clone, Copy)]
+enum Gesture {
+    /// Down, but not yet moved far enough to tell a swipe from a scroll or a tap
+    Pending { x: f64, y: f64 },
+    /// Moving sideways: the message follows the pointer from where it started
+    Swiping { start_x: f64, from: f64 },
+}
+
+/// Swipe-to-reveal for one message on touch screens: drag it left to uncover quick
+/// actions. Vertical movement is left to the browser (`touch-action: pan-y`), which
+/// cancels the gesture once it starts scrolling.
+#[derive(Clone, Copy)]
+pub struct Swipe {
+    id: usize,
+    /// The message whose actions are uncovered; opening one closes the rest
+    open: RwSignal<Option<usize>>,
+    /// Offset while the message is being dragged
+    drag: RwSignal<Option<f64>>,
+    gesture: StoredValue<Option<Gesture>>,
+}
+
+impl Swipe {
+    pub fn new(id: usize, open: RwSignal<Option<usize>>) -> Self {
+        Swipe { id, open, drag: RwSignal::new(None), gesture: StoredValue::new(None) }
+    }
+
+    fn is_open(self) -> bool {
+        self.open.get() == Some(self.id)
+    }
+
+    pub fn close(self) {
+        if self.open.get_untracked() == Some(self.id) {
+            self.open.set(None);
+        }
+    }
+
+    pub fn dragging(self) -> bool {
+        self.drag.with(Option::is_some)
+    }
+
+    /// Horizontal offset of the message in px, zero or negative
+    pub fn offset(self) -> f64 {
+        self.drag.get().unwrap_or(if self.is_open() { -REVEAL } else { 0.0 })
+    }
+
+    pub fn transform(self) -> String {
+        let offset = self.offset();
+        if offset == 0.0 { String::new() } else { format!("translateX({offset}px)") }
+    }
+
+    pub fn down(self, ev: &web_sys::PointerEvent) {
+        let ignored = ev
+            .target()
+            .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+            .is_some_and(|el| el.closest(IGNORED).ok().flatten().is_some());
+        if ev.pointer_type() == "mouse" || !ev.is_primary() || ignored {
+            return;
+        }
+        let (x, y) = (ev.client_x() as f64, ev.client_y() as f64);
+        self.gesture.set_value(Some(Gesture::Pending { x, y }));
+    }
+
+    pub fn moved(self, ev: &web_sys::PointerEvent) {
+        let (x, y) = (ev.client_x() as f64, ev.client_y() as f64);
+        match self.gesture.get_value() {
+            Some(Gesture::Pending { x: start_x, y: start_y }) => {
+                let (dx, dy) = (x - start_x, y - start_y);
+                if dx.abs() > SLOP && dx.abs() > dy.abs() {
+                    if let Some(target) =
+                        ev.current_target().and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+                    {
+                        let _ = target.set_pointer_capture(ev.pointer_id());
+                    }
+                    let from = self.offset();
+                    self.gesture.set_value(Some(Gesture::Swiping { start_x, from }));
+                    self.drag.set(Some(from));
+                } else if dy.abs() > SLOP {
+                    self.gesture.set_value(None);
+                }
+            }
+            Some(Gesture::Swiping { start_x, from }) => {
+                self.drag.set(Some((from + x - start_x).clamp(-REVEAL, 0.0)));
+            }
+            None => {}
+        }
+    }
+
+    pub fn up(self) {
+        let gesture = self.gesture.get_value();
+        self.gesture.set_value(None);
+        match gesture {
+            Some(Gesture::Swiping { .. }) => {
+                let offset = self.drag.get_untracked().unwrap_or(0.0);
+                self.drag.set(None);
+                if offset < -REVEAL / 2.0 {
+                    self.open.set(Some(self.id));
+                } else {
+                    self.close();
+                }
+            }
+            // A tap on a swiped message slides it back
+            Some(Gesture::Pending { .. }) => self.close(),
+            None => {}
+        }
+    }
+
+    pub fn cancel(self) {
+        self.gesture.set_value(None);
+        self.drag.set(None);
+    }
+}
@@ -0,0 +1,197 @@
+use leptos::prelude::{
+    component, event_target_value, signal, view, Callable, Callback, Get, GetUntracked, IntoView,
+    Memo, ReadSignal, RwSignal, Set, With,
+};
+use leptos::tachys::prelude::*;
+use leptos::task::spawn_local;
+use wxve_chat_client::ChartParams;
+
+use crate::conversations::Conversation;
+use crate::currency::{FxRates, Price};
+use crate::watchlist::format_change;
+use crate::{
+    extract_symbols, fetch_symbol_stats, render_chart, Chart, ChartView, Settings, SymbolStats,
+};
+
+const RECENT_CONVERSATIONS: usize = 5;
+
+/// Compact large numbers, e.g. `2.91T`, `48.2M`.
+pub fn format_large(value: f64) -> String {
+    match value.abs() {
+        v if v >= 1e12 => format!("{:.2}T", value / 1e12),
+        v if v >= 1e9 => format!("{:.2}B", value / 1e9),
+        v if v >= 1e6 => format!("{:.1}M", value / 1e6),
+        _ => format!("{value:.0}"),
+    }
+}
+
+fn mentions(conversation: &Conversation, symbol: &str) -> bool {
+    conversation
+        .messages
+        .iter()
+        .any(|m| extract_symbols(&m.content).iter().any(|s| s == symbol))
+}
+
+/// `low - high` for today's session, when the backend reports both.
+pub fn day_range(stats: &SymbolStats) -> Option<String> {
+    Some(format!("{:.2} - {:.2}", stats.day_low?, stats.day_high?))
+}
+
+/// Stat rows that the backend actually provided for this symbol.
+fn stat_rows(stats: &SymbolStats) -> Vec<(&'static str, String)> {
+    [
+        ("Market cap", stats.market_cap.map(format_large)),
+        ("P/E", stats.pe_ratio.map(|pe| format!("{pe:.1}"))),
+        ("Day range", day_range(stats)),
+        ("52w high", stats.high_52w.map(|h| format!("{h:.2}"))),
+        ("52w low", stats.low_52w.map(|l| format!("{l:.2}"))),
+        ("Volume", stats.volume.map(format_large)),
+    ]
+    .into_iter()
+    .filter_map(|(label, value)| value.map(|v| (label, v)))
+    .collect()
+}
+
+/// `/symbol/:ticker` - latest chart, key stats and past conversations for one symbol.
+#[component]
+pub fn SymbolPage(
+    symbol: String,
+    settings: RwSignal<Settings>,
+    rates: RwSignal<FxRates>,
+    dark_mode: ReadSignal<bool>,
+    conversations: RwSignal<Vec<Conversation>>,
+    on_pin: Callback<String>,
+    on_open: Callback<String>,
+    on_ask: Callback<String>,
+) -> impl IntoView {
+    let (stats, set_stats) = signal(None::<SymbolStats>);
+    let (chart, set_chart) = signal(None::<Chart>);
+    let (question, set_question) = signal(String::new());
+
+    {
+        let symbol = symbol.clone();
+        spawn_local(async move {
+            match fetch_symbol_stats(&symbol).await {
+                Ok(fetched) => set_stats.set(Some(fetched)),
+                Err(e) => log::error!(target: "transport", "Stats fetch failed: {e}"),
+            }
+        });
+    }
+    {
+        let symbol = symbol.clone();
+        let indicators = settings.get_untracked().indicators;
+        let params = ChartParams::default();
+        spawn_local(async move {
+            match render_chart(symbol.clone(), indicators.clone(), params.clone()).await {
+                Ok(html) => set_chart.set(Some(Chart::new(symbol, html, indicators, params))),
+                Err(e) => log::error!(target: "ui", "Chart render failed: {e}"),
+            }
+        });
+    }
+
+    let recent = {
+        let symbol = symbol.clone();
+        Memo::new(move |_| {
+            conversations.with(|c| {
+                c.iter()
+                    .filter(|conversation| mentions(conversation, &symbol))
+                    .take(RECENT_CONVERSATIONS)
+                    .map(|conversation| (conversation.id.clone(), conversation.title.clone()))
+                    .collect::<Vec<_>>()
+            })
+        })
+    };
+
+    let ask = {
+        let symbol = symbol.clone();
+        move || {
+            let text = question.get();
+            let text = text.trim();
+            if text.is_empty() {
+                return;
+            }
+            // Seed the new conversation with the symbol unless the question already names it
+            let message = if extract_symbols(text).contains(&symbol) {
+                text.to_string()
+            } else {
+                format!("{symbol}: {text}")
+            };
+            on_ask.run(message);
+        }
+    };
+
+    view! {
+        <div class="page symbol-page">
+            <div class="page-title">
+                {symbol.clone()}
+                {move || stats.get().map(|s| {
+                    let (change, class) = format_change(s.change_percent);
+                    view! {
+                        <span class="symbol-name">{s.name}</span>
+                        <Price amount=s.price currency=s.currency settings=settings rates=rates/>
+                        <span class=class>{change}</span>
+                    }
+                })}
+            </div>
+            <div class="symbol-layout">
+                <div class="symbol-chart">
+                    {move || match chart.get() {
+                        Some(chart) => view! {
+                            <ChartView chart=chart settings=settings dark_mode=dark_mode on_pin=on_pin/>
+                        }
+                        .into_any(),
+                        None => view! { <span class="spinner"></span> }.into_any(),
+                    }}
+                </div>
+                <div class="symbol-side">
+                    <dl class="symbol-stats">
+                        {move || stats.get().map(|s| {
+                            stat_rows(&s)
+                                .into_iter()
+                                .map(|(label, value)| view! { <dt>{label}</dt><dd>{value}</dd> })
+                                .collect::<Vec<_>>()
+                        })}
+                    </dl>
+                    <div class="panel-header">"Recent conversations"</div>
+                    {move || {
+                        let recent = recent.get();
+                        if recent.is_empty() {
+                            view! { <p class="panel-empty">"No conversations yet."</p> }.into_any()
+                        } else {
+                            view! {
+                                <ul class="panel-items">
+                                    {recent.into_iter().map(|(id, title)| view! {
+                                        <li>
+                                            <button
+                                                class="symbol-conversation"
+                                                on:click=move |_| on_open.run(id.clone())
+                                            >
+                                                {title}
+                                            </button>
+                                        </li>
+                                    }).collect::<Vec<_>>()}
+                                </ul>
+                            }
+                            .into_any()
+                        }
+                    }}
+                </div>
+            </div>
+            <form
+                class="input-box symbol-composer"
+                on:submit=move |ev| {
+                    ev.prevent_default();
+                    ask();
+                }
+            >
+                <input
+                    type="text"
+                    placeholder=format!("Ask Xve about {symbol}...")
+                    prop:value=move || question.get()
+                    on:input=move |ev| set_question.set(event_target_value(&ev))
+                />
+                <button type="submit">"Ask"</button>
+            </form>
+        </div>
+    }
+}
@@ -0,0 +1,115 @@
+use std::cell::{Cell, RefCell};
+use std::time::Duration;
+
+use leptos::prelude::{set_interval, Effect, RwSignal, With};
+use leptos::task::spawn_local;
+use serde::Serialize;
+
+use crate::reactions::Reaction;
+use crate::reporting::breadcrumb;
+use crate::{on_visibility_change, post_json, Settings};
+
+/// Pending events are sent once this many pile up, or every `FLUSH_INTERVAL`
+const BATCH_SIZE: usize = 20;
+const FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Everything telemetry can record. Events carry counts and timings only, never
+/// anything the user wrote: no message text, symbols or portfolio data.
+#[derive(Clone, Serialize)]
+#[serde(tag = "name", rename_all = "snake_case")]
+pub enum TelemetryEvent {
+    MessageSent { quoted: bool, history_len: usize },
+    ResponseCompleted { duration_ms: u64, charts: usize },
+    ChartRendered,
+    ErrorShown { source: ErrorSource },
+    /// A reaction given to a response, as a signal of its quality
+    Reacted { reaction: Reaction },
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorSource {
+    /// An `error` chunk from the chat stream
+    Stream,
+    /// The request itself failed
+    Request,
+    /// Any error toast
+    Toast,
+}
+
+#[derive(Serialize)]
+struct Envelope {
+    #[serde(flatten)]
+    event: TelemetryEvent,
+    /// Milliseconds since the epoch
+    at: f64,
+}
+
+#[derive(Serialize)]
+struct Batch {
+    events: Vec<Envelope>,
+}
+
+thread_local! {
+    /// Mirrors `Settings::telemetry`; events are dropped while it's off
+    static ENABLED: Cell<bool> = const { Cell::new(false) };
+    static PENDING: RefCell<Vec<Envelope>> = const { RefCell::new(Vec::new()) };
+}
+
+impl TelemetryEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            TelemetryEvent::MessageSent { .. } => "message_sent",
+            TelemetryEvent::ResponseCompleted { .. } => "response_completed",
+            TelemetryEvent::ChartRendered => "chart_rendered",
+            TelemetryEvent::ErrorShown { .. } => "error_shown",
+            TelemetryEvent::Reacted { .. } => "reacted",
+        }
+    }
+}
+
+/// Record `event` if the user opted in. Every event is also an error-report breadcrumb.
+pub fn track(event: TelemetryEvent) {
+    breadcrumb("event", event.name());
+    if !ENABLED.with(Cell::get) {
+        return;
+    }
+    let pending = PENDING.with(|p| {
+        let mut p = p.borrow_mut();
+        p.push(Envelope { event, at: js_sys::Date::now() });
+        p.len()
+    });
+    if pending >= BATCH_SIZE {
+        flush();
+    }
+}
+
+/// Send pending events. A failed batch is dropped rather than retried.
+fn flush() {
+    let events = PENDING.with(|p| std::mem::take(&mut *p.borrow_mut()));
+    if events.is_empty() {
+        return;
+    }
+    spawn_local(async move {
+        if let Err(e) = post_json("/telemetry", &Batch { events }).await {
+            log::warn!(target: "telemetry", "Batch dropped: {e}");
+        }
+    });
+}
+
+/// Follow the opt-in setting and flush periodically and whenever the tab is hidden.
+pub fn use_telemetry(settings: RwSignal<Settings>) {
+    Effect::new(move |_| {
+        let enabled = settings.with(|s| s.telemetry);
+        ENABLED.with(|e| e.set(enabled));
+        if !enabled {
+            PENDING.with(|p| p.borrow_mut().clear());
+        }
+    });
+    set_interval(flush, FLUSH_INTERVAL);
+    on_visibility_change(|hidden| {
+        if hidden {
+            flush();
+        }
+    });
+}
@@ -0,0 +1,105 @@
+use std::time::Duration;
+
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{ReadableStreamDefaultController, Response};
+
+use crate::sleep;
+
+// Once for the whole test binary: every browser test and benchmark runs in headless Chrome
+wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+/// How a mocked response body ends once its pieces have been read
+#[derive(Clone, Copy)]
+pub enum End {
+    Close,
+    /// The connection drops, as when the network fails mid-stream
+    Fail,
+    /// Nothing more ever arrives; only an abort ends the read
+    Hang,
+}
+
+/// A mocked 200 response: `pieces` arrive one per read, followed by `end`.
+#[derive(Clone)]
+pub struct Body {
+    pub pieces: Vec<String>,
+    pub end: End,
+}
+
+impl Body {
+    pub fn new(pieces: &[&str], end: End) -> Self {
+        Body { pieces: pieces.iter().map(|p| p.to_string()).collect(), end }
+    }
+
+    /// One SSE event per chunk, each in its own read.
+    pub fn events(chunks: &[serde_json::Value], end: End) -> Self {
+        Body { pieces: chunks.iter().map(|c| format!("data: {c}\n\n")).collect(), end }
+    }
+
+    fn into_response(self, request: web_sys::Request) -> Response {
+        let Body { pieces, end } = self;
+        let mut remaining = pieces.into_iter();
+        let pull = Closure::<dyn FnMut(ReadableStreamDefaultController) -> js_sys::Promise>::new(
+            move |controller: ReadableStreamDefaultController| {
+                match (remaining.next(), end) {
+                    (Some(piece), _) => {
+                        let chunk = js_sys::Uint8Array::from(piece.as_bytes());
+                        let _ = controller.enqueue_with_chunk(&chunk);
+                    }
+                    (None, End::Close) => {
+                        let _ = controller.close();
+                    }
+                    (None, End::Fail) => {
+                        controller.error_with_e(&JsValue::from_str("TypeError: network error"));
+                    }
+                    (None, End::Hang) => {
+                        let abort = move || {
+                            controller.error_with_e(&JsValue::from_str("AbortError: aborted"));
+                        };
+                        let signal = request.signal();
+                        if signal.aborted() {
+                            abort();
+                        } else {
+                            let onabort = Closure::once_into_js(abort);
+                            signal.set_onabort(Some(onabort.unchecked_ref()));
+                        }
+                        return js_sys::Promise::new(&mut |_, _| {});
+                    }
+                }
+                js_sys::Promise::resolve(&JsValue::UNDEFINED)
+            },
+        );
+        let source = js_sys::Object::new();
+        let _ = js_sys::Reflect::set(&source, &"pull".into(), pull.as_ref());
+        pull.forget();
+        let stream = web_sys::ReadableStream::new_with_underlying_source(&source).unwrap();
+        Response::new_with_opt_readable_stream(Some(&stream)).unwrap()
+    }
+}
+
+/// Replace `window.fetch`: `respond` gets each request's URL and returns the body to
+/// answer with, or `None` to fail the request. Aborting a hanging body fails it like a
+/// real fetch.
+pub fn mock_fetch(mut respond: impl FnMut(&str) -> Option<Body> + 'static) {
+    let fetch = Closure::<dyn FnMut(JsValue) -> js_sys::Promise>::new(move |request: JsValue| {
+        let request: web_sys::Request = request.unchecked_into();
+        match respond(&request.url()) {
+            Some(body) => js_sys::Promise::resolve(&JsValue::from(body.into_response(request))),
+            None => js_sys::Promise::reject(&JsValue::from_str("TypeError: Failed to fetch")),
+        }
+    });
+    let window = web_sys::window().unwrap();
+    js_sys::Reflect::set(&window, &"fetch".into(), fetch.as_ref()).unwrap();
+    fetch.forget();
+}
+
+/// Poll `condition` until it holds, failing the test after a few seconds.
+pub async fn wait_until(what: &str, mut condition: impl FnMut() -> bool) {
+    for _ in 0..200 {
+        if condition() {
+            return;
+        }
+        sleep(Duration::from_millis(20)).await;
+    }
+    panic!("timed out waiting for {what}");
+}
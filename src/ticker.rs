@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+
+use leptos::prelude::{
+    component, on_cleanup, view, Effect, Get, GetValue, IntoView, Memo, RwSignal, SetValue,
+    StoredValue, Update, With,
+};
+use leptos::tachys::prelude::*;
+use leptos::task::spawn_local;
+use web_sys::AbortController;
+
+use crate::connection::Connection;
+use crate::currency::{FxRates, Price};
+use crate::watchlist::format_change;
+use crate::{keep_subscribed, subscribe_quotes, Quote, Settings};
+
+/// Marquee of live watchlist quotes, fed by a streaming quotes subscription.
+#[component]
+pub fn Ticker(
+    settings: RwSignal<Settings>,
+    quotes: RwSignal<HashMap<String, Quote>>,
+    rates: RwSignal<FxRates>,
+    connection: RwSignal<Connection>,
+) -> impl IntoView {
+    let symbols = Memo::new(move |_| {
+        settings.with(|s| if s.show_ticker { s.watchlist.clone() } else { Vec::new() })
+    });
+    let controller = StoredValue::new(None::<AbortController>);
+
+    let stop = move || {
+        if let Some(controller) = controller.get_value() {
+            controller.abort();
+        }
+        controller.set_value(None);
+    };
+
+    // Resubscribe whenever the symbol set changes
+    Effect::new(move |_| {
+        let symbols = symbols.get();
+        stop();
+        if symbols.is_empty() {
+            return;
+        }
+        let Ok(abort) = AbortController::new() else {
+            return;
+        };
+        let signal = abort.signal();
+        controller.set_value(Some(abort));
+
+        spawn_local(async move {
+            keep_subscribed("Quote", connection, &signal, |opened| {
+                subscribe_quotes(&symbols, &signal, opened, |quote| {
+                    quotes.update(|q| {
+                        q.insert(quote.symbol.clone(), quote);
+                    });
+                })
+            })
+            .await;
+        });
+    });
+    on_cleanup(stop);
+
+    let items = move || {
+        let symbols = symbols.get();
+        quotes.with(|q| {
+            symbols
+                .iter()
+                .map(|symbol| {
+                    let (price, change, class) = match q.get(symbol) {
+                        Some(quote) => {
+                            let (change, class) = format_change(quote.change_percent);
+                            let price = view! {
+                                <Price
+                                    amount=quote.price
+                                    currency=quote.currency.clone()
+                                    settings=settings
+                                    rates=rates
+                                />
+                            };
+                            (price.into_any(), change, class)
+                        }
+                        None => {
+                            let price = view! { <span class="price">"—"</span> };
+                            (price.into_any(), String::new(), "change")
+                        }
+                    };
+                    view! {
+                        <span class="ticker-item">
+                            <span class="ticker-symbol">{symbol.clone()}</span>
+                            {price}
+                            <span class=class>{change}</span>
+                        </span>
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+    };
+
+    move || {
+        (!symbols.with(Vec::is_empty)).then(|| {
+            // Render the items twice so the marquee loops seamlessly
+            view! {
+                <div class="ticker">
+                    <div class="ticker-track">{items}{items}</div>
+                </div>
+            }
+        })
+    }
+}
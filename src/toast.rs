@@ -0,0 +1,115 @@
+use std::cell::Cell;
+use std::time::Duration;
+
+use leptos::prelude::{
+    component, on_cleanup, set_timeout_with_handle, view, For, IntoView, RwSignal, Update, With,
+};
+use leptos::tachys::prelude::*;
+
+use crate::new_id;
+use crate::telemetry::{self, ErrorSource, TelemetryEvent};
+
+/// Toasts beyond this many wait in the queue until one is dismissed
+const MAX_VISIBLE: usize = 3;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Level {
+    Info,
+    Success,
+    Error,
+}
+
+impl Level {
+    fn class(self) -> &'static str {
+        match self {
+            Level::Info => "toast info",
+            Level::Success => "toast success",
+            Level::Error => "toast error",
+        }
+    }
+
+    /// Errors stay up longer so they can be read
+    fn duration(self) -> Duration {
+        match self {
+            Level::Info | Level::Success => Duration::from_secs(4),
+            Level::Error => Duration::from_secs(8),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Toast {
+    id: String,
+    level: Level,
+    message: String,
+}
+
+thread_local! {
+    /// Registered by the mounted `Toaster` so any module can raise toasts
+    static QUEUE: Cell<Option<RwSignal<Vec<Toast>>>> = const { Cell::new(None) };
+}
+
+/// Queue a toast. A no-op until `Toaster` is mounted.
+pub fn toast(level: Level, message: impl Into<String>) {
+    if let Some(queue) = QUEUE.with(Cell::get) {
+        let toast = Toast { id: new_id(), level, message: message.into() };
+        queue.update(|q| q.push(toast));
+    }
+}
+
+pub fn info(message: impl Into<String>) {
+    toast(Level::Info, message);
+}
+
+pub fn success(message: impl Into<String>) {
+    toast(Level::Success, message);
+}
+
+pub fn error(message: impl Into<String>) {
+    telemetry::track(TelemetryEvent::ErrorShown { source: ErrorSource::Toast });
+    toast(Level::Error, message);
+}
+
+fn dismiss(queue: RwSignal<Vec<Toast>>, id: &str) {
+    queue.update(|q| q.retain(|t| t.id != id));
+}
+
+/// Host for toasts raised with `toast()`; mount once near the root.
+#[component]
+pub fn Toaster() -> impl IntoView {
+    let queue = RwSignal::new(Vec::<Toast>::new());
+    QUEUE.with(|q| q.set(Some(queue)));
+    on_cleanup(|| QUEUE.with(|q| q.set(None)));
+
+    view! {
+        <div class="toasts" aria-live="polite">
+            <For
+                each=move || queue.with(|q| q.iter().take(MAX_VISIBLE).cloned().collect::<Vec<_>>())
+                key=|toast| toast.id.clone()
+                children=move |toast| {
+                    // The timer starts once the toast is actually on screen
+                    let id = toast.id.clone();
+                    if let Ok(handle) = set_timeout_with_handle(
+                        move || dismiss(queue, &id),
+                        toast.level.duration(),
+                    ) {
+                        on_cleanup(move || handle.clear());
+                    }
+                    let id = toast.id.clone();
+                    view! {
+                        <div class=toast.level.class() role="status">
+                            <span>{toast.message}</span>
+                            <button
+                                class="toast-dismiss"
+                                title="Dismiss"
+                                on:click=move |_| dismiss(queue, &id)
+                            >
+                                "×"
+                            </button>
+                        </div>
+                    }
+                }
+            />
+        </div>
+    }
+}
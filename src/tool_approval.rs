@@ -0,0 +1,63 @@
+use leptos::prelude::{component, view, Get, GetUntracked, IntoView, Set};
+use leptos::tachys::prelude::*;
+use leptos::task::spawn_local;
+use serde::Serialize;
+
+use crate::state::{use_app_state, AppState};
+use crate::{post_json, toast};
+
+/// A tool call the backend is holding until the user decides
+#[derive(Clone, PartialEq)]
+pub struct ToolRequest {
+    pub id: String,
+    pub name: String,
+    pub input: Option<String>,
+}
+
+/// Body of `POST /tools/approval`
+#[derive(Serialize)]
+struct Decision<'a> {
+    id: &'a str,
+    approved: bool,
+}
+
+/// Tell the backend whether the tool call `id` may run.
+pub fn send_decision(id: String, approved: bool) {
+    spawn_local(async move {
+        if let Err(e) = post_json("/tools/approval", &Decision { id: &id, approved }).await {
+            log::error!(target: "transport", "Tool decision not sent: {e}");
+            toast::error("Couldn't send your decision; stop the response and try again");
+        }
+    });
+}
+
+/// Answer the tool call waiting in `state.tool_request`.
+fn decide(state: AppState, approved: bool) {
+    let Some(request) = state.tool_request.get_untracked() else {
+        return;
+    };
+    state.tool_request.set(None);
+    log::info!(target: "ui", "Tool {} {}", request.name, if approved { "approved" } else { "denied" });
+    send_decision(request.id, approved);
+}
+
+/// "Xve wants to use …" with Allow and Deny, in the response being streamed, while a tool
+/// call waits for approval.
+#[component]
+pub fn ToolApproval() -> impl IntoView {
+    let state = use_app_state();
+    move || {
+        state.tool_request.get().map(|request| view! {
+            <div class="tool-approval" role="alertdialog" aria-label="Approve tool">
+                <p>"Xve wants to use " <code>{request.name}</code></p>
+                {request.input.map(|input| view! { <pre class="tool-input">{input}</pre> })}
+                <div class="dialog-actions">
+                    <button class="dialog-button" on:click=move |_| decide(state, false)>"Deny"</button>
+                    <button class="dialog-button primary" on:click=move |_| decide(state, true)>
+                        "Allow"
+                    </button>
+                </div>
+            </div>
+        })
+    }
+}
@@ -0,0 +1,109 @@
+use leptos::prelude::{
+    component, event_target_checked, view, For, Get, IntoView, RwSignal, Set, Update, With,
+};
+use leptos::tachys::prelude::*;
+use leptos::task::spawn_local;
+use serde::Deserialize;
+
+use crate::{fetch_tools, Settings};
+
+/// A tool the model can call while answering, as listed by `/tools`
+#[derive(Clone, PartialEq, Deserialize)]
+pub struct Tool {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+}
+
+#[derive(Deserialize)]
+pub struct ToolsResponse {
+    pub tools: Vec<Tool>,
+}
+
+/// The backend's tools, fetched once at startup; empty until then or if it fails.
+pub fn use_tools() -> RwSignal<Vec<Tool>> {
+    let tools = RwSignal::new(Vec::new());
+    spawn_local(async move {
+        match fetch_tools().await {
+            Ok(fetched) => tools.set(fetched),
+            Err(e) => log::error!(target: "transport", "Tools fetch failed: {e}"),
+        }
+    });
+    tools
+}
+
+/// `ChatRequest.tools`: the tools left on, or `None` (all of them) when none are switched off.
+/// An allow-list can only be built from a known list, so until `/tools` answers every tool
+/// stays allowed.
+pub fn enabled_tools(available: &[Tool], settings: &Settings) -> Option<Vec<String>> {
+    if settings.disabled_tools.is_empty() || available.is_empty() {
+        return None;
+    }
+    let enabled = available
+        .iter()
+        .filter(|tool| !settings.disabled_tools.contains(&tool.name))
+        .map(|tool| tool.name.clone())
+        .collect();
+    Some(enabled)
+}
+
+/// Tools section of the settings dialog: which tools the model may call, and whether it
+/// asks first (see `tool_approval.rs`).
+#[component]
+pub fn ToolSettings(settings: RwSignal<Settings>, tools: RwSignal<Vec<Tool>>) -> impl IntoView {
+    let toggle = move |name: String, on: bool| {
+        settings.update(|s| {
+            s.disabled_tools.retain(|t| *t != name);
+            if !on {
+                s.disabled_tools.push(name);
+            }
+        });
+    };
+
+    view! {
+        <section class="settings-section">
+            <h3>"Tools"</h3>
+            <p class="settings-hint">"Tools Xve may use while answering. Switched-off ones stay off in every conversation."</p>
+            <label class="settings-row">
+                <input
+                    type="checkbox"
+                    prop:checked=move || settings.with(|s| s.approve_tools)
+                    on:change=move |ev| {
+                        let on = event_target_checked(&ev);
+                        settings.update(|s| s.approve_tools = on);
+                    }
+                />
+                "Ask before each tool runs"
+            </label>
+            {move || tools.with(Vec::is_empty).then(|| view! {
+                <p class="settings-hint">"The list of tools couldn't be loaded."</p>
+            })}
+            <For
+                each=move || tools.get()
+                key=|tool| tool.name.clone()
+                children=move |tool| {
+                    let Tool { name, description } = tool;
+                    let checked = {
+                        let name = name.clone();
+                        move || settings.with(|s| !s.disabled_tools.contains(&name))
+                    };
+                    let change = {
+                        let name = name.clone();
+                        move |ev| toggle(name.clone(), event_target_checked(&ev))
+                    };
+                    view! {
+                        <label class="settings-row">
+                            <input type="checkbox" prop:checked=checked on:change=change/>
+                            <span>
+                                <code>{name}</code>
+                                {(!description.is_empty()).then(|| view! {
+                                    <span class="tool-description">{description}</span>
+                                })}
+                            </span>
+                        </label>
+                    }
+                }
+            />
+        </section>
+    }
+}
@@ -0,0 +1,155 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use leptos::prelude::Callback;
+use serde_json::{json, Value};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+use crate::test_support::{mock_fetch, Body, End};
+use crate::{send_message, subscribe_quotes, Quote, StreamChunk};
+
+/// Send a chat message through the mocked fetch, returning the chunks received as JSON.
+async fn chat(pieces: &[&str], end: End) -> (Result<(), String>, Vec<Value>) {
+    let body = Body::new(pieces, end);
+    mock_fetch(move |_| Some(body.clone()));
+    let chunks = Rc::new(RefCell::new(Vec::new()));
+    let received = chunks.clone();
+    let result = send_message(json!({ "message": "hi" }), "test", None, move |chunk: StreamChunk| {
+        received.borrow_mut().push(serde_json::to_value(&chunk).unwrap());
+    })
+    .await;
+    let chunks = chunks.borrow().clone();
+    (result, chunks)
+}
+
+#[wasm_bindgen_test]
+async fn reassembles_events_split_across_reads() {
+    let (result, chunks) = chat(
+        &[
+            "data: {\"type\":\"text\",\"con",
+            "tent\":\"Hel\"}\n",
+            "\ndata: {\"type\":\"text\",\"content\":\"lo\"}\n\nda",
+            "ta: {\"type\":\"done\"}\n\n",
+        ],
+        End::Close,
+    )
+    .await;
+    assert_eq!(result, Ok(()));
+    assert_eq!(
+        chunks,
+        [
+            json!({ "type": "text", "content": "Hel" }),
+            json!({ "type": "text", "content": "lo" }),
+            json!({ "type": "done" }),
+        ]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn skips_comments_and_unparseable_payloads() {
+    let (result, chunks) = chat(
+        &[
+            ": keep-alive\n\nevent: message\ndata: not json\n\n",
+            "data: {\"type\":\"unknown\"}\n\ndata: {\"type\":\"text\",\"content\":\"ok\"}\n\n",
+            "data: {\"type\":\"done\"}\n\n",
+        ],
+        End::Close,
+    )
+    .await;
+    assert_eq!(result, Ok(()));
+    assert_eq!(chunks, [json!({ "type": "text", "content": "ok" }), json!({ "type": "done" })]);
+}
+
+#[wasm_bindgen_test]
+async fn delivers_error_chunks_and_keeps_reading() {
+    let (result, chunks) = chat(
+        &[
+            "data: {\"type\":\"error\",\"message\":\"rate limited\"}\n\n",
+            "data: {\"type\":\"done\"}\n\n",
+        ],
+        End::Close,
+    )
+    .await;
+    assert_eq!(result, Ok(()));
+    assert_eq!(
+        chunks,
+        [json!({ "type": "error", "message": "rate limited" }), json!({ "type": "done" })]
+    );
+}
+
+#[wasm_bindgen_test]
+async fn stops_reading_at_done() {
+    // The body never ends, so this only returns if `done` stops the read
+    let (result, chunks) = chat(
+        &["data: {\"type\":\"done\"}\n\ndata: {\"type\":\"text\",\"content\":\"late\"}\n\n"],
+        End::Hang,
+    )
+    .await;
+    assert_eq!(result, Ok(()));
+    assert_eq!(chunks, [json!({ "type": "done" })]);
+}
+
+#[wasm_bindgen_test]
+async fn ends_cleanly_without_done() {
+    let (result, chunks) =
+        chat(&["data: {\"type\":\"text\",\"content\":\"partial\"}\n\n"], End::Close).await;
+    assert_eq!(result, Ok(()));
+    assert_eq!(chunks, [json!({ "type": "text", "content": "partial" })]);
+}
+
+#[wasm_bindgen_test]
+async fn dropped_connection_is_an_error_after_earlier_chunks() {
+    let (result, chunks) =
+        chat(&["data: {\"type\":\"text\",\"content\":\"Hel\"}\n\n"], End::Fail).await;
+    assert!(result.unwrap_err().contains("network error"));
+    assert_eq!(chunks, [json!({ "type": "text", "content": "Hel" })]);
+}
+
+#[wasm_bindgen_test]
+async fn abort_ends_a_subscription() {
+    let body = Body::new(
+        &["data: {\"symbol\":\"AAPL\",\"price\":190.5,\"change_percent\":1.2}\n\n"],
+        End::Hang,
+    );
+    mock_fetch(move |_| Some(body.clone()));
+    let controller = web_sys::AbortController::new().unwrap();
+    let quotes = Rc::new(RefCell::new(Vec::<Quote>::new()));
+    let received = quotes.clone();
+    let abort = controller.clone();
+    let result = subscribe_quotes(
+        &["AAPL".to_string()],
+        &controller.signal(),
+        Callback::new(|()| {}),
+        move |quote| {
+            received.borrow_mut().push(quote);
+            abort.abort();
+        },
+    )
+    .await;
+    assert!(result.unwrap_err().contains("AbortError"));
+    let quotes = quotes.borrow();
+    assert_eq!(quotes.len(), 1);
+    assert_eq!(quotes[0].symbol, "AAPL");
+    assert_eq!(quotes[0].currency, "USD");
+}
+
+#[wasm_bindgen_test]
+async fn no_chunks_after_a_stop() {
+    // Both events arrive in one read, so only the abort check keeps the second out
+    let body = Body::new(
+        &["data: {\"type\":\"text\",\"content\":\"Hel\"}\n\ndata: {\"type\":\"text\",\"content\":\"lo\"}\n\n"],
+        End::Hang,
+    );
+    mock_fetch(move |_| Some(body.clone()));
+    let controller = web_sys::AbortController::new().unwrap();
+    let chunks = Rc::new(RefCell::new(Vec::new()));
+    let received = chunks.clone();
+    let abort = controller.clone();
+    let result = send_message(json!({ "message": "hi" }), "test", Some(&controller.signal()), move |chunk| {
+        received.borrow_mut().push(serde_json::to_value(&chunk).unwrap());
+        abort.abort();
+    })
+    .await;
+    assert_eq!(result, Ok(()));
+    assert_eq!(*chunks.borrow(), [json!({ "type": "text", "content": "Hel" })]);
+}
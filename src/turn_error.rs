@@ -0,0 +1,144 @@
+use leptos::prelude::{component, view, Get, IntoView, Memo, With};
+use leptos::tachys::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::state::use_app_state;
+use crate::{reporting, toast, Role};
+
+/// What kind of failure ended a turn, which decides what the user is offered
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Failure {
+    /// The connection dropped, or the backend was busy (429, 502-504); trying again may work
+    Transient,
+    /// The backend didn't accept who we are (401, 403)
+    Auth,
+    /// The backend failed answering: other statuses and `error` chunks
+    Server,
+}
+
+impl Failure {
+    /// Sort an error from `fetch()`: `HTTP <status>`, or the browser's own error when the
+    /// request never got an answer.
+    pub fn of_request(error: &str) -> Failure {
+        match error.strip_prefix("HTTP ").and_then(|status| status.parse::<u16>().ok()) {
+            None => Failure::Transient,
+            Some(401 | 403) => Failure::Auth,
+            Some(408 | 429 | 502 | 503 | 504) => Failure::Transient,
+            Some(_) => Failure::Server,
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Failure::Transient => "Couldn't reach Xve",
+            Failure::Auth => "Your session has expired",
+            Failure::Server => "Xve ran into a problem",
+        }
+    }
+
+    fn hint(self) -> &'static str {
+        match self {
+            Failure::Transient => "This is usually brief; try again in a moment.",
+            Failure::Auth => "Sign in again to carry on; the conversation is kept.",
+            Failure::Server => "Trying again may help. If it keeps happening, let us know.",
+        }
+    }
+}
+
+/// Why a turn has no answer, saved on the assistant message in its place
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TurnError {
+    pub kind: Failure,
+    pub detail: String,
+}
+
+/// An error in the conversation where the answer would be, with what can be done about it:
+/// Retry, Sign in again (a reload, so the login in front of the app runs) or Report.
+#[component]
+pub fn TurnErrorBanner(id: usize, error: TurnError) -> impl IntoView {
+    let state = use_app_state();
+    // Retry sends the message this failed to answer
+    let prompt = Memo::new(move |_| {
+        let at = state.index_of(id)?.checked_sub(1)?;
+        state.messages.with(|m| Some(m[at].id).filter(|_| m[at].role == Role::User))
+    });
+    let request_id = state.messages.with(|m| {
+        let meta = m.iter().find(|m| m.id == id)?.meta.as_ref()?;
+        Some(meta.request_id.clone())
+    });
+    let TurnError { kind, detail } = error;
+    let report = {
+        let detail = detail.clone();
+        move |_| {
+            let request = request_id.as_deref().unwrap_or("unknown");
+            let message = format!("{kind:?} turn error (request {request}): {detail}");
+            if reporting::user_report(&message) {
+                toast::info("Reported, thanks");
+            } else {
+                toast::error("Couldn't send the report");
+            }
+        }
+    };
+
+    view! {
+        <div class="turn-error" role="alert">
+            <strong>{kind.title()}</strong>
+            <p>{kind.hint()}</p>
+            <p class="turn-error-detail">{detail}</p>
+            <div class="dialog-actions">
+                {(kind == Failure::Server).then(|| view! {
+                    <button class="dialog-button" on:click=report>"Report"</button>
+                })}
+                {match kind {
+                    Failure::Auth => view! {
+                        <button
+                            class="dialog-button primary"
+                            on:click=|_| {
+                                if let Some(window) = web_sys::window() {
+                                    let _ = window.location().reload();
+                                }
+                            }
+                        >
+                            "Sign in again"
+                        </button>
+                    }
+                    .into_any(),
+                    Failure::Transient | Failure::Server => view! {
+                        <button
+                            class="dialog-button primary"
+                            disabled=move || state.loading.get() || prompt.with(Option::is_none)
+                            on:click=move |_| {
+                                if let Some(prompt) = prompt.get() {
+                                    state.retry(prompt);
+                                }
+                            }
+                        >
+                            "Retry"
+                        </button>
+                    }
+                    .into_any(),
+                }}
+            </div>
+        </div>
+    }
+}
+
+/// Under a response whose stream died midway: what's there is kept, and Continue asks the
+/// backend to finish it from there.
+#[component]
+pub fn InterruptedNote(id: usize) -> impl IntoView {
+    let state = use_app_state();
+    view! {
+        <div class="interrupted-note" role="status">
+            <span>"Interrupted before the answer was finished"</span>
+            <button
+                class="dialog-button"
+                disabled=state.loading
+                on:click=move |_| state.continue_response(id)
+            >
+                "Continue"
+            </button>
+        </div>
+    }
+}
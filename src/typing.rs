@@ -0,0 +1,128 @@
+use std::time::Duration;
+
+use leptos::prelude::{
+    component, event_target_value, on_cleanup, set_interval_with_handle, view, Effect, Get,
+    GetUntracked, IntoView, Memo, RwSignal, Set, Update, With, WithUntracked,
+};
+use leptos::tachys::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::Settings;
+
+/// How often typed-out text advances
+const TICK: Duration = Duration::from_millis(50);
+/// Most text held back, in seconds of typing; beyond it typing speeds up to catch up
+const MAX_LAG_SECONDS: f64 = 1.5;
+
+/// Pace streamed text is shown at, whatever pace it arrives at
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TypingSpeed {
+    /// As soon as it arrives
+    #[default]
+    Instant,
+    Fast,
+    Normal,
+    Slow,
+}
+
+impl TypingSpeed {
+    const ALL: [TypingSpeed; 4] =
+        [TypingSpeed::Instant, TypingSpeed::Fast, TypingSpeed::Normal, TypingSpeed::Slow];
+
+    fn chars_per_second(self) -> Option<f64> {
+        match self {
+            TypingSpeed::Instant => None,
+            TypingSpeed::Fast => Some(240.0),
+            TypingSpeed::Normal => Some(120.0),
+            TypingSpeed::Slow => Some(60.0),
+        }
+    }
+
+    fn key(self) -> &'static str {
+        match self {
+            TypingSpeed::Instant => "instant",
+            TypingSpeed::Fast => "fast",
+            TypingSpeed::Normal => "normal",
+            TypingSpeed::Slow => "slow",
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            TypingSpeed::Instant => "Instant",
+            TypingSpeed::Fast => "Fast",
+            TypingSpeed::Normal => "Normal",
+            TypingSpeed::Slow => "Slow",
+        }
+    }
+}
+
+/// `text` typed out at `Settings::typing_speed`, so bursts from the backend read as an
+/// even flow. Text that arrives faster than the pace is held in a buffer, which is never
+/// more than `MAX_LAG_SECONDS` behind.
+pub fn use_typewriter(text: Memo<String>, settings: RwSignal<Settings>) -> Memo<String> {
+    // Characters of `text` shown so far
+    let typed = RwSignal::new(0usize);
+
+    let interval = set_interval_with_handle(
+        move || {
+            let total = text.with_untracked(|t| t.chars().count());
+            let shown = typed.get_untracked();
+            if shown == total {
+                return;
+            }
+            let rate = settings.with_untracked(|s| s.typing_speed.chars_per_second());
+            let next = match rate {
+                Some(rate) if shown < total => {
+                    let rate = rate.max((total - shown) as f64 / MAX_LAG_SECONDS);
+                    (shown + (rate * TICK.as_secs_f64()).ceil() as usize).min(total)
+                }
+                _ => total,
+            };
+            typed.set(next);
+        },
+        TICK,
+    );
+    on_cleanup(move || {
+        if let Ok(interval) = interval {
+            interval.clear();
+        }
+    });
+    // A new response starts typing from the beginning
+    Effect::new(move |_| {
+        if text.with(String::is_empty) {
+            typed.set(0);
+        }
+    });
+
+    Memo::new(move |_| {
+        let instant = settings.with(|s| s.typing_speed == TypingSpeed::Instant);
+        text.with(|t| if instant { t.clone() } else { t.chars().take(typed.get()).collect() })
+    })
+}
+
+/// Picker for `Settings::typing_speed`.
+#[component]
+pub fn TypingSpeedSelect(settings: RwSignal<Settings>) -> impl IntoView {
+    view! {
+        <select
+            class="panel-option"
+            on:change=move |ev| {
+                let key = event_target_value(&ev);
+                if let Some(speed) = TypingSpeed::ALL.into_iter().find(|s| s.key() == key) {
+                    settings.update(|s| s.typing_speed = speed);
+                }
+            }
+        >
+            {TypingSpeed::ALL.into_iter().map(|speed| view! {
+                <option
+                    value=speed.key()
+                    selected=move || settings.with(|s| s.typing_speed == speed)
+                >
+                    {speed.label()}
+                </option>
+            }).collect::<Vec<_>>()}
+        </select>
+    }
+}
@@ -0,0 +1,140 @@
+use std::time::Duration;
+
+use js_sys::Array;
+use leptos::html::Div;
+use leptos::prelude::{
+    component, on_cleanup, set_timeout, view, Effect, Get, IntoView, Memo,
+    NodeRef, ReadSignal, RwSignal, Set, Update,
+};
+use leptos::tachys::prelude::*;
+use send_wrapper::SendWrapper;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+use web_sys::{IntersectionObserver, IntersectionObserverEntry};
+
+use crate::state::use_app_state;
+use crate::{document_hidden, element, on_visibility_change, window_listener};
+
+const TITLE: &str = "Xve Chat";
+
+const FAVICON: &str = "data:image/svg+xml,<svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 32 32'>\
+<rect width='32' height='32' rx='7' fill='%23111'/>\
+<path d='M6 9l4 14 4-10 4 10 4-14' stroke='%23fff' stroke-width='2.5' fill='none' stroke-linejoin='round'/></svg>";
+
+const FAVICON_BADGE: &str = "data:image/svg+xml,<svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 32 32'>\
+<rect width='32' height='32' rx='7' fill='%23111'/>\
+<path d='M6 9l4 14 4-10 4 10 4-14' stroke='%23fff' stroke-width='2.5' fill='none' stroke-linejoin='round'/>\
+<circle cx='25' cy='7' r='6' fill='%23dc2626' stroke='%23111' stroke-width='1.5'/></svg>";
+
+fn set_favicon(href: &str) {
+    let Some(document) = web_sys::window().and_then(|w| w.document()) else {
+        return;
+    };
+    let link = match document.get_element_by_id("favicon") {
+        Some(link) => link,
+        None => {
+            let Ok(link) = document.create_element("link") else {
+                return;
+            };
+            let _ = link.set_attribute("id", "favicon");
+            let _ = link.set_attribute("rel", "icon");
+            if let Some(head) = document.head() {
+                let _ = head.append_child(&link);
+            }
+            link
+        }
+    };
+    let _ = link.set_attribute("href", href);
+}
+
+/// Count of responses that finished while the tab was hidden, reflected as a
+/// `(n)` title prefix (see `use_document_title`) and a badged favicon until the tab is
+/// visible again.
+pub fn use_unread() -> RwSignal<usize> {
+    let unread = RwSignal::new(0usize);
+    // The host page owns the title and favicon around a `<wxve-chat>`
+    if element::is_element() {
+        return unread;
+    }
+
+    on_visibility_change(move |hidden| {
+        if !hidden {
+            unread.set(0);
+        }
+    });
+    window_listener(leptos::ev::focus, move |_| unread.set(0));
+
+    Effect::new(move |_| {
+        set_favicon(if unread.get() == 0 { FAVICON } else { FAVICON_BADGE });
+    });
+
+    unread
+}
+
+/// Keep the document title on the open conversation's title and the unread count.
+pub fn use_document_title(unread: RwSignal<usize>, conversation: Memo<Option<String>>) {
+    if element::is_element() {
+        return;
+    }
+    Effect::new(move |_| {
+        let title = match conversation.get() {
+            Some(conversation) => format!("{conversation} · {TITLE}"),
+            None => TITLE.to_string(),
+        };
+        if let Some(document) = web_sys::window().and_then(|w| w.document()) {
+            match unread.get() {
+                0 => document.set_title(&title),
+                n => document.set_title(&format!("({n}) {title}")),
+            }
+        }
+    });
+}
+
+/// How long the "new responses" divider stays once it's been seen
+const SEEN_AFTER: Duration = Duration::from_secs(2);
+
+/// Record a finished response, if nobody is looking, and mark where the unseen ones start.
+pub fn mark_unread(unread: RwSignal<usize>, unseen: RwSignal<Option<usize>>, id: usize) {
+    if document_hidden() {
+        unread.update(|n| *n += 1);
+        unseen.update(|u| {
+            u.get_or_insert(id);
+        });
+    }
+}
+
+/// "New responses" above the first response that finished while the tab was hidden. It
+/// goes away shortly after it's been in view with the tab showing.
+#[component]
+pub fn NewResponsesDivider(page_visible: ReadSignal<bool>) -> impl IntoView {
+    let state = use_app_state();
+    let divider = NodeRef::<Div>::new();
+    let in_view = RwSignal::new(false);
+    Effect::new(move |_| {
+        let Some(divider) = divider.get() else {
+            return;
+        };
+        let callback = Closure::<dyn Fn(Array)>::new(move |entries: Array| {
+            if let Some(entry) = entries.iter().last() {
+                in_view.set(entry.unchecked_into::<IntersectionObserverEntry>().is_intersecting());
+            }
+        });
+        let Ok(observer) = IntersectionObserver::new(callback.as_ref().unchecked_ref()) else {
+            return;
+        };
+        observer.observe(&divider);
+        let observer = SendWrapper::new((observer, callback));
+        on_cleanup(move || observer.0.disconnect());
+    });
+    Effect::new(move |_| {
+        if in_view.get() && page_visible.get() {
+            set_timeout(move || state.unseen.set(None), SEEN_AFTER);
+        }
+    });
+
+    view! {
+        <div class="new-responses" node_ref=divider role="separator">
+            <span>"New responses"</span>
+        </div>
+    }
+}
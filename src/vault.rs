@@ -0,0 +1,383 @@
+use std::cell::Cell;
+
+use leptos::prelude::{
+    component, event_target_value, signal, view, AnyView, Callable, Callback, Get, GetUntracked,
+    IntoView, RwSignal, Set, Update, With,
+};
+use leptos::tachys::prelude::*;
+use leptos::task::spawn_local;
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{AesDerivedKeyParams, AesGcmParams, CryptoKey, Pbkdf2Params, SubtleCrypto};
+
+use crate::conversations::{save_conversations, Conversation, CONVERSATIONS_KEY};
+use crate::state::use_app_state;
+use crate::{dialog, load_json, local_storage, save_json, toast, workspace};
+
+const VAULT_KEY: &str = "wxve-chat:conversations-encrypted";
+
+/// OWASP's recommendation for PBKDF2-HMAC-SHA256
+const PBKDF2_ITERATIONS: u32 = 600_000;
+
+const MIN_PASSPHRASE: usize = 8;
+
+thread_local! {
+    // Saves encrypt asynchronously; only the latest may write so an older one can't land last
+    static SEAL_GENERATION: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Saved conversations as AES-GCM ciphertext, base64 encoded
+#[derive(Serialize, Deserialize)]
+struct Sealed {
+    salt: String,
+    iv: String,
+    data: String,
+}
+
+/// Whether saved conversations are encrypted, and the key while they're unlocked.
+#[derive(Clone)]
+pub enum Vault {
+    Off,
+    Locked,
+    Unlocked { key: CryptoKey, salt: Vec<u8> },
+}
+
+impl Vault {
+    pub fn load() -> Self {
+        let key = workspace::scoped(VAULT_KEY);
+        match local_storage().and_then(|s| s.get_item(&key).ok().flatten()) {
+            Some(_) => Vault::Locked,
+            None => Vault::Off,
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        matches!(self, Vault::Locked)
+    }
+}
+
+/// Persist `conversations` according to `vault`; nothing is written while locked
+/// so the encrypted copy is never overwritten with what little is in memory.
+pub fn save(vault: &Vault, conversations: &[Conversation]) {
+    match vault {
+        Vault::Off => save_conversations(conversations),
+        Vault::Locked => {}
+        Vault::Unlocked { key, salt } => {
+            let generation = SEAL_GENERATION.with(|g| {
+                g.set(g.get() + 1);
+                g.get()
+            });
+            let (key, salt) = (key.clone(), salt.clone());
+            let plaintext = serde_json::to_vec(conversations).unwrap_or_default();
+            spawn_local(async move {
+                match seal(&key, &salt, plaintext).await {
+                    Ok(sealed) if SEAL_GENERATION.with(Cell::get) == generation => {
+                        save_json(VAULT_KEY, &sealed);
+                    }
+                    Ok(_) => {}
+                    Err(e) => log::error!(target: "storage", "Encrypting conversations failed: {e}"),
+                }
+            });
+        }
+    }
+}
+
+/// Encrypt the saved conversations under a key derived from `passphrase`.
+async fn enable(passphrase: &str, conversations: &[Conversation]) -> Result<Vault, String> {
+    let mut salt = vec![0u8; 16];
+    crypto()?.get_random_values_with_u8_array(&mut salt).map_err(js_error)?;
+    let key = derive_key(passphrase, &salt).await?;
+    let sealed = seal(&key, &salt, serde_json::to_vec(conversations).map_err(|e| e.to_string())?).await?;
+    save_json(VAULT_KEY, &sealed);
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(&workspace::scoped(CONVERSATIONS_KEY));
+    }
+    Ok(Vault::Unlocked { key, salt })
+}
+
+/// Decrypt the saved conversations; a wrong passphrase fails authentication.
+async fn unlock(passphrase: &str) -> Result<(Vault, Vec<Conversation>), String> {
+    let sealed: Sealed = load_json(VAULT_KEY).ok_or("No encrypted conversations found")?;
+    let salt = from_base64(&sealed.salt)?;
+    let key = derive_key(passphrase, &salt).await?;
+    let params = AesGcmParams::new_with_u8_array(
+        "AES-GCM",
+        &js_sys::Uint8Array::from(from_base64(&sealed.iv)?.as_slice()),
+    );
+    let plaintext = subtle()?
+        .decrypt_with_object_and_u8_array(&params, &key, &from_base64(&sealed.data)?)
+        .map_err(js_error)?;
+    let plaintext = JsFuture::from(plaintext)
+        .await
+        .map_err(|_| "Wrong passphrase".to_string())?;
+    let plaintext = js_sys::Uint8Array::new(&plaintext).to_vec();
+    let conversations = serde_json::from_slice(&plaintext).map_err(|e| e.to_string())?;
+    Ok((Vault::Unlocked { key, salt }, conversations))
+}
+
+/// Store the conversations in plain text again.
+fn disable(conversations: &[Conversation]) {
+    save_conversations(conversations);
+    if let Some(storage) = local_storage() {
+        let _ = storage.remove_item(&workspace::scoped(VAULT_KEY));
+    }
+}
+
+async fn derive_key(passphrase: &str, salt: &[u8]) -> Result<CryptoKey, String> {
+    let subtle = subtle()?;
+    let material = subtle
+        .import_key_with_str(
+            "raw",
+            &js_sys::Uint8Array::from(passphrase.as_bytes()),
+            "PBKDF2",
+            false,
+            &usages(&["deriveKey"]),
+        )
+        .map_err(js_error)?;
+    let material: CryptoKey = JsFuture::from(material).await.map_err(js_error)?.unchecked_into();
+    let params = Pbkdf2Params::new(
+        "PBKDF2",
+        &JsValue::from_str("SHA-256"),
+        PBKDF2_ITERATIONS,
+        &js_sys::Uint8Array::from(salt),
+    );
+    let key = subtle
+        .derive_key_with_object_and_object(
+            &params,
+            &material,
+            &AesDerivedKeyParams::new("AES-GCM", 256),
+            false,
+            &usages(&["encrypt", "decrypt"]),
+        )
+        .map_err(js_error)?;
+    Ok(JsFuture::from(key).await.map_err(js_error)?.unchecked_into())
+}
+
+async fn seal(key: &CryptoKey, salt: &[u8], plaintext: Vec<u8>) -> Result<Sealed, String> {
+    // A fresh nonce for every write; AES-GCM must never reuse one under the same key
+    let mut iv = vec![0u8; 12];
+    crypto()?.get_random_values_with_u8_array(&mut iv).map_err(js_error)?;
+    let params = AesGcmParams::new_with_u8_array("AES-GCM", &js_sys::Uint8Array::from(iv.as_slice()));
+    let data = subtle()?
+        .encrypt_with_object_and_u8_array(&params, key, &plaintext)
+        .map_err(js_error)?;
+    let data = js_sys::Uint8Array::new(&JsFuture::from(data).await.map_err(js_error)?).to_vec();
+    Ok(Sealed { salt: to_base64(salt)?, iv: to_base64(&iv)?, data: to_base64(&data)? })
+}
+
+fn crypto() -> Result<web_sys::Crypto, String> {
+    web_sys::window().ok_or("no window")?.crypto().map_err(js_error)
+}
+
+fn subtle() -> Result<SubtleCrypto, String> {
+    Ok(crypto()?.subtle())
+}
+
+fn usages(names: &[&str]) -> JsValue {
+    names.iter().map(|name| JsValue::from_str(name)).collect::<js_sys::Array>().into()
+}
+
+fn js_error(e: JsValue) -> String {
+    format!("{e:?}")
+}
+
+fn to_base64(bytes: &[u8]) -> Result<String, String> {
+    let binary: String = bytes.iter().map(|&b| b as char).collect();
+    web_sys::window().ok_or("no window")?.btoa(&binary).map_err(js_error)
+}
+
+fn from_base64(encoded: &str) -> Result<Vec<u8>, String> {
+    let binary = web_sys::window().ok_or("no window")?.atob(encoded).map_err(js_error)?;
+    Ok(binary.chars().map(|c| c as u8).collect())
+}
+
+/// Passphrase prompt that decrypts the saved conversations and merges in any made while locked.
+#[component]
+fn UnlockForm(
+    vault: RwSignal<Vault>,
+    conversations: RwSignal<Vec<Conversation>>,
+    #[prop(optional, into)] on_unlocked: Option<Callback<()>>,
+) -> impl IntoView {
+    let (passphrase, set_passphrase) = signal(String::new());
+    let (busy, set_busy) = signal(false);
+    let (error, set_error) = signal(None::<String>);
+
+    let submit = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        set_busy.set(true);
+        set_error.set(None);
+        spawn_local(async move {
+            match unlock(&passphrase.get()).await {
+                Ok((unlocked, saved)) => {
+                    conversations.update(|current| {
+                        for conversation in saved {
+                            if !current.iter().any(|c| c.id == conversation.id) {
+                                current.push(conversation);
+                            }
+                        }
+                    });
+                    vault.set(unlocked);
+                    set_passphrase.set(String::new());
+                    if let Some(on_unlocked) = on_unlocked {
+                        on_unlocked.run(());
+                    }
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+            set_busy.set(false);
+        });
+    };
+
+    view! {
+        <form class="vault-form" on:submit=submit>
+            <input
+                type="password"
+                placeholder="Passphrase"
+                autocomplete="current-password"
+                autofocus=true
+                prop:value=passphrase
+                on:input=move |ev| set_passphrase.set(event_target_value(&ev))
+            />
+            <button class="dialog-button primary" type="submit" disabled=move || busy.get()>
+                {move || if busy.get() { "Unlocking…" } else { "Unlock" }}
+            </button>
+            {move || error.get().map(|e| view! { <p class="vault-error">{e}</p> })}
+        </form>
+    }
+}
+
+/// Encryption section of the settings dialog.
+#[component]
+pub fn EncryptionSettings(
+    vault: RwSignal<Vault>,
+    conversations: RwSignal<Vec<Conversation>>,
+    on_lock: Callback<()>,
+) -> impl IntoView {
+    let state = use_app_state();
+    let (passphrase, set_passphrase) = signal(String::new());
+    let (confirmation, set_confirmation) = signal(String::new());
+    let (busy, set_busy) = signal(false);
+    let (error, set_error) = signal(None::<String>);
+
+    let encrypt = move |ev: leptos::ev::SubmitEvent| {
+        ev.prevent_default();
+        let passphrase = passphrase.get();
+        if passphrase.chars().count() < MIN_PASSPHRASE {
+            set_error.set(Some(format!("Use at least {MIN_PASSPHRASE} characters")));
+            return;
+        }
+        if passphrase != confirmation.get() {
+            set_error.set(Some("Passphrases don't match".to_string()));
+            return;
+        }
+        set_busy.set(true);
+        set_error.set(None);
+        spawn_local(async move {
+            if let Err(e) = state.unarchive_all().await {
+                set_error.set(Some(e));
+                set_busy.set(false);
+                return;
+            }
+            match enable(&passphrase, &conversations.get_untracked()).await {
+                Ok(unlocked) => {
+                    vault.set(unlocked);
+                    set_passphrase.set(String::new());
+                    set_confirmation.set(String::new());
+                    toast::success("Saved conversations are now encrypted");
+                }
+                Err(e) => set_error.set(Some(e)),
+            }
+            set_busy.set(false);
+        });
+    };
+
+    let remove = move |_| {
+        spawn_local(async move {
+            if dialog::confirm("Store conversations unencrypted again? Anyone with access to this browser could read them.").await {
+                conversations.with(|c| disable(c));
+                vault.set(Vault::Off);
+            }
+        });
+    };
+
+    view! {
+        <section class="settings-section">
+            <h3>"Encryption"</h3>
+            {move || match vault.get() {
+                Vault::Off => view! {
+                    <p class="settings-hint">
+                        "Encrypt saved conversations with a passphrase. It can't be recovered if you forget it."
+                    </p>
+                    <form class="vault-form" on:submit=encrypt>
+                        <input
+                            type="password"
+                            placeholder="Passphrase"
+                            autocomplete="new-password"
+                            prop:value=passphrase
+                            on:input=move |ev| set_passphrase.set(event_target_value(&ev))
+                        />
+                        <input
+                            type="password"
+                            placeholder="Confirm passphrase"
+                            autocomplete="new-password"
+                            prop:value=confirmation
+                            on:input=move |ev| set_confirmation.set(event_target_value(&ev))
+                        />
+                        <button class="dialog-button primary" type="submit" disabled=move || busy.get()>
+                            {move || if busy.get() { "Encrypting…" } else { "Encrypt" }}
+                        </button>
+                        {move || error.get().map(|e| view! { <p class="vault-error">{e}</p> })}
+                    </form>
+                }.into_any(),
+                Vault::Locked => view! {
+                    <p class="settings-hint">"Saved conversations are locked."</p>
+                    <UnlockForm vault=vault conversations=conversations/>
+                }.into_any(),
+                Vault::Unlocked { .. } => view! {
+                    <p class="settings-hint">"Saved conversations are encrypted and unlocked."</p>
+                    <div class="dialog-actions">
+                        <button class="dialog-button" on:click=remove>"Remove encryption"</button>
+                        <button class="dialog-button primary" on:click=move |_| on_lock.run(())>"Lock now"</button>
+                    </div>
+                }.into_any(),
+            }}
+        </section>
+    }
+}
+
+struct UnlockDialog {
+    vault: RwSignal<Vault>,
+    conversations: RwSignal<Vec<Conversation>>,
+}
+
+impl dialog::Dialog for UnlockDialog {
+    fn title(&self) -> String {
+        "Unlock conversations".to_string()
+    }
+
+    fn body(&self, close: Callback<()>) -> AnyView {
+        view! { <UnlockForm vault=self.vault conversations=self.conversations on_unlocked=close/> }
+            .into_any()
+    }
+}
+
+/// Banner shown while saved conversations are locked.
+#[component]
+pub fn LockedBanner(vault: RwSignal<Vault>, conversations: RwSignal<Vec<Conversation>>) -> impl IntoView {
+    move || {
+        vault.with(Vault::is_locked).then(|| view! {
+            <div class="banner info" role="status">
+                <span class="banner-text">"Your saved conversations are encrypted and locked."</span>
+                <button
+                    class="dialog-button"
+                    on:click=move |_| {
+                        dialog::open(UnlockDialog { vault, conversations });
+                    }
+                >
+                    "Unlock"
+                </button>
+            </div>
+        })
+    }
+}
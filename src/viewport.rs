@@ -0,0 +1,50 @@
+use leptos::prelude::{on_cleanup, GetUntracked, ReadSignal, RwSignal, Set};
+use send_wrapper::SendWrapper;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::JsCast;
+
+/// How much of the bottom of the layout viewport the on-screen keyboard covers, in px,
+/// until the current owner is cleaned up.
+///
+/// Android with `interactive-widget=resizes-content` shrinks the layout viewport itself,
+/// so this stays 0 there; iOS only shrinks the visual viewport, leaving fixed elements
+/// like the composer behind the keyboard unless they're moved up by this much.
+pub fn use_keyboard_inset() -> ReadSignal<f64> {
+    let inset = RwSignal::new(0.0);
+    let Some(window) = web_sys::window() else {
+        return inset.read_only();
+    };
+    let Some(viewport) = window.visual_viewport() else {
+        return inset.read_only();
+    };
+
+    let update = {
+        let viewport = viewport.clone();
+        move || {
+            // Zooming in shrinks the visual viewport too; that's not a keyboard
+            let zoomed = viewport.scale() > 1.01;
+            let layout = window.inner_height().ok().and_then(|h| h.as_f64()).unwrap_or(0.0);
+            let covered = if zoomed {
+                0.0
+            } else {
+                (layout - viewport.height() - viewport.offset_top()).max(0.0).round()
+            };
+            if inset.get_untracked() != covered {
+                inset.set(covered);
+            }
+        }
+    };
+    let callback = Closure::<dyn Fn()>::new(update);
+    for event in ["resize", "scroll"] {
+        let _ = viewport.add_event_listener_with_callback(event, callback.as_ref().unchecked_ref());
+    }
+    let listener = SendWrapper::new((viewport, callback));
+    on_cleanup(move || {
+        let (viewport, callback) = listener.take();
+        for event in ["resize", "scroll"] {
+            let _ = viewport
+                .remove_event_listener_with_callback(event, callback.as_ref().unchecked_ref());
+        }
+    });
+    inset.read_only()
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use leptos::prelude::{
+    component, event_target_checked, event_target_value, on_cleanup, set_interval_with_handle,
+    signal, view, Callable, Callback, Children, Effect, For, Get, GetUntracked, IntoView, Memo,
+    RwSignal, Set, Track, Update, With,
+};
+use leptos::tachys::prelude::*;
+use leptos::task::spawn_local;
+
+use crate::currency::{CurrencySelect, FxRates, Price};
+use crate::{fetch_quotes, toggle_panel, Panel, Quote, Settings};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Uppercase a user-entered ticker, rejecting anything that can't be a symbol.
+pub fn normalize_symbol(raw: &str) -> Option<String> {
+    let symbol = raw.trim().to_uppercase();
+    let valid = !symbol.is_empty()
+        && symbol.len() <= 10
+        && symbol.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '^'));
+    valid.then_some(symbol)
+}
+
+pub fn format_change(change_percent: f64) -> (String, &'static str) {
+    let class = if change_percent >= 0.0 { "change up" } else { "change down" };
+    (format!("{change_percent:+.2}%"), class)
+}
+
+/// Poll `/quotes` for `symbols` into the shared quote map for as long as the caller lives.
+pub fn poll_quotes(symbols: Memo<Vec<String>>, quotes: RwSignal<HashMap<String, Quote>>) {
+    let refresh = move || {
+        let symbols = symbols.get_untracked();
+        if symbols.is_empty() {
+            return;
+        }
+        spawn_local(async move {
+            match fetch_quotes(&symbols).await {
+                Ok(fetched) => quotes.update(|q| {
+                    for quote in fetched {
+                        q.insert(quote.symbol.clone(), quote);
+                    }
+                }),
+                Err(e) => log::error!(target: "transport", "Quote fetch failed: {e}"),
+            }
+        });
+    };
+
+    // Refetch immediately whenever the list changes, then poll
+    Effect::new(move |_| {
+        symbols.track();
+        refresh();
+    });
+    if let Ok(handle) = set_interval_with_handle(refresh, POLL_INTERVAL) {
+        on_cleanup(move || handle.clear());
+    }
+}
+
+#[component]
+pub fn Watchlist(
+    settings: RwSignal<Settings>,
+    quotes: RwSignal<HashMap<String, Quote>>,
+    rates: RwSignal<FxRates>,
+    open_panel: RwSignal<Option<Panel>>,
+    on_select: Callback<String>,
+    on_details: Callback<String>,
+    /// Extra widgets shown beneath the symbol list
+    children: Children,
+) -> impl IntoView {
+    let (draft, set_draft) = signal(String::new());
+    let symbols = Memo::new(move |_| settings.with(|s| s.watchlist.clone()));
+
+    poll_quotes(symbols, quotes);
+
+    let add = move || {
+        if let Some(symbol) = normalize_symbol(&draft.get()) {
+            settings.update(|s| {
+                if !s.watchlist.contains(&symbol) {
+                    s.watchlist.push(symbol);
+                }
+            });
+            set_draft.set(String::new());
+        }
+    };
+
+    let remove = move |symbol: String| {
+        settings.update(|s| s.watchlist.retain(|w| *w != symbol));
+        quotes.update(|q| {
+            q.remove(&symbol);
+        });
+    };
+
+    view! {
+        <button
+            class="icon-btn watchlist-toggle"
+            title="Watchlist"
+            on:click=move |_| toggle_panel(open_panel, Panel::Watchlist)
+        >
+            "☰"
+        </button>
+        <aside class="panel watchlist" class:open=move || open_panel.get() == Some(Panel::Watchlist)>
+            <div class="panel-header">
+                "Watchlist"
+                <label class="panel-option">
+                    <input
+                        type="checkbox"
+                        prop:checked=move || settings.with(|s| s.show_ticker)
+                        on:change=move |ev| {
+                            let checked = event_target_checked(&ev);
+                            settings.update(|s| s.show_ticker = checked);
+                        }
+                    />
+                    "Ticker"
+                </label>
+                <CurrencySelect settings=settings/>
+            </div>
+            <form
+                class="panel-form"
+                on:submit=move |ev| {
+                    ev.prevent_default();
+                    add();
+                }
+            >
+                <input
+                    type="text"
+                    placeholder="Add symbol"
+                    prop:value=move || draft.get()
+                    on:input=move |ev| set_draft.set(event_target_value(&ev))
+                />
+            </form>
+            <ul class="panel-items">
+                <For
+                    each=move || symbols.get()
+                    key=|symbol| symbol.clone()
+                    children=move |symbol| {
+                        let quote_symbol = symbol.clone();
+                        let quote = move || quotes.with(|q| q.get(&quote_symbol).cloned());
+                        let select_symbol = symbol.clone();
+                        let details_symbol = symbol.clone();
+                        let remove_symbol = symbol.clone();
+                        view! {
+                            <li class="panel-item">
+                                <button
+                                    class="watchlist-symbol"
+                                    on:click=move |_| on_select.run(select_symbol.clone())
+                                >
+                                    {symbol}
+                                </button>
+                                {move || match quote() {
+                                    Some(quote) => {
+                                        let (change, class) = format_change(quote.change_percent);
+                                        view! {
+                                            <Price
+                                                amount=quote.price
+                                                currency=quote.currency
+                                                settings=settings
+                                                rates=rates
+                                            />
+                                            <span class=class>{change}</span>
+                                        }
+                                        .into_any()
+                                    }
+                                    None => view! { <span class="price">"—"</span> }.into_any(),
+                                }}
+                                <button
+                                    class="watchlist-details"
+                                    title="Details"
+                                    on:click=move |_| on_details.run(details_symbol.clone())
+                                >
+                                    "ⓘ"
+                                </button>
+                                <button
+                                    class="panel-remove"
+                                    title="Remove"
+                                    on:click=move |_| remove(remove_symbol.clone())
+                                >
+                                    "×"
+                                </button>
+                            </li>
+                        }
+                    }
+                />
+            </ul>
+            {children()}
+        </aside>
+    }
+}
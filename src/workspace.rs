@@ -0,0 +1,246 @@
+use leptos::prelude::{
+    component, event_target_value, view, AnyView, Callable, Callback, For, Get, IntoView,
+    RwSignal, Set,
+};
+use leptos::tachys::prelude::*;
+use leptos::task::spawn_local;
+use serde::{Deserialize, Serialize};
+
+use crate::account::STORAGE_PREFIX;
+use crate::conversations::{Conversation, CONVERSATIONS_KEY};
+use crate::dialog::{self, Dialog};
+use crate::state::use_app_state;
+use crate::{history, load_json, local_storage, new_id, save_json, toast};
+
+/// Which workspaces there are and which is open; the one key every workspace shares
+const WORKSPACES_KEY: &str = "wxve-chat:workspaces";
+
+/// The workspace everything saved before workspaces existed belongs to. Its keys have no
+/// workspace part, so that data stays where it was.
+const DEFAULT_ID: &str = "default";
+
+/// A separate set of conversations, watchlist, settings and everything else the app saves
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Workspace {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Workspaces {
+    active: String,
+    list: Vec<Workspace>,
+}
+
+impl Default for Workspaces {
+    fn default() -> Self {
+        Workspaces {
+            active: DEFAULT_ID.to_string(),
+            list: vec![Workspace { id: DEFAULT_ID.to_string(), name: "Personal".to_string() }],
+        }
+    }
+}
+
+fn load() -> Workspaces {
+    load_json(WORKSPACES_KEY).unwrap_or_default()
+}
+
+/// `key` within the open workspace: `wxve-chat:settings` is `wxve-chat:<id>/settings` in
+/// any workspace but the default one. `load_json` and `save_json` go through this, so
+/// modules name their keys as before and never see each other's workspaces.
+pub fn scoped(key: &str) -> String {
+    if key == WORKSPACES_KEY {
+        return key.to_string();
+    }
+    let active = load().active;
+    match key.strip_prefix(STORAGE_PREFIX) {
+        Some(name) if active != DEFAULT_ID => format!("{STORAGE_PREFIX}{active}/{name}"),
+        _ => key.to_string(),
+    }
+}
+
+/// Whether the default workspace is open, the only one whose settings follow the user
+/// through `/profile`.
+pub fn is_default() -> bool {
+    load().active == DEFAULT_ID
+}
+
+/// Open workspace `id`. Everything is loaded from storage at startup, so the page reloads.
+fn switch(id: &str) {
+    let mut workspaces = load();
+    workspaces.active = id.to_string();
+    save_json(WORKSPACES_KEY, &workspaces);
+    if let Some(window) = web_sys::window() {
+        let _ = window.location().reload();
+    }
+}
+
+/// Add a workspace called `name` and open it.
+fn create(name: &str) {
+    let name = name.trim();
+    if name.is_empty() {
+        return;
+    }
+    let mut workspaces = load();
+    let id = new_id();
+    workspaces.list.push(Workspace { id: id.clone(), name: name.to_string() });
+    save_json(WORKSPACES_KEY, &workspaces);
+    switch(&id);
+}
+
+/// Forget workspace `id` and everything saved in it. Neither the open workspace nor the
+/// default one can be deleted.
+fn delete(id: &str) {
+    let mut workspaces = load();
+    if id == DEFAULT_ID || id == workspaces.active {
+        return;
+    }
+    workspaces.list.retain(|w| w.id != id);
+    save_json(WORKSPACES_KEY, &workspaces);
+    let Some(storage) = local_storage() else {
+        return;
+    };
+    let prefix = format!("{STORAGE_PREFIX}{id}/");
+    // Their archived messages are in IndexedDB, under the ids of their conversations. An
+    // encrypted workspace can't be read, but its archive went when the vault was turned on.
+    let conversations_key = CONVERSATIONS_KEY.replacen(STORAGE_PREFIX, &prefix, 1);
+    let saved: Vec<Conversation> = storage
+        .get_item(&conversations_key)
+        .ok()
+        .flatten()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default();
+    for conversation in saved.iter().filter(|c| c.archived > 0) {
+        history::delete(&conversation.id);
+    }
+    let keys: Vec<String> = (0..storage.length().unwrap_or(0))
+        .filter_map(|i| storage.key(i).ok().flatten())
+        .filter(|key| key.starts_with(&prefix))
+        .collect();
+    for key in keys {
+        let _ = storage.remove_item(&key);
+    }
+}
+
+/// Create, open and delete workspaces.
+struct WorkspacesDialog {
+    workspaces: RwSignal<Vec<Workspace>>,
+}
+
+impl Dialog for WorkspacesDialog {
+    fn title(&self) -> String {
+        "Workspaces".to_string()
+    }
+
+    fn body(&self, close: Callback<()>) -> AnyView {
+        let workspaces = self.workspaces;
+        let active = load().active;
+        let draft = RwSignal::new(String::new());
+        let remove = move |workspace: Workspace| {
+            spawn_local(async move {
+                let question = format!(
+                    "Delete the workspace \"{}\" with all its conversations and settings?",
+                    workspace.name,
+                );
+                if dialog::confirm(question).await {
+                    delete(&workspace.id);
+                    workspaces.set(load().list);
+                    toast::info(format!("Deleted {}", workspace.name));
+                }
+            });
+        };
+
+        view! {
+            <p class="settings-hint">
+                "Each workspace keeps its own conversations, watchlist and settings on this device."
+            </p>
+            <ul class="panel-items">
+                <For
+                    each=move || workspaces.get()
+                    key=|w| w.id.clone()
+                    children=move |workspace| {
+                        let open = workspace.id == active;
+                        let removable = !open && workspace.id != DEFAULT_ID;
+                        let id = workspace.id.clone();
+                        view! {
+                            <li class="panel-item">
+                                <span>{workspace.name.clone()}</span>
+                                {if open {
+                                    view! { <span class="settings-hint">"Open"</span> }.into_any()
+                                } else {
+                                    view! {
+                                        <button class="dialog-button" on:click=move |_| switch(&id)>
+                                            "Open"
+                                        </button>
+                                    }
+                                    .into_any()
+                                }}
+                                {removable.then(|| view! {
+                                    <button
+                                        class="panel-remove"
+                                        title="Delete workspace"
+                                        on:click=move |_| remove(workspace.clone())
+                                    >
+                                        "×"
+                                    </button>
+                                })}
+                            </li>
+                        }
+                    }
+                />
+            </ul>
+            <form
+                class="panel-form"
+                on:submit=move |ev| {
+                    ev.prevent_default();
+                    create(&draft.get());
+                }
+            >
+                <input
+                    type="text"
+                    placeholder="New workspace, e.g. fund research"
+                    prop:value=draft
+                    on:input=move |ev| draft.set(event_target_value(&ev))
+                />
+                <button type="submit">"Create"</button>
+            </form>
+            <div class="dialog-actions">
+                <button class="dialog-button" on:click=move |_| close.run(())>"Done"</button>
+            </div>
+        }
+        .into_any()
+    }
+}
+
+/// Workspace picker at the top of the conversations panel. Switching waits for a response
+/// being streamed to end.
+#[component]
+pub fn WorkspaceSelect() -> impl IntoView {
+    let state = use_app_state();
+    let Workspaces { active, list } = load();
+    let workspaces = RwSignal::new(list);
+
+    view! {
+        <div class="workspace-select">
+            <select
+                title="Workspace"
+                disabled=state.loading
+                on:change=move |ev| switch(&event_target_value(&ev))
+            >
+                {move || workspaces.get().into_iter().map(|w| {
+                    let selected = w.id == active;
+                    view! { <option value=w.id selected=selected>{w.name}</option> }
+                }).collect::<Vec<_>>()}
+            </select>
+            <button
+                class="panel-option"
+                on:click=move |_| {
+                    workspaces.set(load().list);
+                    dialog::open(WorkspacesDialog { workspaces });
+                }
+            >
+                "Manage"
+            </button>
+        </div>
+    }
+}